@@ -1,4 +1,4 @@
-use std::{env, process, sync::Arc};
+use std::{env, process, sync::Arc, time::Duration};
 
 use clap::Parser;
 use ream::cli::{
@@ -12,20 +12,27 @@ use ream_checkpoint_sync::initialize_db_from_checkpoint;
 use ream_consensus::constants::set_genesis_validator_root;
 use ream_executor::ReamExecutor;
 use ream_manager::service::ManagerService;
-use ream_network_spec::networks::set_network_spec;
-use ream_operation_pool::OperationPool;
-use ream_rpc::{config::RpcServerConfig, start_server};
+use ream_network_spec::networks::{network_spec, set_network_spec};
+use ream_operation_pool::{DEFAULT_MAX_ATTESTATIONS, OperationPool};
+use ream_rpc::{
+    config::RpcServerConfig, handlers::events::EVENT_CHANNEL_CAPACITY, run_server,
+    shutdown::DEFAULT_SHUTDOWN_TIMEOUT,
+};
 use ream_storage::{
     db::{ReamDB, reset_db},
     dir::setup_data_dir,
-    tables::Table,
 };
 use ream_validator::validator::ValidatorService;
-use tracing::info;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
 use tracing_subscriber::EnvFilter;
 
 pub const APP_NAME: &str = "ream";
 
+/// How long the node waits on shutdown for its tasks to wind down. Longer than the time the HTTP
+/// server gives in-flight requests, so the server always gets to finish draining them.
+const SHUTDOWN_TIMEOUT: Duration = DEFAULT_SHUTDOWN_TIMEOUT.saturating_add(Duration::from_secs(5));
+
 fn main() {
     // Set the default log level to `info` if not set
     let rust_log = env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default();
@@ -62,7 +69,7 @@ fn main() {
         executor_clone.shutdown_signal();
     });
 
-    executor_clone.shutdown_runtime();
+    executor_clone.shutdown_runtime(SHUTDOWN_TIMEOUT);
 
     process::exit(0);
 }
@@ -93,21 +100,25 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor) {
 
     info!("Database Initialization completed");
 
-    let oldest_root = ream_db
-        .slot_index_provider()
-        .get_oldest_root()
-        .expect("Failed to access slot index provider")
-        .expect("No oldest root found");
+    let network = network_spec();
+    if network.genesis_validators_root().is_none() {
+        warn!(
+            "No known genesis_validators_root for {:?}, the stored genesis state is not checked against the network",
+            network.network
+        );
+    }
     set_genesis_validator_root(
         ream_db
-            .beacon_state_provider()
-            .get(oldest_root)
-            .expect("Failed to access beacon state provider")
-            .expect("No beacon state found")
-            .genesis_validators_root,
+            .validate_genesis(network.genesis_validators_root(), network.min_genesis_time)
+            .expect("Stored genesis state does not match the network config"),
     );
 
-    let operation_pool = Arc::new(OperationPool::default());
+    let operation_pool = Arc::new(OperationPool::new(
+        DEFAULT_MAX_ATTESTATIONS,
+        network_spec().attestation_propagation_slot_range,
+    ));
+
+    let (event_sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
 
     let server_config = RpcServerConfig::new(
         config.http_address,
@@ -121,6 +132,7 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor) {
         ream_db.clone(),
         ream_dir,
         operation_pool.clone(),
+        event_sender.clone(),
     )
     .await
     .expect("Failed to create manager service");
@@ -133,13 +145,17 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor) {
         network_manager.start().await;
     });
 
-    let http_future = executor.spawn(async move {
-        start_server(
+    let http_future = executor.spawn_graceful(|mut shutdown| async move {
+        run_server(
             server_config,
             ream_db,
             network_state,
             operation_pool,
             execution_engine,
+            event_sender,
+            async move {
+                let _ = shutdown.recv().await;
+            },
         )
         .await
     });
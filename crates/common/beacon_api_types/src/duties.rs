@@ -21,6 +21,8 @@ pub struct AttesterDuty {
     #[serde(with = "serde_utils::quoted_u64")]
     pub committee_index: u64,
     #[serde(with = "serde_utils::quoted_u64")]
+    pub committee_length: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
     pub committees_at_slot: u64,
     #[serde(with = "serde_utils::quoted_u64")]
     pub validator_committee_index: u64,
@@ -34,5 +36,6 @@ pub struct SyncCommitteeDuty {
     pub public_key: PublicKey,
     #[serde(with = "serde_utils::quoted_u64")]
     pub validator_index: u64,
+    #[serde(with = "serde_utils::quoted_u64_vec")]
     pub validator_sync_committee_indices: Vec<u64>,
 }
@@ -1,6 +1,24 @@
 use actix_web::{HttpResponse, ResponseError, http::StatusCode};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// The JSON error body defined by the beacon API
+///
+/// # Example
+/// {
+///  "code": 404,
+///  "message": "Api Endpoint Not Found: ...",
+///  "request_id": "5f0c6b0e-..."
+/// }
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ErrorResponse {
+    pub code: u16,
+    pub message: String,
+    /// Id the server assigned to the failed request, also recorded in its logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
 #[derive(Error, Debug)]
 pub enum ApiError {
     #[error("Unauthorized")]
@@ -23,11 +41,37 @@ pub enum ApiError {
 
     #[error("Too many validator IDs in request")]
     TooManyValidatorsIds,
+
+    #[error("Node is syncing: {0}")]
+    NotSynced(String),
+
+    #[error("Server is shutting down")]
+    ShuttingDown,
+
+    #[error("Payload Too Large: {0}")]
+    PayloadTooLarge(String),
+}
+
+impl ApiError {
+    /// The error response of a request, with the id the server assigned to the request so the
+    /// response can be matched with the server logs.
+    pub fn error_response_with_request_id(&self, request_id: String) -> HttpResponse {
+        self.build_error_response(Some(request_id))
+    }
+
+    fn build_error_response(&self, request_id: Option<String>) -> HttpResponse {
+        let status_code = self.status_code();
+        HttpResponse::build(status_code).json(ErrorResponse {
+            code: status_code.as_u16(),
+            message: self.to_string(),
+            request_id,
+        })
+    }
 }
 
 impl ResponseError for ApiError {
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::build(self.status_code()).body(self.to_string())
+        self.build_error_response(None)
     }
 
     fn status_code(&self) -> StatusCode {
@@ -39,6 +83,9 @@ impl ResponseError for ApiError {
             ApiError::InvalidParameter(_) => StatusCode::BAD_REQUEST,
             ApiError::ValidatorNotFound(_) => StatusCode::NOT_FOUND,
             ApiError::TooManyValidatorsIds => StatusCode::URI_TOO_LONG,
+            ApiError::NotSynced(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::ShuttingDown => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
         }
     }
 }
@@ -75,3 +122,77 @@ pub enum ValidatorError {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::MessageBody;
+
+    use super::*;
+
+    fn error_body(error: ApiError) -> ErrorResponse {
+        let response = error.error_response();
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        let body = response.into_body().try_into_bytes().unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[test]
+    fn test_not_found_error_body() {
+        assert_eq!(
+            error_body(ApiError::NotFound(
+                "Failed to find `beacon block`".to_string()
+            )),
+            ErrorResponse {
+                code: 404,
+                message: "Api Endpoint Not Found: Failed to find `beacon block`".to_string(),
+                request_id: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_error_body_code_matches_status() {
+        for error in [
+            ApiError::Unauthorized,
+            ApiError::BadRequest("bad".to_string()),
+            ApiError::InternalError("internal".to_string()),
+            ApiError::InvalidParameter("parameter".to_string()),
+            ApiError::ValidatorNotFound("validator".to_string()),
+            ApiError::TooManyValidatorsIds,
+            ApiError::NotSynced("syncing".to_string()),
+            ApiError::ShuttingDown,
+            ApiError::PayloadTooLarge("large".to_string()),
+        ] {
+            let code = error.status_code().as_u16();
+            let message = error.to_string();
+            assert_eq!(
+                error_body(error),
+                ErrorResponse {
+                    code,
+                    message,
+                    request_id: None,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_error_body_with_request_id() {
+        let response = ApiError::InternalError("internal".to_string())
+            .error_response_with_request_id("request-1".to_string());
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let body = response.into_body().try_into_bytes().unwrap();
+        assert_eq!(
+            serde_json::from_slice::<ErrorResponse>(&body).unwrap(),
+            ErrorResponse {
+                code: 500,
+                message: "Internal Server Error: internal".to_string(),
+                request_id: Some("request-1".to_string()),
+            }
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+
+/// Topics of the beacon node event stream, as passed in the `topics` query parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventTopic {
+    Head,
+    ChainReorg,
+}
+
+/// An event of the beacon node event stream, serialized as `{"event": topic, "data": event}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum BeaconEvent {
+    Head(HeadEvent),
+    ChainReorg(ChainReorgEvent),
+}
+
+impl BeaconEvent {
+    pub fn topic(&self) -> EventTopic {
+        match self {
+            BeaconEvent::Head(_) => EventTopic::Head,
+            BeaconEvent::ChainReorg(_) => EventTopic::ChainReorg,
+        }
+    }
+}
+
+/// Emitted when the fork choice head changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeadEvent {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+    pub block: B256,
+    pub state: B256,
+    pub epoch_transition: bool,
+    pub previous_duty_dependent_root: B256,
+    pub current_duty_dependent_root: B256,
+    pub execution_optimistic: bool,
+}
+
+/// Emitted when the fork choice head switches to a block that does not descend from the previous
+/// head. `depth` is the number of blocks of the old head's chain after the common ancestor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainReorgEvent {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub depth: u64,
+    pub old_head_block: B256,
+    pub new_head_block: B256,
+    pub old_head_state: B256,
+    pub new_head_state: B256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub epoch: u64,
+    pub execution_optimistic: bool,
+}
@@ -4,6 +4,7 @@ use std::{fmt::Display, str::FromStr};
 use alloy_primitives::{B256, hex};
 use ream_bls::PublicKey;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ID {
@@ -25,34 +26,46 @@ impl Serialize for ID {
     }
 }
 
-impl<'de> Deserialize<'de> for ID {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
+/// A `block_id` or `state_id` that is none of the formats the beacon API accepts.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error(
+    "Invalid ID {0:?}, expected one of head, genesis, finalized, justified, a slot number or a 0x-prefixed 32 byte hex root"
+)]
+pub struct InvalidIDError(pub String);
+
+impl FromStr for ID {
+    type Err = InvalidIDError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "finalized" => Ok(ID::Finalized),
             "genesis" => Ok(ID::Genesis),
             "head" => Ok(ID::Head),
             "justified" => Ok(ID::Justified),
             _ => {
-                if s.starts_with("0x") {
-                    B256::from_str(&s)
-                        .map(ID::Root)
-                        .map_err(|_| serde::de::Error::custom(format!("Invalid hex root: {s}")))
+                let id = if s.starts_with("0x") {
+                    B256::from_str(s).ok().map(ID::Root)
                 } else if s.chars().all(|c| c.is_ascii_digit()) {
-                    s.parse::<u64>()
-                        .map(ID::Slot)
-                        .map_err(|_| serde::de::Error::custom(format!("Invalid slot number: {s}")))
+                    s.parse::<u64>().ok().map(ID::Slot)
                 } else {
-                    Err(serde::de::Error::custom(format!("Invalid state ID: {s}")))
-                }
+                    None
+                };
+                id.ok_or_else(|| InvalidIDError(s.to_string()))
             }
         }
     }
 }
 
+impl<'de> Deserialize<'de> for ID {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 impl fmt::Display for ID {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -112,3 +125,33 @@ impl Display for ValidatorID {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_id() {
+        assert_eq!("head".parse(), Ok(ID::Head));
+        assert_eq!("Finalized".parse(), Ok(ID::Finalized));
+        assert_eq!("12".parse(), Ok(ID::Slot(12)));
+        assert_eq!(
+            format!("0x{}", "ab".repeat(32)).parse(),
+            Ok(ID::Root(B256::repeat_byte(0xab)))
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_id() {
+        for id in [
+            "0xnothex",
+            "0xabcd",
+            "latest",
+            "",
+            "-1",
+            "18446744073709551616",
+        ] {
+            assert_eq!(id.parse::<ID>(), Err(InvalidIDError(id.to_string())));
+        }
+    }
+}
@@ -2,8 +2,10 @@ pub mod block;
 pub mod committee;
 pub mod duties;
 pub mod error;
+pub mod events;
 pub mod id;
 pub mod query;
+pub mod quoted_i64;
 pub mod request;
 pub mod responses;
 pub mod sync;
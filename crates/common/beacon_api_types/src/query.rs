@@ -1,8 +1,8 @@
 use alloy_primitives::B256;
 use serde::{Deserialize, Serialize};
 
-use super::id::ValidatorID;
-use crate::validator::ValidatorStatus;
+use super::id::{ID, ValidatorID};
+use crate::{events::EventTopic, validator::ValidatorStatus};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EpochQuery {
@@ -14,11 +14,22 @@ pub struct SlotQuery {
     pub slot: Option<u64>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct StateIdQuery {
+    pub state_id: Option<ID>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct IndexQuery {
     pub index: Option<u64>,
 }
 
+#[derive(Default, Debug, Deserialize)]
+pub struct AttestationPoolQuery {
+    pub slot: Option<u64>,
+    pub committee_index: Option<u64>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RootQuery {
     pub root: Option<B256>,
@@ -34,11 +45,27 @@ pub struct IdQuery {
     pub id: Option<Vec<ValidatorID>>,
 }
 
+#[derive(Default, Debug, Deserialize)]
+pub struct PaginationQuery {
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
 #[derive(Default, Debug, Deserialize)]
 pub struct BlobSidecarQuery {
     pub indices: Option<Vec<u64>>,
 }
 
+#[derive(Default, Debug, Deserialize)]
+pub struct DedupQuery {
+    pub dedup: Option<bool>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+pub struct CountQuery {
+    pub count_only: Option<bool>,
+}
+
 #[derive(Default, Debug, Deserialize)]
 pub struct StatusQuery {
     pub status: Option<Vec<ValidatorStatus>>,
@@ -54,8 +81,22 @@ impl StatusQuery {
 
     pub fn contains_status(&self, status: &ValidatorStatus) -> bool {
         match &self.status {
-            Some(statuses) => statuses.contains(status),
+            Some(statuses) => statuses.iter().any(|filter| status.matches(filter)),
             None => true, // If no statuses specified, accept all
         }
     }
 }
+
+#[derive(Debug, Deserialize)]
+pub struct TopicsQuery {
+    pub topics: Option<Vec<EventTopic>>,
+}
+
+impl TopicsQuery {
+    pub fn contains_topic(&self, topic: EventTopic) -> bool {
+        match &self.topics {
+            Some(topics) => topics.contains(&topic),
+            None => true, // If no topics specified, accept all
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Serde helper for signed integers the beacon API sends as decimal strings, like rewards that
+//! turn negative when they are penalties. The signed counterpart of `serde_utils::quoted_u64`,
+//! deserialization also accepts a bare number.
+
+use std::fmt;
+
+use serde::{
+    Deserializer, Serializer,
+    de::{Error, Visitor},
+};
+
+pub fn serialize<S>(value: &i64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&value.to_string())
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(QuotedI64Visitor)
+}
+
+struct QuotedI64Visitor;
+
+impl Visitor<'_> for QuotedI64Visitor {
+    type Value = i64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a quoted or bare signed integer")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<i64, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_i64<E: Error>(self, value: i64) -> Result<i64, E> {
+        Ok(value)
+    }
+
+    fn visit_u64<E: Error>(self, value: u64) -> Result<i64, E> {
+        i64::try_from(value).map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+    struct Reward {
+        #[serde(with = "crate::quoted_i64")]
+        reward: i64,
+    }
+
+    #[test]
+    fn test_negative_reward_is_quoted() {
+        let penalty = Reward { reward: -123 };
+        let serialized = serde_json::to_value(&penalty).unwrap();
+        assert_eq!(serialized, json!({ "reward": "-123" }));
+        assert_eq!(
+            serde_json::from_value::<Reward>(serialized).unwrap(),
+            penalty
+        );
+    }
+
+    #[test]
+    fn test_positive_reward_is_quoted() {
+        let reward = Reward { reward: 123 };
+        let serialized = serde_json::to_value(&reward).unwrap();
+        assert_eq!(serialized, json!({ "reward": "123" }));
+        assert_eq!(
+            serde_json::from_value::<Reward>(serialized).unwrap(),
+            reward
+        );
+    }
+
+    #[test]
+    fn test_bare_and_invalid_rewards() {
+        assert_eq!(
+            serde_json::from_value::<Reward>(json!({ "reward": -5 })).unwrap(),
+            Reward { reward: -5 }
+        );
+        assert!(serde_json::from_value::<Reward>(json!({ "reward": "1.5" })).is_err());
+        assert!(serde_json::from_value::<Reward>(json!({ "reward": u64::MAX })).is_err());
+    }
+}
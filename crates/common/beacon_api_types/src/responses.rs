@@ -1,8 +1,11 @@
 use alloy_primitives::B256;
+use ream_consensus::checkpoint::Checkpoint;
 use serde::{Deserialize, Serialize};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 
+use crate::validator::ValidatorStatus;
+
 pub const ACCEPT_PRIORITY: &str = "application/octet-stream;q=1.0,application/json;q=0.9";
 pub const ETH_CONSENSUS_VERSION_HEADER: &str = "Eth-Consensus-Version";
 pub const EXECUTION_OPTIMISTIC: bool = false;
@@ -30,6 +33,54 @@ impl<T: Serialize> DataResponse<T> {
     }
 }
 
+/// An ApiResponse data struct that wraps data type used for json rpc responses, with the beacon
+/// API metadata fields attached as needed. Fields that were not set are omitted.
+///
+/// # Example
+/// {
+///     "version": "electra",
+///     "execution_optimistic" : bool,
+///     "finalized" : bool,
+///     "data" : T
+/// }
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiResponse<T> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_optimistic: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finalized: Option<bool>,
+    pub data: T,
+}
+
+impl<T: Serialize> ApiResponse<T> {
+    pub fn data(data: T) -> Self {
+        Self {
+            version: None,
+            execution_optimistic: None,
+            finalized: None,
+            data,
+        }
+    }
+
+    /// Attach the consensus fork name of ``data``.
+    pub fn with_version(mut self, fork: impl Into<String>) -> Self {
+        self.version = Some(fork.into());
+        self
+    }
+
+    pub fn with_optimistic(mut self, execution_optimistic: bool) -> Self {
+        self.execution_optimistic = Some(execution_optimistic);
+        self
+    }
+
+    pub fn with_finalized(mut self, finalized: bool) -> Self {
+        self.finalized = Some(finalized);
+        self
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct RootResponse {
     pub root: B256,
@@ -67,6 +118,11 @@ impl<T: Serialize> BeaconResponse<T> {
             finalized: FINALIZED,
         }
     }
+
+    pub fn with_finalized(mut self, finalized: bool) -> Self {
+        self.finalized = finalized;
+        self
+    }
 }
 
 /// A BeaconVersionedResponse data struct that can be used to wrap data type
@@ -98,6 +154,11 @@ impl<T: Serialize> BeaconVersionedResponse<T> {
             finalized: FINALIZED,
         }
     }
+
+    pub fn with_finalized(mut self, finalized: bool) -> Self {
+        self.finalized = finalized;
+        self
+    }
 }
 
 /// A DataVersionedResponse data struct that can be used to wrap data type
@@ -121,6 +182,12 @@ impl<T: Serialize> DataVersionedResponse<T> {
             data,
         }
     }
+
+    /// Attach the consensus fork name of ``data``.
+    pub fn with_version(mut self, fork: impl Into<String>) -> Self {
+        self.version = fork.into();
+        self
+    }
 }
 
 /// A DutiesResponse data struct that can be used to wrap duty data
@@ -176,6 +243,7 @@ impl<T: Serialize + Encode + Decode> SyncCommitteeDutiesResponse<T> {
 #[derive(Debug, Serialize)]
 pub struct BeaconHeadResponse {
     pub root: B256,
+    #[serde(with = "serde_utils::quoted_u64")]
     pub slot: u64,
     pub execution_optimistic: bool,
 }
@@ -189,3 +257,115 @@ impl BeaconHeadResponse {
         }
     }
 }
+
+/// A fork choice leaf with the weight of the branch it ends, as returned by
+/// `/eth/v1/debug/beacon/heads`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DebugHeadResponse {
+    pub root: B256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+    pub execution_optimistic: bool,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub weight: u64,
+}
+
+/// The fork choice leaves together with the checkpoints the store filters the block tree by.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DebugHeadsResponse {
+    pub justified_checkpoint: Checkpoint,
+    pub finalized_checkpoint: Checkpoint,
+    pub heads: Vec<DebugHeadResponse>,
+}
+
+/// Number of validators matching the filters of `/eth/v1/beacon/states/{state_id}/validators`,
+/// as returned with `count_only=true`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ValidatorCountResponse {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub count: u64,
+}
+
+/// Aggregate statistics over the validator registry of a state, as returned by
+/// `/eth/v1/debug/beacon/validator_stats`.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ValidatorStatsResponse {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub total_validators: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub active_validators: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub total_effective_balance: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub active_effective_balance: u64,
+    /// Average over the whole registry, rounded down
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub average_effective_balance: u64,
+    /// The fine-grained statuses held by at least one validator
+    pub statuses: Vec<ValidatorStatusCount>,
+    /// The effective balances held by at least one validator, lowest first
+    pub effective_balances: Vec<EffectiveBalanceBucket>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ValidatorStatusCount {
+    pub status: ValidatorStatus,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub count: u64,
+}
+
+/// Number of validators whose effective balance falls in the `EFFECTIVE_BALANCE_INCREMENT` wide
+/// bucket starting at `effective_balance`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EffectiveBalanceBucket {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub effective_balance: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub count: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_versioned_api_response() {
+        let response = ApiResponse::data(vec![1, 2])
+            .with_version(VERSION)
+            .with_optimistic(EXECUTION_OPTIMISTIC)
+            .with_finalized(true);
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "version": "electra",
+                "execution_optimistic": false,
+                "finalized": true,
+                "data": [1, 2]
+            })
+        );
+    }
+
+    #[test]
+    fn test_unversioned_api_response() {
+        assert_eq!(
+            serde_json::to_value(ApiResponse::data("0x01")).unwrap(),
+            json!({ "data": "0x01" })
+        );
+        assert_eq!(
+            serde_json::to_value(
+                ApiResponse::data("0x01")
+                    .with_optimistic(false)
+                    .with_finalized(false)
+            )
+            .unwrap(),
+            json!({
+                "execution_optimistic": false,
+                "finalized": false,
+                "data": "0x01"
+            })
+        );
+    }
+}
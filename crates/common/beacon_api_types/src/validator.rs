@@ -1,7 +1,7 @@
-use ream_consensus::validator::Validator;
+use ream_consensus::{constants::FAR_FUTURE_EPOCH, validator::Validator};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ValidatorStatus {
     Pending,
@@ -20,6 +20,54 @@ pub enum ValidatorStatus {
     Offline,
 }
 
+impl ValidatorStatus {
+    /// The fine-grained status of `validator` with `balance` at `epoch`, as defined by the beacon
+    /// API. Never one of the umbrella statuses `pending`, `active`, `exited` or `withdrawal`.
+    pub fn new(validator: &Validator, balance: u64, epoch: u64) -> Self {
+        if epoch < validator.activation_epoch {
+            if validator.activation_eligibility_epoch == FAR_FUTURE_EPOCH {
+                Self::PendingInitialized
+            } else {
+                Self::PendingQueued
+            }
+        } else if epoch < validator.exit_epoch {
+            if validator.exit_epoch == FAR_FUTURE_EPOCH {
+                Self::ActiveOngoing
+            } else if validator.slashed {
+                Self::ActiveSlashed
+            } else {
+                Self::ActiveExiting
+            }
+        } else if epoch < validator.withdrawable_epoch {
+            if validator.slashed {
+                Self::ExitedSlashed
+            } else {
+                Self::ExitedUnslashed
+            }
+        } else if balance != 0 {
+            Self::WithdrawalPossible
+        } else {
+            Self::WithdrawalDone
+        }
+    }
+
+    /// Whether a validator with this status is selected by the `filter` status of a request. The
+    /// umbrella statuses select all of their fine-grained members, e.g. `active` selects
+    /// `active_ongoing`, `active_exiting` and `active_slashed`.
+    pub fn matches(&self, filter: &ValidatorStatus) -> bool {
+        match filter {
+            Self::Pending => matches!(self, Self::PendingInitialized | Self::PendingQueued),
+            Self::Active => matches!(
+                self,
+                Self::ActiveOngoing | Self::ActiveExiting | Self::ActiveSlashed
+            ),
+            Self::Exited => matches!(self, Self::ExitedUnslashed | Self::ExitedSlashed),
+            Self::Withdrawal => matches!(self, Self::WithdrawalPossible | Self::WithdrawalDone),
+            _ => self == filter,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ValidatorData {
     #[serde(with = "serde_utils::quoted_u64")]
@@ -48,3 +96,166 @@ pub struct ValidatorBalance {
     #[serde(with = "serde_utils::quoted_u64")]
     pub balance: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_bls::PublicKey;
+
+    use super::*;
+
+    const FINE_GRAINED_STATUSES: [ValidatorStatus; 9] = [
+        ValidatorStatus::PendingInitialized,
+        ValidatorStatus::PendingQueued,
+        ValidatorStatus::ActiveOngoing,
+        ValidatorStatus::ActiveExiting,
+        ValidatorStatus::ActiveSlashed,
+        ValidatorStatus::ExitedUnslashed,
+        ValidatorStatus::ExitedSlashed,
+        ValidatorStatus::WithdrawalPossible,
+        ValidatorStatus::WithdrawalDone,
+    ];
+
+    fn matched_by(filter: ValidatorStatus) -> Vec<ValidatorStatus> {
+        FINE_GRAINED_STATUSES
+            .into_iter()
+            .filter(|status| status.matches(&filter))
+            .collect()
+    }
+
+    fn validator(
+        slashed: bool,
+        activation_eligibility_epoch: u64,
+        activation_epoch: u64,
+        exit_epoch: u64,
+        withdrawable_epoch: u64,
+    ) -> Validator {
+        Validator {
+            public_key: PublicKey::default(),
+            withdrawal_credentials: B256::ZERO,
+            effective_balance: 32_000_000_000,
+            slashed,
+            activation_eligibility_epoch,
+            activation_epoch,
+            exit_epoch,
+            withdrawable_epoch,
+        }
+    }
+
+    #[test]
+    fn test_umbrella_statuses_match_their_members() {
+        assert_eq!(
+            matched_by(ValidatorStatus::Pending),
+            [
+                ValidatorStatus::PendingInitialized,
+                ValidatorStatus::PendingQueued
+            ]
+        );
+        assert_eq!(
+            matched_by(ValidatorStatus::Active),
+            [
+                ValidatorStatus::ActiveOngoing,
+                ValidatorStatus::ActiveExiting,
+                ValidatorStatus::ActiveSlashed
+            ]
+        );
+        assert_eq!(
+            matched_by(ValidatorStatus::Exited),
+            [
+                ValidatorStatus::ExitedUnslashed,
+                ValidatorStatus::ExitedSlashed
+            ]
+        );
+        assert_eq!(
+            matched_by(ValidatorStatus::Withdrawal),
+            [
+                ValidatorStatus::WithdrawalPossible,
+                ValidatorStatus::WithdrawalDone
+            ]
+        );
+        assert!(matched_by(ValidatorStatus::Offline).is_empty());
+    }
+
+    #[test]
+    fn test_fine_grained_statuses_match_only_themselves() {
+        for filter in FINE_GRAINED_STATUSES {
+            assert_eq!(matched_by(filter), [filter]);
+        }
+    }
+
+    #[test]
+    fn test_status_deserializes_from_query_values() {
+        let statuses: Vec<ValidatorStatus> =
+            serde_json::from_str(r#"["active", "pending_queued", "withdrawal_done"]"#).unwrap();
+        assert_eq!(
+            statuses,
+            [
+                ValidatorStatus::Active,
+                ValidatorStatus::PendingQueued,
+                ValidatorStatus::WithdrawalDone
+            ]
+        );
+    }
+
+    #[test]
+    fn test_status_at_epoch() {
+        let balance = 32_000_000_000;
+        let pending = validator(
+            false,
+            FAR_FUTURE_EPOCH,
+            FAR_FUTURE_EPOCH,
+            FAR_FUTURE_EPOCH,
+            FAR_FUTURE_EPOCH,
+        );
+        assert_eq!(
+            ValidatorStatus::new(&pending, balance, 10),
+            ValidatorStatus::PendingInitialized
+        );
+
+        let queued = validator(
+            false,
+            5,
+            FAR_FUTURE_EPOCH,
+            FAR_FUTURE_EPOCH,
+            FAR_FUTURE_EPOCH,
+        );
+        assert_eq!(
+            ValidatorStatus::new(&queued, balance, 10),
+            ValidatorStatus::PendingQueued
+        );
+
+        let active = validator(false, 5, 10, FAR_FUTURE_EPOCH, FAR_FUTURE_EPOCH);
+        assert_eq!(
+            ValidatorStatus::new(&active, balance, 10),
+            ValidatorStatus::ActiveOngoing
+        );
+
+        let exiting = validator(false, 5, 10, 20, 276);
+        assert_eq!(
+            ValidatorStatus::new(&exiting, balance, 19),
+            ValidatorStatus::ActiveExiting
+        );
+        assert_eq!(
+            ValidatorStatus::new(&exiting, balance, 20),
+            ValidatorStatus::ExitedUnslashed
+        );
+        assert_eq!(
+            ValidatorStatus::new(&exiting, balance, 276),
+            ValidatorStatus::WithdrawalPossible
+        );
+        assert_eq!(
+            ValidatorStatus::new(&exiting, 0, 276),
+            ValidatorStatus::WithdrawalDone
+        );
+
+        let slashed = validator(true, 5, 10, 20, 8212);
+        assert_eq!(
+            ValidatorStatus::new(&slashed, balance, 15),
+            ValidatorStatus::ActiveSlashed
+        );
+        assert_eq!(
+            ValidatorStatus::new(&slashed, balance, 20),
+            ValidatorStatus::ExitedSlashed
+        );
+    }
+}
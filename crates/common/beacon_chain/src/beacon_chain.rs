@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
+use alloy_primitives::B256;
 use anyhow::bail;
+use ream_beacon_api_types::events::BeaconEvent;
 use ream_consensus::{
     attestation::Attestation, attester_slashing::AttesterSlashing,
     constants::genesis_validators_root, electra::beacon_block::SignedBeaconBlock,
@@ -17,13 +19,17 @@ use ream_storage::{
     db::ReamDB,
     tables::{Field, Table},
 };
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, broadcast};
 use tracing::warn;
 
 /// BeaconChain is the main struct which manages the nodes local beacon chain.
 pub struct BeaconChain {
     pub store: Mutex<Store>,
+    /// The head after the last block import, which the next import reorgs from.
+    head: Mutex<Option<B256>>,
     pub execution_engine: Option<ExecutionEngine>,
+    /// Feeds the events stream of the Beacon API.
+    pub event_sender: broadcast::Sender<BeaconEvent>,
 }
 
 impl BeaconChain {
@@ -32,16 +38,34 @@ impl BeaconChain {
         db: ReamDB,
         operation_pool: Arc<OperationPool>,
         execution_engine: Option<ExecutionEngine>,
+        event_sender: broadcast::Sender<BeaconEvent>,
     ) -> Self {
         Self {
             store: Mutex::new(Store::new(db, operation_pool)),
+            head: Mutex::new(None),
             execution_engine,
+            event_sender,
         }
     }
 
+    /// Imports `signed_block` and updates the head, emitting a `chain_reorg` event if the block
+    /// moved the head off its previous branch.
     pub async fn process_block(&self, signed_block: SignedBeaconBlock) -> anyhow::Result<()> {
         let mut store = self.store.lock().await;
+        let mut head = self.head.lock().await;
+        // Fork choice only runs for the first import, there is no head to reorg from before the
+        // first checkpoint is set
+        let old_head = match *head {
+            Some(head) => Some(head),
+            None => store.get_head().ok(),
+        };
         on_block(&mut store, &signed_block, &self.execution_engine).await?;
+
+        // The block is imported either way, a failed head update is retried by the next block
+        match store.update_head(old_head, &self.event_sender) {
+            Ok(new_head) => *head = Some(new_head),
+            Err(err) => warn!("Failed to update head after block import: {err}"),
+        }
         Ok(())
     }
 
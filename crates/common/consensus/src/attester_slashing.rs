@@ -9,3 +9,99 @@ pub struct AttesterSlashing {
     pub attestation_1: IndexedAttestation,
     pub attestation_2: IndexedAttestation,
 }
+
+impl AttesterSlashing {
+    /// Check if ``attestation_1`` and ``attestation_2`` are distinct votes for the same target.
+    pub fn is_double_vote(&self) -> bool {
+        let data_1 = &self.attestation_1.data;
+        let data_2 = &self.attestation_2.data;
+        data_1 != data_2 && data_1.target.epoch == data_2.target.epoch
+    }
+
+    /// Check if the vote of ``attestation_1`` surrounds the vote of ``attestation_2``.
+    pub fn is_surround_vote(&self) -> bool {
+        let data_1 = &self.attestation_1.data;
+        let data_2 = &self.attestation_2.data;
+        data_1.source.epoch < data_2.source.epoch && data_2.target.epoch < data_1.target.epoch
+    }
+
+    /// Check if the two attestations conflict under the Casper FFG rules.
+    pub fn is_slashable(&self) -> bool {
+        self.is_double_vote() || self.is_surround_vote()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_bls::BLSSignature;
+    use ssz_types::VariableList;
+
+    use super::*;
+    use crate::{attestation_data::AttestationData, checkpoint::Checkpoint};
+
+    fn indexed_attestation(source_epoch: u64, target_epoch: u64, byte: u8) -> IndexedAttestation {
+        IndexedAttestation {
+            attesting_indices: VariableList::from(vec![1, 2, 3]),
+            data: AttestationData {
+                slot: target_epoch * 32,
+                index: 0,
+                beacon_block_root: B256::repeat_byte(byte),
+                source: Checkpoint {
+                    epoch: source_epoch,
+                    root: B256::repeat_byte(1),
+                },
+                target: Checkpoint {
+                    epoch: target_epoch,
+                    root: B256::repeat_byte(byte),
+                },
+            },
+            signature: BLSSignature::default(),
+        }
+    }
+
+    #[test]
+    fn test_double_vote() {
+        let attester_slashing = AttesterSlashing {
+            attestation_1: indexed_attestation(1, 3, 2),
+            attestation_2: indexed_attestation(1, 3, 3),
+        };
+        assert!(attester_slashing.is_double_vote());
+        assert!(!attester_slashing.is_surround_vote());
+        assert!(attester_slashing.is_slashable());
+    }
+
+    #[test]
+    fn test_surround_vote() {
+        let attester_slashing = AttesterSlashing {
+            attestation_1: indexed_attestation(1, 4, 2),
+            attestation_2: indexed_attestation(2, 3, 3),
+        };
+        assert!(attester_slashing.is_surround_vote());
+        assert!(!attester_slashing.is_double_vote());
+        assert!(attester_slashing.is_slashable());
+
+        // The surrounded vote comes second, not first
+        let attester_slashing = AttesterSlashing {
+            attestation_1: attester_slashing.attestation_2,
+            attestation_2: attester_slashing.attestation_1,
+        };
+        assert!(!attester_slashing.is_slashable());
+    }
+
+    #[test]
+    fn test_non_conflicting_attestations() {
+        let attester_slashing = AttesterSlashing {
+            attestation_1: indexed_attestation(1, 3, 2),
+            attestation_2: indexed_attestation(1, 3, 2),
+        };
+        assert!(!attester_slashing.is_slashable());
+
+        // Consecutive votes neither share a target nor surround each other
+        let attester_slashing = AttesterSlashing {
+            attestation_1: indexed_attestation(1, 3, 2),
+            attestation_2: indexed_attestation(3, 4, 3),
+        };
+        assert!(!attester_slashing.is_slashable());
+    }
+}
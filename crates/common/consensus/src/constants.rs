@@ -76,6 +76,7 @@ pub const SLOTS_PER_EPOCH: u64 = 32;
 pub const SLOTS_PER_HISTORICAL_ROOT: u64 = 8192;
 pub const SYNC_COMMITTEE_SIZE: u64 = 512;
 pub const SYNC_REWARD_WEIGHT: u64 = 2;
+pub const TARGET_AGGREGATORS_PER_COMMITTEE: u64 = 16;
 pub const TARGET_COMMITTEE_SIZE: u64 = 128;
 pub const TIMELY_HEAD_FLAG_INDEX: u8 = 2;
 pub const TIMELY_SOURCE_FLAG_INDEX: u8 = 0;
@@ -23,13 +23,7 @@ pub struct SignedBeaconBlock {
 impl SignedBeaconBlock {
     pub fn signed_header(&self) -> SignedBeaconBlockHeader {
         SignedBeaconBlockHeader {
-            message: BeaconBlockHeader {
-                slot: self.message.slot,
-                proposer_index: self.message.proposer_index,
-                parent_root: self.message.parent_root,
-                state_root: self.message.state_root,
-                body_root: self.message.body.tree_hash_root(),
-            },
+            message: self.message.header(),
             signature: self.signature.clone(),
         }
     }
@@ -88,4 +82,15 @@ impl BeaconBlock {
     pub fn block_root(&self) -> B256 {
         self.tree_hash_root()
     }
+
+    /// Return the header of this block, which has the same ``hash_tree_root`` as the block.
+    pub fn header(&self) -> BeaconBlockHeader {
+        BeaconBlockHeader {
+            slot: self.slot,
+            proposer_index: self.proposer_index,
+            parent_root: self.parent_root,
+            state_root: self.state_root,
+            body_root: self.body.tree_hash_root(),
+        }
+    }
 }
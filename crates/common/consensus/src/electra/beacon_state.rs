@@ -424,15 +424,23 @@ impl BeaconState {
     }
 
     /// Return the signature domain (fork version concatenated with domain type) of a message.
-    pub fn get_domain(&self, domain_type: B32, epoch: Option<u64>) -> B256 {
+    ///
+    /// ``fork_version_override`` pins the fork version instead of deriving it from ``self.fork``,
+    /// e.g. voluntary exits are always signed with ``CAPELLA_FORK_VERSION`` (EIP-7044).
+    pub fn get_domain(
+        &self,
+        domain_type: B32,
+        epoch: Option<u64>,
+        fork_version_override: Option<B32>,
+    ) -> B256 {
         let epoch = match epoch {
             Some(epoch) => epoch,
             None => self.get_current_epoch(),
         };
-        let fork_version = if epoch < self.fork.epoch {
-            self.fork.previous_version
-        } else {
-            self.fork.current_version
+        let fork_version = match fork_version_override {
+            Some(fork_version) => fork_version,
+            None if epoch < self.fork.epoch => self.fork.previous_version,
+            None => self.fork.current_version,
         };
         compute_domain(
             domain_type,
@@ -501,6 +509,7 @@ impl BeaconState {
         let domain = self.get_domain(
             DOMAIN_BEACON_ATTESTER,
             Some(indexed_attestation.data.target.epoch),
+            None,
         );
         let signing_root = compute_signing_root(&indexed_attestation.data, domain);
 
@@ -524,10 +533,27 @@ impl BeaconState {
     /// Return the set of attesting indices corresponding to ``aggregation_bits`` and
     /// ``committee_bits``.
     pub fn get_attesting_indices(&self, attestation: &Attestation) -> anyhow::Result<HashSet<u64>> {
+        self.get_attesting_indices_with_cache(attestation, &mut HashMap::new())
+    }
+
+    /// Same as ``get_attesting_indices``, but looks committees up in ``committee_cache`` keyed by
+    /// ``(slot, committee_index)`` so that each committee is only shuffled once across calls.
+    pub fn get_attesting_indices_with_cache(
+        &self,
+        attestation: &Attestation,
+        committee_cache: &mut HashMap<(u64, u64), Vec<u64>>,
+    ) -> anyhow::Result<HashSet<u64>> {
         let mut output = HashSet::new();
         let mut committee_offset = 0;
         for committee_index in get_committee_indices(&attestation.committee_bits) {
-            let committee = self.get_beacon_committee(attestation.data.slot, committee_index)?;
+            let key = (attestation.data.slot, committee_index);
+            if !committee_cache.contains_key(&key) {
+                committee_cache.insert(
+                    key,
+                    self.get_beacon_committee(attestation.data.slot, committee_index)?,
+                );
+            }
+            let committee = &committee_cache[&key];
 
             let mut committee_attesters = HashSet::new();
             for (i, attester_index) in committee.iter().enumerate() {
@@ -1112,37 +1138,65 @@ impl BeaconState {
         )
     }
 
-    pub fn process_bls_to_execution_change(
-        &mut self,
+    /// Check that ``signed_address_change`` would be accepted by
+    /// ``process_bls_to_execution_change``: the validator still has ``BLS_WITHDRAWAL_PREFIX``
+    /// credentials committing to ``from_bls_public_key`` and the signature verifies.
+    pub fn validate_bls_to_execution_change(
+        &self,
         signed_address_change: &SignedBLSToExecutionChange,
     ) -> anyhow::Result<()> {
         let address_change = &signed_address_change.message;
 
-        ensure!(address_change.validator_index < self.validators.len() as u64);
-
-        let validator: &Validator = &self.validators[address_change.validator_index as usize];
+        let validator = self
+            .validators
+            .get(address_change.validator_index as usize)
+            .ok_or(anyhow!("Invalid validator index"))?;
 
-        ensure!(&validator.withdrawal_credentials[..1] == BLS_WITHDRAWAL_PREFIX);
+        ensure!(
+            &validator.withdrawal_credentials[..1] == BLS_WITHDRAWAL_PREFIX,
+            "Validator does not have BLS withdrawal credentials"
+        );
         ensure!(
             validator.withdrawal_credentials[1..]
-                == hash(address_change.from_bls_public_key.to_bytes())[1..]
+                == hash(address_change.from_bls_public_key.to_bytes())[1..],
+            "Withdrawal credentials do not match from_bls_pubkey"
+        );
+
+        ensure!(
+            self.verify_bls_to_execution_change_signature(signed_address_change)?,
+            "BLS Signature verification failed!"
         );
 
+        Ok(())
+    }
+
+    /// Verify the signature of ``signed_address_change`` against ``from_bls_public_key``.
+    pub fn verify_bls_to_execution_change_signature(
+        &self,
+        signed_address_change: &SignedBLSToExecutionChange,
+    ) -> anyhow::Result<bool> {
+        let address_change = &signed_address_change.message;
+
         // Fork-agnostic domain since address changes are valid across forks
         let domain = compute_domain(
             DOMAIN_BLS_TO_EXECUTION_CHANGE,
             None,
             Some(self.genesis_validators_root),
         );
-
         let signing_root = compute_signing_root(address_change, domain);
-        ensure!(
-            signed_address_change
-                .signature
-                .verify(&address_change.from_bls_public_key, signing_root.as_ref())?,
-            "BLS Signature verification failed!"
-        );
 
+        Ok(signed_address_change
+            .signature
+            .verify(&address_change.from_bls_public_key, signing_root.as_ref())?)
+    }
+
+    pub fn process_bls_to_execution_change(
+        &mut self,
+        signed_address_change: &SignedBLSToExecutionChange,
+    ) -> anyhow::Result<()> {
+        self.validate_bls_to_execution_change(signed_address_change)?;
+
+        let address_change = &signed_address_change.message;
         let withdrawal_credentials = [
             ETH1_ADDRESS_WITHDRAWAL_PREFIX,
             vec![0x00; 11].as_slice(),
@@ -1206,24 +1260,35 @@ impl BeaconState {
             "Validator has not been active long enough"
         );
 
-        // Compute signature domain
-        let domain = compute_domain(
-            DOMAIN_VOLUNTARY_EXIT,
-            Some(CAPELLA_FORK_VERSION),
-            Some(self.genesis_validators_root),
-        );
-        let signing_root = compute_signing_root(voluntary_exit, domain);
-
         ensure!(
-            signed_voluntary_exit
-                .signature
-                .verify(&validator.public_key, signing_root.as_ref())?,
+            self.verify_voluntary_exit_signature(signed_voluntary_exit)?,
             "BLS Signature verification failed!"
         );
 
         Ok(())
     }
 
+    /// Verify the signature of ``signed_voluntary_exit`` against the public key of the exiting
+    /// validator. Exits are signed with ``CAPELLA_FORK_VERSION`` (EIP-7044).
+    pub fn verify_voluntary_exit_signature(
+        &self,
+        signed_voluntary_exit: &SignedVoluntaryExit,
+    ) -> anyhow::Result<bool> {
+        let voluntary_exit = &signed_voluntary_exit.message;
+        let validator = self
+            .validators
+            .get(voluntary_exit.validator_index as usize)
+            .ok_or(anyhow!("Invalid validator index"))?;
+
+        // Compute signature domain
+        let domain = self.get_domain(DOMAIN_VOLUNTARY_EXIT, None, Some(CAPELLA_FORK_VERSION));
+        let signing_root = compute_signing_root(voluntary_exit, domain);
+
+        Ok(signed_voluntary_exit
+            .signature
+            .verify(&validator.public_key, signing_root.as_ref())?)
+    }
+
     pub fn process_voluntary_exit(
         &mut self,
         signed_voluntary_exit: &SignedVoluntaryExit,
@@ -1559,6 +1624,7 @@ impl BeaconState {
             let domain = self.get_domain(
                 DOMAIN_BEACON_PROPOSER,
                 Some(compute_epoch_at_slot(signed_header.message.slot)),
+                None,
             );
 
             let signing_root = compute_signing_root(&signed_header.message, domain);
@@ -1651,6 +1717,7 @@ impl BeaconState {
         let domain = self.get_domain(
             DOMAIN_SYNC_COMMITTEE,
             Some(compute_epoch_at_slot(previous_slot)),
+            None,
         );
         let signing_root =
             compute_signing_root(self.get_block_root_at_slot(previous_slot)?, domain);
@@ -1665,44 +1732,62 @@ impl BeaconState {
         );
 
         // Compute participant and proposer rewards
-        let total_active_increments = self.get_total_active_balance() / EFFECTIVE_BALANCE_INCREMENT;
-        let total_base_rewards = self.get_base_reward_per_increment() * total_active_increments;
-        let max_participant_rewards =
-            total_base_rewards * SYNC_REWARD_WEIGHT / WEIGHT_DENOMINATOR / SLOTS_PER_EPOCH;
-        let participant_reward = max_participant_rewards / SYNC_COMMITTEE_SIZE;
-        let proposer_reward =
-            participant_reward * PROPOSER_WEIGHT / (WEIGHT_DENOMINATOR - PROPOSER_WEIGHT);
+        let (participant_reward, proposer_reward) = self.get_sync_aggregate_rewards();
 
         // Apply participant and proposer rewards
-        let mut all_public_keys = vec![];
-        for validator in &self.validators {
-            all_public_keys.push(validator.public_key.clone());
-        }
-
-        let mut committee_indices = vec![];
-        for public_key in &self.current_sync_committee.public_keys {
-            let index = all_public_keys
-                .iter()
-                .position(|r| r == public_key)
-                .ok_or_else(|| anyhow!("Public key not found in all_public_keys."))?;
-            committee_indices.push(index);
-        }
-
+        let committee_indices = self.get_current_sync_committee_indices()?;
         for (participant_index, participation_bit) in committee_indices
             .iter()
             .zip(sync_aggregate.sync_committee_bits.iter())
         {
             if participation_bit {
-                self.increase_balance(*participant_index as u64, participant_reward)?;
+                self.increase_balance(*participant_index, participant_reward)?;
                 self.increase_balance(self.get_beacon_proposer_index(None)?, proposer_reward)?;
             } else {
-                self.decrease_balance(*participant_index as u64, participant_reward)?;
+                self.decrease_balance(*participant_index, participant_reward)?;
             }
         }
 
         Ok(())
     }
 
+    /// Return the reward of each sync committee participant and the reward the proposer earns
+    /// for including each participant, as applied in ``process_sync_aggregate``.
+    pub fn get_sync_aggregate_rewards(&self) -> (u64, u64) {
+        let total_active_increments = self.get_total_active_balance() / EFFECTIVE_BALANCE_INCREMENT;
+        let total_base_rewards = self.get_base_reward_per_increment() * total_active_increments;
+        let max_participant_rewards =
+            total_base_rewards * SYNC_REWARD_WEIGHT / WEIGHT_DENOMINATOR / SLOTS_PER_EPOCH;
+        let participant_reward = max_participant_rewards / SYNC_COMMITTEE_SIZE;
+        let proposer_reward =
+            participant_reward * PROPOSER_WEIGHT / (WEIGHT_DENOMINATOR - PROPOSER_WEIGHT);
+        (participant_reward, proposer_reward)
+    }
+
+    /// Return the validator index of each member of the current sync committee, in committee
+    /// order and with possible duplicates.
+    pub fn get_current_sync_committee_indices(&self) -> anyhow::Result<Vec<u64>> {
+        // Iterate in reverse so the first validator with a public key wins, as ``list.index`` does
+        let validator_indices = self
+            .validators
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, validator)| (&validator.public_key, index as u64))
+            .collect::<HashMap<_, _>>();
+
+        self.current_sync_committee
+            .public_keys
+            .iter()
+            .map(|public_key| {
+                validator_indices
+                    .get(public_key)
+                    .copied()
+                    .ok_or_else(|| anyhow!("Public key not found in all_public_keys."))
+            })
+            .collect()
+    }
+
     pub fn process_justification_and_finalization(&mut self) -> anyhow::Result<()> {
         // Initial FFG checkpoint values have a `0x00` stub for `root`.
         // Skip FFG updates in the first two epochs to avoid corner cases that might result in
@@ -1853,7 +1938,7 @@ impl BeaconState {
             .get(self.get_beacon_proposer_index(None)? as usize)
         {
             let signing_root =
-                compute_signing_root(epoch, self.get_domain(DOMAIN_RANDAO, Some(epoch)));
+                compute_signing_root(epoch, self.get_domain(DOMAIN_RANDAO, Some(epoch), None));
             ensure!(
                 body.randao_reveal
                     .verify(&proposer.public_key, signing_root.as_ref())?,
@@ -2265,7 +2350,7 @@ impl BeaconState {
         let proposer = &self.validators[signed_block.message.proposer_index as usize];
         let signing_root = compute_signing_root(
             signed_block.message.clone(),
-            self.get_domain(DOMAIN_BEACON_PROPOSER, None),
+            self.get_domain(DOMAIN_BEACON_PROPOSER, None, None),
         );
 
         signed_block
@@ -2361,7 +2446,21 @@ impl BeaconState {
             return Ok(());
         }
 
-        // Get deltas for each flag index and inactivity penalties
+        // Iterate over rewards and penalties for each delta
+        for (rewards, penalties) in self.get_attestation_deltas()? {
+            for index in 0..self.validators.len() {
+                self.increase_balance(index as u64, rewards[index])?;
+                self.decrease_balance(index as u64, penalties[index])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the ``(rewards, penalties)`` of the previous epoch attestations applied in
+    /// ``process_rewards_and_penalties``: one pair per participation flag index, followed by the
+    /// inactivity penalties.
+    pub fn get_attestation_deltas(&self) -> anyhow::Result<Vec<(Vec<u64>, Vec<u64>)>> {
         let mut deltas = vec![];
 
         // Collect the flag deltas for each participation flag index
@@ -2372,15 +2471,48 @@ impl BeaconState {
         // Add the inactivity penalties
         deltas.push(self.get_inactivity_penalty_deltas()?);
 
-        // Iterate over rewards and penalties for each delta
-        for (rewards, penalties) in deltas {
-            for index in 0..self.validators.len() {
-                self.increase_balance(index as u64, rewards[index])?;
-                self.decrease_balance(index as u64, penalties[index])?;
-            }
+        Ok(deltas)
+    }
+
+    /// Return, for each of ``effective_balances``, the reward per participation flag index that an
+    /// eligible, unslashed validator with that effective balance earns in ``get_flag_index_deltas``
+    /// by attesting timely and correctly in the previous epoch.
+    pub fn get_ideal_flag_index_rewards(
+        &self,
+        effective_balances: &[u64],
+    ) -> anyhow::Result<Vec<Vec<u64>>> {
+        let previous_epoch = self.get_previous_epoch();
+        let active_increments = self.get_total_active_balance() / EFFECTIVE_BALANCE_INCREMENT;
+        let in_inactivity_leak = self.is_in_inactivity_leak();
+
+        let mut unslashed_participating_increments = vec![];
+        for flag_index in 0..PARTICIPATION_FLAG_WEIGHTS.len() {
+            let unslashed_participating_indices =
+                self.get_unslashed_participating_indices(flag_index as u8, previous_epoch)?;
+            unslashed_participating_increments.push(
+                self.get_total_balance(unslashed_participating_indices)
+                    / EFFECTIVE_BALANCE_INCREMENT,
+            );
         }
 
-        Ok(())
+        Ok(effective_balances
+            .iter()
+            .map(|effective_balance| {
+                let base_reward = effective_balance / EFFECTIVE_BALANCE_INCREMENT
+                    * self.get_base_reward_per_increment();
+                PARTICIPATION_FLAG_WEIGHTS
+                    .iter()
+                    .zip(&unslashed_participating_increments)
+                    .map(|(weight, participating_increments)| {
+                        if in_inactivity_leak {
+                            return 0;
+                        }
+                        base_reward * weight * participating_increments
+                            / (active_increments * WEIGHT_DENOMINATOR)
+                    })
+                    .collect()
+            })
+            .collect())
     }
 
     /// Return the next sync committee, with possible public_key duplicates.
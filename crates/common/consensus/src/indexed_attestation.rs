@@ -1,10 +1,15 @@
-use ream_bls::BLSSignature;
+use anyhow::{anyhow, ensure};
+use rayon::prelude::*;
+use ream_bls::{BLSSignature, PublicKey, traits::Verifiable};
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use ssz_types::{VariableList, serde_utils::quoted_u64_var_list, typenum::U131072};
 use tree_hash_derive::TreeHash;
 
-use crate::attestation_data::AttestationData;
+use crate::{
+    attestation_data::AttestationData, constants::DOMAIN_BEACON_ATTESTER,
+    electra::beacon_state::BeaconState, misc::compute_signing_root,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
 pub struct IndexedAttestation {
@@ -13,3 +18,92 @@ pub struct IndexedAttestation {
     pub data: AttestationData,
     pub signature: BLSSignature,
 }
+
+impl IndexedAttestation {
+    /// Verify ``signature`` against the aggregate public key of ``attesting_indices`` in the
+    /// validator registry of ``state``. An empty ``attesting_indices`` is never valid.
+    pub fn verify_signature(&self, state: &BeaconState) -> anyhow::Result<bool> {
+        if self.attesting_indices.is_empty() {
+            return Ok(false);
+        }
+
+        let public_keys = self
+            .attesting_indices
+            .iter()
+            .map(|&index| {
+                state
+                    .validators
+                    .get(index as usize)
+                    .map(|validator| validator.public_key.clone())
+                    .ok_or(anyhow!("Invalid validator index {index}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let aggregate_public_key = PublicKey::aggregate_committee(&public_keys)
+            .map_err(|err| anyhow!("Failed to aggregate attesting public keys: {err:?}"))?;
+
+        let domain = state.get_domain(DOMAIN_BEACON_ATTESTER, Some(self.data.target.epoch), None);
+        let signing_root = compute_signing_root(&self.data, domain);
+
+        self.signature
+            .verify(&aggregate_public_key, signing_root.as_ref())
+            .map_err(|err| anyhow!("Failed to verify indexed attestation signature: {err:?}"))
+    }
+}
+
+/// Check every attestation in ``indexed_attestations`` with
+/// [`BeaconState::is_valid_indexed_attestation`], aggregating public keys and verifying signatures
+/// across the rayon thread pool. Returns the first failure found, in which case the remaining
+/// attestations may not have been checked. Accepts exactly the batches that checking each
+/// attestation in turn would accept.
+pub fn verify_indexed_attestations_parallel(
+    state: &BeaconState,
+    indexed_attestations: &[IndexedAttestation],
+) -> anyhow::Result<()> {
+    indexed_attestations
+        .par_iter()
+        .enumerate()
+        .try_for_each(|(index, indexed_attestation)| {
+            ensure!(
+                state.is_valid_indexed_attestation(indexed_attestation)?,
+                "Indexed attestation {index} is not valid"
+            );
+            Ok(())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ssz_types::typenum::Unsigned;
+
+    use super::*;
+    use crate::{
+        checkpoint::Checkpoint,
+        constants::{MAX_COMMITTEES_PER_SLOT, MAX_VALIDATORS_PER_COMMITTEE},
+    };
+
+    fn max_len<N: Unsigned>(_: &VariableList<u64, N>) -> u64 {
+        N::to_u64()
+    }
+
+    #[test]
+    fn test_attesting_indices_bound() {
+        let indexed_attestation = IndexedAttestation {
+            attesting_indices: VariableList::empty(),
+            data: AttestationData {
+                slot: 0,
+                index: 0,
+                beacon_block_root: B256::ZERO,
+                source: Checkpoint::default(),
+                target: Checkpoint::default(),
+            },
+            signature: BLSSignature::default(),
+        };
+
+        // Electra attestations aggregate every committee of a slot
+        assert_eq!(
+            max_len(&indexed_attestation.attesting_indices),
+            MAX_VALIDATORS_PER_COMMITTEE * MAX_COMMITTEES_PER_SLOT
+        );
+    }
+}
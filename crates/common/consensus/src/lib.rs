@@ -34,6 +34,7 @@ pub mod pending_partial_withdrawal;
 pub mod polynomial_commitments;
 pub mod predicates;
 pub mod proposer_slashing;
+pub mod signature_set;
 pub mod signing_data;
 pub mod single_attestation;
 pub mod sync_aggregate;
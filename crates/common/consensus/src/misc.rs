@@ -3,13 +3,14 @@ use std::cmp::max;
 use alloy_primitives::{B256, aliases::B32};
 use anyhow::ensure;
 use ethereum_hashing::hash;
+use ream_bls::signature::BLSSignature;
 use ssz_types::{BitVector, typenum::U64};
 use tree_hash::TreeHash;
 
 use crate::{
     constants::{
         COMPOUNDING_WITHDRAWAL_PREFIX, EPOCHS_PER_SYNC_COMMITTEE_PERIOD, GENESIS_FORK_VERSION,
-        MAX_SEED_LOOKAHEAD, SHUFFLE_ROUND_COUNT, SLOTS_PER_EPOCH,
+        MAX_SEED_LOOKAHEAD, SHUFFLE_ROUND_COUNT, SLOTS_PER_EPOCH, TARGET_AGGREGATORS_PER_COMMITTEE,
     },
     fork_data::ForkData,
     signing_data::SigningData,
@@ -78,6 +79,13 @@ pub fn bytes_to_int64(slice: &[u8]) -> u64 {
     u64::from_le_bytes(bytes)
 }
 
+/// Return whether the validator with selection proof ``slot_signature`` is an aggregator of a
+/// committee of ``committee_length`` validators.
+pub fn is_aggregator(committee_length: u64, slot_signature: &BLSSignature) -> bool {
+    let modulo = max(1, committee_length / TARGET_AGGREGATORS_PER_COMMITTEE);
+    bytes_to_int64(&hash(slot_signature.to_slice())[0..8]) % modulo == 0
+}
+
 /// Return the committee corresponding to ``indices``, ``seed``, ``index``, and committee ``count``.
 pub fn compute_committee(
     indices: &[u64],
@@ -134,6 +142,16 @@ pub fn compute_domain(
     B256::from_slice(&domain_bytes)
 }
 
+/// Return the 4-byte fork digest for ``fork_version`` and ``genesis_validators_root``, which
+/// separates the p2p networks of different forks and chains.
+pub fn compute_fork_digest(fork_version: B32, genesis_validators_root: B256) -> B32 {
+    ForkData {
+        current_version: fork_version,
+        genesis_validators_root,
+    }
+    .compute_fork_digest()
+}
+
 pub fn is_sorted_and_unique(indices: &[usize]) -> bool {
     indices.windows(2).all(|w| w[0] < w[1])
 }
@@ -157,3 +175,51 @@ pub fn compute_sync_committee_period(epoch: u64) -> u64 {
 pub fn compute_sync_committee_period_at_slot(slot: u64) -> u64 {
     compute_sync_committee_period(compute_epoch_at_slot(slot))
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{b256, fixed_bytes};
+    use ssz_types::FixedVector;
+
+    use super::*;
+
+    const MAINNET_GENESIS_VALIDATORS_ROOT: B256 =
+        b256!("0x4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95");
+
+    fn signature(byte: u8) -> BLSSignature {
+        BLSSignature {
+            inner: FixedVector::from(vec![byte; 96]),
+        }
+    }
+
+    #[test]
+    fn test_is_aggregator() {
+        // sha256 prefixes as little endian integers: 0xab..ab is 1911656678662886733, the
+        // infinity signature 16014238365542935304 and 0xfa..fa 12007607456083300224
+        for committee_length in [0, 1, 16, 31] {
+            assert!(is_aggregator(committee_length, &signature(0xab)));
+        }
+        assert!(!is_aggregator(128, &signature(0xab)));
+
+        assert!(is_aggregator(128, &BLSSignature::infinity()));
+        assert!(!is_aggregator(256, &BLSSignature::infinity()));
+
+        assert!(is_aggregator(2048, &signature(0xfa)));
+        assert!(!is_aggregator(4096, &signature(0xfa)));
+    }
+
+    #[test]
+    fn test_compute_fork_digest_mainnet() {
+        for (fork_version, fork_digest) in [
+            (fixed_bytes!("0x00000000"), fixed_bytes!("0xb5303f2a")),
+            (fixed_bytes!("0x01000000"), fixed_bytes!("0xafcaaba0")),
+            (fixed_bytes!("0x04000000"), fixed_bytes!("0x6a95a1a9")),
+            (fixed_bytes!("0x05000000"), fixed_bytes!("0xad532ceb")),
+        ] {
+            assert_eq!(
+                compute_fork_digest(fork_version, MAINNET_GENESIS_VALIDATORS_ROOT),
+                fork_digest
+            );
+        }
+    }
+}
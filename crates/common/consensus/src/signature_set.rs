@@ -0,0 +1,375 @@
+use std::cmp::max;
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, bail};
+use ream_bls::{BLSSignature, PublicKey, traits::Verifiable};
+
+use crate::{
+    constants::{
+        CAPELLA_FORK_VERSION, DOMAIN_BEACON_ATTESTER, DOMAIN_BEACON_PROPOSER,
+        DOMAIN_BLS_TO_EXECUTION_CHANGE, DOMAIN_RANDAO, DOMAIN_SYNC_COMMITTEE,
+        DOMAIN_VOLUNTARY_EXIT,
+    },
+    electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
+    indexed_attestation::IndexedAttestation,
+    misc::{compute_domain, compute_epoch_at_slot, compute_signing_root},
+};
+
+/// The part of a block a signature in a [`SignatureSets`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureKind {
+    BlockProposal,
+    RandaoReveal,
+    ProposerSlashing,
+    AttesterSlashing,
+    Attestation,
+    VoluntaryExit,
+    BLSToExecutionChange,
+    SyncAggregate,
+}
+
+/// A signature together with the public key and signing root it has to verify against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureSet {
+    pub kind: SignatureKind,
+    /// Position of the operation in its list of the block body, ``0`` for the signatures a block
+    /// has only one of.
+    pub index: usize,
+    /// The aggregate of the signers' public keys for aggregate signatures.
+    pub public_key: PublicKey,
+    pub signing_root: B256,
+    pub signature: BLSSignature,
+}
+
+impl SignatureSet {
+    fn is_valid(&self) -> bool {
+        matches!(
+            self.signature
+                .verify(&self.public_key, self.signing_root.as_ref()),
+            Ok(true)
+        )
+    }
+}
+
+/// Accumulates the signatures of a block so they can be checked with one batched BLS
+/// verification instead of one verification each.
+#[derive(Debug, Clone, Default)]
+pub struct SignatureSets {
+    sets: Vec<SignatureSet>,
+}
+
+impl SignatureSets {
+    pub fn push(
+        &mut self,
+        kind: SignatureKind,
+        index: usize,
+        public_key: PublicKey,
+        signing_root: B256,
+        signature: BLSSignature,
+    ) {
+        self.sets.push(SignatureSet {
+            kind,
+            index,
+            public_key,
+            signing_root,
+            signature,
+        });
+    }
+
+    /// Add an aggregate ``signature`` of all of ``public_keys``, which can't be empty.
+    pub fn push_aggregate(
+        &mut self,
+        kind: SignatureKind,
+        index: usize,
+        public_keys: &[PublicKey],
+        signing_root: B256,
+        signature: BLSSignature,
+    ) -> anyhow::Result<()> {
+        let public_key = PublicKey::aggregate_committee(public_keys)
+            .map_err(|err| anyhow!("Failed to aggregate {kind:?} public keys: {err:?}"))?;
+        self.push(kind, index, public_key, signing_root, signature);
+        Ok(())
+    }
+
+    pub fn sets(&self) -> &[SignatureSet] {
+        &self.sets
+    }
+
+    pub fn len(&self) -> usize {
+        self.sets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    /// Return the first invalid set, or ``None`` if every signature is valid. All sets are
+    /// verified in one batch first, only if the batch fails are they verified one by one to find
+    /// the signature that failed.
+    pub fn find_invalid(&self) -> Option<&SignatureSet> {
+        if self.sets.is_empty() {
+            return None;
+        }
+
+        let batch = self
+            .sets
+            .iter()
+            .map(|set| (&set.public_key, set.signing_root.as_slice(), &set.signature))
+            .collect::<Vec<_>>();
+        if matches!(BLSSignature::verify_multiple(&batch), Ok(true)) {
+            return None;
+        }
+
+        self.sets.iter().find(|set| !set.is_valid())
+    }
+
+    pub fn verify(&self) -> anyhow::Result<()> {
+        match self.find_invalid() {
+            Some(set) => bail!("Invalid {:?} signature at index {}", set.kind, set.index),
+            None => Ok(()),
+        }
+    }
+}
+
+fn get_public_key(state: &BeaconState, index: u64) -> anyhow::Result<PublicKey> {
+    state
+        .validators
+        .get(index as usize)
+        .map(|validator| validator.public_key.clone())
+        .ok_or(anyhow!("Invalid validator index {index}"))
+}
+
+fn push_indexed_attestation(
+    signature_sets: &mut SignatureSets,
+    state: &BeaconState,
+    kind: SignatureKind,
+    index: usize,
+    indexed_attestation: &IndexedAttestation,
+) -> anyhow::Result<()> {
+    let public_keys = indexed_attestation
+        .attesting_indices
+        .iter()
+        .map(|&validator_index| get_public_key(state, validator_index))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let domain = state.get_domain(
+        DOMAIN_BEACON_ATTESTER,
+        Some(indexed_attestation.data.target.epoch),
+        None,
+    );
+
+    signature_sets.push_aggregate(
+        kind,
+        index,
+        &public_keys,
+        compute_signing_root(&indexed_attestation.data, domain),
+        indexed_attestation.signature.clone(),
+    )
+}
+
+/// Collect the signatures ``process_block`` verifies for ``signed_block``, including the block
+/// signature itself. ``state`` has to be advanced to the slot of the block. Deposit signatures
+/// are left out, as an invalid deposit signature doesn't make the block invalid.
+pub fn get_block_signature_sets(
+    state: &BeaconState,
+    signed_block: &SignedBeaconBlock,
+) -> anyhow::Result<SignatureSets> {
+    let block = &signed_block.message;
+    let body = &block.body;
+    let mut signature_sets = SignatureSets::default();
+
+    signature_sets.push(
+        SignatureKind::BlockProposal,
+        0,
+        get_public_key(state, block.proposer_index)?,
+        compute_signing_root(block, state.get_domain(DOMAIN_BEACON_PROPOSER, None, None)),
+        signed_block.signature.clone(),
+    );
+
+    let epoch = state.get_current_epoch();
+    signature_sets.push(
+        SignatureKind::RandaoReveal,
+        0,
+        get_public_key(state, state.get_beacon_proposer_index(None)?)?,
+        compute_signing_root(epoch, state.get_domain(DOMAIN_RANDAO, Some(epoch), None)),
+        body.randao_reveal.clone(),
+    );
+
+    for (index, proposer_slashing) in body.proposer_slashings.iter().enumerate() {
+        for signed_header in [
+            &proposer_slashing.signed_header_1,
+            &proposer_slashing.signed_header_2,
+        ] {
+            let domain = state.get_domain(
+                DOMAIN_BEACON_PROPOSER,
+                Some(compute_epoch_at_slot(signed_header.message.slot)),
+                None,
+            );
+            signature_sets.push(
+                SignatureKind::ProposerSlashing,
+                index,
+                get_public_key(state, signed_header.message.proposer_index)?,
+                compute_signing_root(&signed_header.message, domain),
+                signed_header.signature.clone(),
+            );
+        }
+    }
+
+    for (index, attester_slashing) in body.attester_slashings.iter().enumerate() {
+        for indexed_attestation in [
+            &attester_slashing.attestation_1,
+            &attester_slashing.attestation_2,
+        ] {
+            push_indexed_attestation(
+                &mut signature_sets,
+                state,
+                SignatureKind::AttesterSlashing,
+                index,
+                indexed_attestation,
+            )?;
+        }
+    }
+
+    for (index, attestation) in body.attestations.iter().enumerate() {
+        push_indexed_attestation(
+            &mut signature_sets,
+            state,
+            SignatureKind::Attestation,
+            index,
+            &state.get_indexed_attestation(attestation)?,
+        )?;
+    }
+
+    for (index, signed_voluntary_exit) in body.voluntary_exits.iter().enumerate() {
+        let voluntary_exit = &signed_voluntary_exit.message;
+        let domain = state.get_domain(DOMAIN_VOLUNTARY_EXIT, None, Some(CAPELLA_FORK_VERSION));
+        signature_sets.push(
+            SignatureKind::VoluntaryExit,
+            index,
+            get_public_key(state, voluntary_exit.validator_index)?,
+            compute_signing_root(voluntary_exit, domain),
+            signed_voluntary_exit.signature.clone(),
+        );
+    }
+
+    for (index, signed_address_change) in body.bls_to_execution_changes.iter().enumerate() {
+        let address_change = &signed_address_change.message;
+        let domain = compute_domain(
+            DOMAIN_BLS_TO_EXECUTION_CHANGE,
+            None,
+            Some(state.genesis_validators_root),
+        );
+        signature_sets.push(
+            SignatureKind::BLSToExecutionChange,
+            index,
+            address_change.from_bls_public_key.clone(),
+            compute_signing_root(address_change, domain),
+            signed_address_change.signature.clone(),
+        );
+    }
+
+    let sync_aggregate = &body.sync_aggregate;
+    let participant_public_keys = state
+        .current_sync_committee
+        .public_keys
+        .iter()
+        .zip(sync_aggregate.sync_committee_bits.iter())
+        .filter_map(|(public_key, bit)| bit.then(|| public_key.clone()))
+        .collect::<Vec<_>>();
+    if participant_public_keys.is_empty() {
+        // ``eth_fast_aggregate_verify`` only accepts the infinity signature without participants
+        if sync_aggregate.sync_committee_signature != BLSSignature::infinity() {
+            bail!("Sync aggregate without participants must have the infinity signature");
+        }
+    } else {
+        let previous_slot = max(state.slot, 1) - 1;
+        let domain = state.get_domain(
+            DOMAIN_SYNC_COMMITTEE,
+            Some(compute_epoch_at_slot(previous_slot)),
+            None,
+        );
+        signature_sets.push_aggregate(
+            SignatureKind::SyncAggregate,
+            0,
+            &participant_public_keys,
+            compute_signing_root(state.get_block_root_at_slot(previous_slot)?, domain),
+            sync_aggregate.sync_committee_signature.clone(),
+        )?;
+    }
+
+    Ok(signature_sets)
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_bls::{PrivateKey, traits::Signable};
+
+    use super::*;
+
+    fn private_key(byte: u8) -> PrivateKey {
+        PrivateKey {
+            inner: B256::left_padding_from(&[byte]),
+        }
+    }
+
+    fn push_signed(
+        signature_sets: &mut SignatureSets,
+        kind: SignatureKind,
+        private_key: &PrivateKey,
+        signing_root: B256,
+        signed_root: B256,
+    ) {
+        signature_sets.push(
+            kind,
+            0,
+            private_key.public_key().unwrap(),
+            signing_root,
+            private_key.sign(signed_root.as_ref()).unwrap(),
+        );
+    }
+
+    fn block_signature_sets(randao_signed_root: B256) -> SignatureSets {
+        let proposer = private_key(1);
+        let mut signature_sets = SignatureSets::default();
+        push_signed(
+            &mut signature_sets,
+            SignatureKind::BlockProposal,
+            &proposer,
+            B256::repeat_byte(1),
+            B256::repeat_byte(1),
+        );
+        push_signed(
+            &mut signature_sets,
+            SignatureKind::RandaoReveal,
+            &proposer,
+            B256::repeat_byte(2),
+            randao_signed_root,
+        );
+        push_signed(
+            &mut signature_sets,
+            SignatureKind::VoluntaryExit,
+            &private_key(2),
+            B256::repeat_byte(3),
+            B256::repeat_byte(3),
+        );
+        signature_sets
+    }
+
+    #[test]
+    fn test_valid_signature_sets() {
+        let signature_sets = block_signature_sets(B256::repeat_byte(2));
+
+        assert_eq!(signature_sets.find_invalid(), None);
+        assert!(signature_sets.verify().is_ok());
+        assert!(SignatureSets::default().verify().is_ok());
+    }
+
+    #[test]
+    fn test_bad_randao_reveal_is_identified() {
+        let signature_sets = block_signature_sets(B256::repeat_byte(4));
+
+        let invalid_set = signature_sets.find_invalid().unwrap();
+        assert_eq!(invalid_set.kind, SignatureKind::RandaoReveal);
+        assert_eq!(invalid_set.index, 0);
+        assert!(signature_sets.verify().is_err());
+    }
+}
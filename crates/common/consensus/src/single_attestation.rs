@@ -7,7 +7,9 @@ use crate::attestation_data::AttestationData;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
 pub struct SingleAttestation {
+    #[serde(with = "serde_utils::quoted_u64")]
     pub committee_index: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
     pub attester_index: u64,
     pub data: AttestationData,
     pub signature: BLSSignature,
@@ -1,19 +1,27 @@
-use std::{future::Future, sync::Arc, thread::sleep, time::Duration};
-
-use tokio::{runtime::Runtime, sync::broadcast, task::JoinHandle};
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::{
+    runtime::Runtime,
+    sync::{broadcast, oneshot},
+    task::JoinHandle,
+};
 use tracing::{debug, warn};
 
 #[derive(Clone)]
 pub struct ReamExecutor {
     runtime: Arc<Runtime>,
     shutdown: broadcast::Sender<()>,
+    /// Resolve once the matching task spawned by `spawn_graceful` has completed
+    graceful_tasks: Arc<Mutex<Vec<oneshot::Receiver<()>>>>,
 }
 
 impl ReamExecutor {
     pub fn new() -> std::io::Result<Self> {
-        let runtime = Arc::new(Runtime::new()?);
-        let (shutdown, _) = broadcast::channel(1);
-        Ok(Self { runtime, shutdown })
+        Ok(Self::with_runtime(Runtime::new()?))
     }
 
     /// Creates a new TaskExecutor with an existing runtime
@@ -22,6 +30,7 @@ impl ReamExecutor {
         Self {
             runtime: Arc::new(runtime),
             shutdown,
+            graceful_tasks: Arc::default(),
         }
     }
 
@@ -58,6 +67,27 @@ impl ReamExecutor {
         })
     }
 
+    /// Spawns a task that is handed the shutdown signal instead of being cancelled by it, so it can
+    /// wind down gracefully. `shutdown_runtime` waits for it before shutting the runtime down.
+    pub fn spawn_graceful<F, Fut, T>(&self, future_fn: F) -> JoinHandle<T>
+    where
+        F: FnOnce(broadcast::Receiver<()>) -> Fut,
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (completed, completion) = oneshot::channel();
+        self.graceful_tasks
+            .lock()
+            .expect("graceful task list lock poisoned")
+            .push(completion);
+        let future = future_fn(self.shutdown.subscribe());
+        self.runtime.spawn(async move {
+            let result = future.await;
+            let _ = completed.send(());
+            result
+        })
+    }
+
     /// Spawns a blocking task in a dedicated thread pool
     pub fn spawn_blocking<F, R>(&self, task: F) -> JoinHandle<R>
     where
@@ -95,8 +125,24 @@ impl ReamExecutor {
         }
     }
 
-    pub fn shutdown_runtime(self) {
-        sleep(Duration::from_secs(5));
+    /// Waits up to `timeout` for the tasks spawned by `spawn_graceful` to complete, then shuts the
+    /// runtime down. Call it after `shutdown_signal`, with a `timeout` longer than the tasks take
+    /// to wind down.
+    pub fn shutdown_runtime(self, timeout: Duration) {
+        let graceful_tasks = std::mem::take(
+            &mut *self
+                .graceful_tasks
+                .lock()
+                .expect("graceful task list lock poisoned"),
+        );
+        // A task that panicked drops its sender, which completes the receiver as well
+        let completed = self.runtime.block_on(tokio::time::timeout(
+            timeout,
+            futures::future::join_all(graceful_tasks),
+        ));
+        if completed.is_err() {
+            warn!("Tasks did not wind down within {timeout:?}, shutting down anyway");
+        }
 
         let arc_count = Arc::strong_count(&self.runtime) + Arc::weak_count(&self.runtime);
         match Arc::try_unwrap(self.runtime) {
@@ -155,6 +201,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_graceful_task_outlives_shutdown_signal() {
+        let executor = ReamExecutor::new().unwrap();
+
+        let handle = executor.spawn_graceful(|mut shutdown| async move {
+            let _ = shutdown.recv().await;
+            sleep(Duration::from_millis(50)).await;
+            "wound down"
+        });
+
+        executor.shutdown_signal();
+        assert_eq!(executor.runtime.block_on(handle).unwrap(), "wound down");
+    }
+
+    #[test]
+    fn test_shutdown_runtime_waits_for_graceful_tasks() {
+        let executor = ReamExecutor::new().unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        executor.spawn_graceful(|mut shutdown| async move {
+            let _ = shutdown.recv().await;
+            sleep(Duration::from_millis(50)).await;
+            sender.send("wound down").unwrap();
+        });
+
+        executor.shutdown_signal();
+        executor.shutdown_runtime(Duration::from_secs(5));
+        assert_eq!(receiver.try_recv(), Ok("wound down"));
+    }
+
     #[test]
     fn test_spawn_many() {
         let executor = ReamExecutor::new().unwrap();
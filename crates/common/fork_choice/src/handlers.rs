@@ -1,4 +1,4 @@
-use alloy_primitives::{B256, map::HashSet};
+use alloy_primitives::map::HashSet;
 use anyhow::{anyhow, ensure};
 use ream_consensus::{
     attestation::Attestation,
@@ -9,10 +9,7 @@ use ream_consensus::{
     misc::compute_start_slot_at_epoch,
     predicates::is_slashable_attestation_data,
 };
-use ream_storage::{
-    errors::StoreError,
-    tables::{Field, Table},
-};
+use ream_storage::tables::{Field, Table, execution_status::ExecutionStatus};
 use tree_hash::TreeHash;
 
 use crate::store::Store;
@@ -52,12 +49,7 @@ pub async fn on_block(
     ensure!(block.slot > finalized_slot);
 
     // Check block is a descendant of the finalized block at the checkpoint finalized slot
-    let finalized_checkpoint_block = store.get_checkpoint_block(
-        block.parent_root,
-        store.db.finalized_checkpoint_provider().get()?.epoch,
-    )?;
-
-    ensure!(store.db.finalized_checkpoint_provider().get()?.root == finalized_checkpoint_block);
+    ensure!(store.is_finalized_descendant(block.parent_root)?);
 
     // Check if blob data is available
     // If not, this block MAY be queued and subsequently considered when blob data becomes available
@@ -86,16 +78,13 @@ pub async fn on_block(
         .state_transition(signed_block, true, execution_engine)
         .await?;
 
-    // Add new block to the store
-    store
-        .db
-        .beacon_block_provider()
-        .insert(block_root, signed_block.clone())?;
-    // Add new state for this block to the store
-    store
-        .db
-        .beacon_state_provider()
-        .insert(block_root, state.clone())?;
+    // Without an execution engine the payload is imported optimistically, otherwise the state
+    // transition only succeeds for payloads the execution layer reported as valid
+    let execution_status = if execution_engine.is_some() {
+        ExecutionStatus::Valid
+    } else {
+        ExecutionStatus::Optimistic
+    };
 
     // Add block timeliness to the store
     let time_into_slot = (store.db.time_provider().get()?
@@ -103,23 +92,43 @@ pub async fn on_block(
         % SECONDS_PER_SLOT;
     let is_before_attesting_interval = time_into_slot < SECONDS_PER_SLOT / INTERVALS_PER_SLOT;
     let is_timely = store.get_current_slot()? == block.slot && is_before_attesting_interval;
-    store
-        .db
-        .block_timeliness_provider()
-        .insert(block_root, is_timely)?;
 
     // Add proposer score boost if the block is timely and not conflicting with an existing block
-    let is_first_block = store.db.proposer_boost_root_provider().get()? == B256::ZERO;
-
-    if is_timely && is_first_block {
-        store.db.proposer_boost_root_provider().insert(block_root)?;
+    let is_first_block = store.get_proposer_boost_root()?.is_none();
+
+    // Write the block, its state and the checkpoint updates atomically, so a crash cannot leave an
+    // index pointing at a block whose state was never persisted
+    let is_finalized_updated = store.db.write_batch(|batch| {
+        // Add new block and its post-state to the store. The validating state transition already
+        // checked the post-state against the state root of the block
+        batch.insert_block_with_state(
+            block_root,
+            signed_block.clone(),
+            state.clone(),
+            block.state_root,
+        )?;
+        batch.insert_block_timeliness(block_root, is_timely)?;
+        batch.insert_execution_status(block_root, execution_status)?;
+        if is_timely && is_first_block {
+            batch.insert_proposer_boost_root(block_root)?;
+        }
+
+        // Update checkpoints in store if necessary
+        store.stage_checkpoints(
+            batch,
+            state.current_justified_checkpoint,
+            state.finalized_checkpoint,
+        )
+    })?;
+    if is_finalized_updated {
+        store.clean_operation_pool(state.finalized_checkpoint)?;
     }
 
-    // Update checkpoints in store if necessary
-    store.update_checkpoints(
-        state.current_justified_checkpoint,
-        state.finalized_checkpoint,
-    )?;
+    // A payload the execution layer verified confirms every ancestor payload, so the ancestors
+    // imported optimistically are no longer optimistic
+    if execution_status == ExecutionStatus::Valid {
+        store.db.set_execution_valid(block.parent_root)?;
+    }
 
     // Eagerly compute unrealized justification and finality.
     store.compute_pulled_up_tip(block_root)?;
@@ -160,11 +169,7 @@ pub fn on_attester_slashing(
         .into_iter()
         .collect::<HashSet<_>>();
 
-    let mut equivocating = match store.db.equivocating_indices_provider().get() {
-        Ok(set) => set,
-        Err(StoreError::FieldNotInitilized) => HashSet::default(),
-        Err(err) => return Err(err.into()),
-    };
+    let mut equivocating = store.get_equivocating_indices()?;
 
     for index in attestation_1_indices.intersection(&attestation_2_indices) {
         equivocating.insert(*index);
@@ -181,7 +186,7 @@ pub fn on_attester_slashing(
 pub fn on_tick(store: &mut Store, time: u64) -> anyhow::Result<()> {
     // If the ``store.time`` falls behind, while loop catches up slot by slot
     // to ensure that every previous slot is processed with ``on_tick_per_slot``
-    let tick_slot = (time - store.db.genesis_time_provider().get()?) / SECONDS_PER_SLOT;
+    let tick_slot = time.saturating_sub(store.db.genesis_time_provider().get()?) / SECONDS_PER_SLOT;
     while store.get_current_slot()? < tick_slot {
         let previous_time = store.db.genesis_time_provider().get()?
             + (store.get_current_slot()? + 1) * SECONDS_PER_SLOT;
@@ -1,8 +1,12 @@
-use std::{cmp::Ordering, sync::Arc};
+use std::{
+    cmp::{Ordering, min},
+    sync::{Arc, LazyLock},
+};
 
-use alloy_primitives::B256;
+use alloy_primitives::{B256, map::HashSet};
 use anyhow::{anyhow, bail, ensure};
 use hashbrown::HashMap;
+use ream_beacon_api_types::events::{BeaconEvent, ChainReorgEvent};
 use ream_bls::BLSSignature;
 use ream_consensus::{
     attestation::Attestation,
@@ -20,13 +24,16 @@ use ream_consensus::{
     helpers::{calculate_committee_fraction, get_total_active_balance},
     misc::{compute_epoch_at_slot, compute_start_slot_at_epoch, is_shuffling_stable},
     polynomial_commitments::kzg_commitment::KZGCommitment,
+    proposer_slashing::ProposerSlashing,
 };
 use ream_operation_pool::OperationPool;
 use ream_polynomial_commitments::handlers::verify_blob_kzg_proof_batch;
 use ream_storage::{
-    db::ReamDB,
+    db::{ReamDB, WriteBatch},
+    errors::StoreError,
     tables::{Field, MultimapTable, Table},
 };
+use tokio::sync::broadcast;
 use tree_hash::TreeHash;
 
 use crate::constants::{
@@ -34,6 +41,10 @@ use crate::constants::{
     REORG_PARENT_WEIGHT_THRESHOLD,
 };
 
+/// The operation pool of stores built by [`Store::read_only`], never written to.
+static EMPTY_OPERATION_POOL: LazyLock<Arc<OperationPool>> =
+    LazyLock::new(|| Arc::new(OperationPool::default()));
+
 #[derive(Debug)]
 pub struct Store {
     pub db: ReamDB,
@@ -45,6 +56,13 @@ impl Store {
         Self { db, operation_pool }
     }
 
+    /// Create a store over ``db`` for reading fork choice, such as the head or the block tree,
+    /// without the operation pool of the node. All read only stores share one empty pool, so
+    /// they must not be used to import blocks or operations.
+    pub fn read_only(db: ReamDB) -> Self {
+        Self::new(db, EMPTY_OPERATION_POOL.clone())
+    }
+
     pub fn is_previous_epoch_justified(&self) -> anyhow::Result<bool> {
         let current_epoch = self.get_current_store_epoch()?;
         Ok(self.db.justified_checkpoint_provider().get()?.epoch + 1 == current_epoch)
@@ -58,24 +76,30 @@ impl Store {
         Ok(GENESIS_SLOT + self.get_slots_since_genesis()?)
     }
 
+    /// Return the number of slots since genesis, or 0 while ``store.time`` is before genesis.
     pub fn get_slots_since_genesis(&self) -> anyhow::Result<u64> {
-        Ok(
-            (self.db.time_provider().get()? - self.db.genesis_time_provider().get()?)
-                / SECONDS_PER_SLOT,
-        )
+        Ok(self
+            .db
+            .time_provider()
+            .get()?
+            .saturating_sub(self.db.genesis_time_provider().get()?)
+            / SECONDS_PER_SLOT)
     }
 
+    /// Return the root of the ancestor of block ``root`` at ``slot``, or at the latest slot
+    /// before it if ``slot`` was skipped. A block at or before ``slot`` is its own ancestor.
     pub fn get_ancestor(&self, root: B256, slot: u64) -> anyhow::Result<B256> {
-        let block = self
-            .db
-            .beacon_block_provider()
-            .get(root)?
-            .ok_or(anyhow!("Failed to find beacon_block_provider()"))?
-            .message;
-        if block.slot > slot {
-            self.get_ancestor(block.parent_root, slot)
-        } else {
-            Ok(root)
+        let beacon_block_provider = self.db.beacon_block_provider();
+        let mut root = root;
+        loop {
+            let block = beacon_block_provider
+                .get(root)?
+                .ok_or_else(|| anyhow!("Failed to find beacon block {root}"))?
+                .message;
+            if block.slot <= slot {
+                return Ok(root);
+            }
+            root = block.parent_root;
         }
     }
 
@@ -85,6 +109,99 @@ impl Store {
         self.get_ancestor(root, epoch_first_slot)
     }
 
+    /// Return the latest block in the chains of both ``first`` and ``second``.
+    pub fn get_common_ancestor(&self, first: B256, second: B256) -> anyhow::Result<B256> {
+        let mut slot = min(self.get_block(first)?.slot, self.get_block(second)?.slot);
+        loop {
+            let first_ancestor = self.get_ancestor(first, slot)?;
+            let second_ancestor = self.get_ancestor(second, slot)?;
+            if first_ancestor == second_ancestor {
+                return Ok(first_ancestor);
+            }
+
+            let first_slot = self.get_block(first_ancestor)?.slot;
+            let second_slot = self.get_block(second_ancestor)?.slot;
+            slot = match first_slot.cmp(&second_slot) {
+                // Distinct blocks at the same slot, the common ancestor is older than both
+                Ordering::Equal => first_slot.checked_sub(1).ok_or_else(|| {
+                    anyhow!("Blocks {first} and {second} have no common ancestor")
+                })?,
+                _ => min(first_slot, second_slot),
+            };
+        }
+    }
+
+    /// Emit a ``chain_reorg`` event on ``event_sender`` if ``new_head`` does not descend from
+    /// ``old_head``. The depth of the reorg is the number of blocks from ``old_head`` back to the
+    /// common ancestor of both heads, a head that extends ``old_head`` is not a reorg.
+    pub fn on_head_update(
+        &self,
+        old_head: B256,
+        new_head: B256,
+        event_sender: &broadcast::Sender<BeaconEvent>,
+    ) -> anyhow::Result<()> {
+        let common_ancestor = self.get_common_ancestor(old_head, new_head)?;
+        if common_ancestor == old_head {
+            return Ok(());
+        }
+
+        let mut depth = 0;
+        let mut root = old_head;
+        while root != common_ancestor {
+            root = self.get_block(root)?.parent_root;
+            depth += 1;
+        }
+
+        let old_head_block = self.get_block(old_head)?;
+        let new_head_block = self.get_block(new_head)?;
+        let execution_optimistic = self.db.is_optimistic(new_head)?;
+        // Sending only fails without subscribers, in which case nobody misses the event
+        let _ = event_sender.send(BeaconEvent::ChainReorg(ChainReorgEvent {
+            slot: new_head_block.slot,
+            depth,
+            old_head_block: old_head,
+            new_head_block: new_head,
+            old_head_state: old_head_block.state_root,
+            new_head_state: new_head_block.state_root,
+            epoch: compute_epoch_at_slot(new_head_block.slot),
+            execution_optimistic,
+        }));
+
+        Ok(())
+    }
+
+    /// Run fork choice, point the slot index at the chain of the new head and emit a
+    /// ``chain_reorg`` event on ``event_sender`` if the new head does not descend from
+    /// ``old_head``, see [`Store::on_head_update`]. Returns the new head.
+    pub fn update_head(
+        &self,
+        old_head: Option<B256>,
+        event_sender: &broadcast::Sender<BeaconEvent>,
+    ) -> anyhow::Result<B256> {
+        let new_head = self.get_head()?;
+        self.db.update_slot_index(new_head)?;
+        if let Some(old_head) = old_head {
+            self.on_head_update(old_head, new_head, event_sender)?;
+        }
+        Ok(new_head)
+    }
+
+    fn get_block(&self, root: B256) -> anyhow::Result<BeaconBlock> {
+        Ok(self
+            .db
+            .beacon_block_provider()
+            .get(root)?
+            .ok_or_else(|| anyhow!("Failed to find beacon block {root}"))?
+            .message)
+    }
+
+    /// Check whether block ``root`` is the finalized checkpoint block or one of its descendants.
+    pub fn is_finalized_descendant(&self, root: B256) -> anyhow::Result<bool> {
+        let finalized_checkpoint = self.db.finalized_checkpoint_provider().get()?;
+        Ok(self.get_checkpoint_block(root, finalized_checkpoint.epoch)?
+            == finalized_checkpoint.root)
+    }
+
     pub fn filter_block_tree(
         &self,
         block_root: B256,
@@ -126,12 +243,9 @@ impl Store {
                 || voting_source.epoch + 2 >= current_epoch
         };
 
-        let finalized_checkpoint = self.db.finalized_checkpoint_provider().get()?;
-        let finalized_checkpoint_block =
-            self.get_checkpoint_block(block_root, finalized_checkpoint.epoch)?;
-
-        let correct_finalized = finalized_checkpoint.epoch == GENESIS_EPOCH
-            || finalized_checkpoint.root == finalized_checkpoint_block;
+        let correct_finalized = self.db.finalized_checkpoint_provider().get()?.epoch
+            == GENESIS_EPOCH
+            || self.is_finalized_descendant(block_root)?;
 
         // If expected finalized/justified, add to viable block-tree and signal viability to parent.
         if correct_justified && correct_finalized {
@@ -143,6 +257,49 @@ impl Store {
         Ok(false)
     }
 
+    /// Scan the blocks descending from the finalized checkpoint and return a ``ProposerSlashing``
+    /// for every block that shares its ``(slot, proposer_index)`` with an earlier-seen block.
+    pub fn detect_proposer_equivocations(&self) -> anyhow::Result<Vec<ProposerSlashing>> {
+        let mut proposals: HashMap<(u64, u64), Vec<SignedBeaconBlock>> = HashMap::new();
+        let mut pending_roots = vec![self.db.finalized_checkpoint_provider().get()?.root];
+        while let Some(block_root) = pending_roots.pop() {
+            let block = self
+                .db
+                .beacon_block_provider()
+                .get(block_root)?
+                .ok_or_else(|| anyhow!("beacon_block not found"))?;
+            proposals
+                .entry((block.message.slot, block.message.proposer_index))
+                .or_default()
+                .push(block);
+            pending_roots.extend(
+                self.db
+                    .parent_root_index_multimap_provider()
+                    .get(block_root)?
+                    .unwrap_or_default(),
+            );
+        }
+
+        let mut proposer_slashings = vec![];
+        for blocks in proposals.into_values() {
+            let Some((first_block, other_blocks)) = blocks.split_first() else {
+                continue;
+            };
+            for block in other_blocks {
+                proposer_slashings.push(ProposerSlashing {
+                    signed_header_1: first_block.signed_header(),
+                    signed_header_2: block.signed_header(),
+                });
+            }
+        }
+        proposer_slashings.sort_by_key(|proposer_slashing| {
+            let header = &proposer_slashing.signed_header_2.message;
+            (header.slot, header.proposer_index, header.tree_hash_root())
+        });
+
+        Ok(proposer_slashings)
+    }
+
     /// Retrieve a filtered block tree from ``store``, only returning branches
     /// whose leaf state's justified/finalized info agrees with that in ``store``.
     pub fn get_filtered_block_tree(&self) -> anyhow::Result<HashMap<B256, BeaconBlock>> {
@@ -198,27 +355,49 @@ impl Store {
         justified_checkpoint: Checkpoint,
         finalized_checkpoint: Checkpoint,
     ) -> anyhow::Result<()> {
+        let is_finalized_updated = self.db.write_batch(|batch| {
+            self.stage_checkpoints(batch, justified_checkpoint, finalized_checkpoint)
+        })?;
+        if is_finalized_updated {
+            self.clean_operation_pool(finalized_checkpoint)?;
+        }
+
+        Ok(())
+    }
+
+    /// Add the checkpoint updates of [`Store::update_checkpoints`] to ``batch``, returning whether
+    /// the finalized checkpoint changed. The operation pool is not cleaned until the batch is
+    /// committed, see [`Store::clean_operation_pool`].
+    pub fn stage_checkpoints(
+        &self,
+        batch: &WriteBatch,
+        justified_checkpoint: Checkpoint,
+        finalized_checkpoint: Checkpoint,
+    ) -> Result<bool, StoreError> {
         // Update justified checkpoint
         if justified_checkpoint.epoch > self.db.justified_checkpoint_provider().get()?.epoch {
-            self.db
-                .justified_checkpoint_provider()
-                .insert(justified_checkpoint)?;
+            batch.insert_justified_checkpoint(justified_checkpoint)?;
         }
 
         // Update finalized checkpoint
-        if finalized_checkpoint.epoch > self.db.finalized_checkpoint_provider().get()?.epoch {
-            self.db
-                .finalized_checkpoint_provider()
-                .insert(finalized_checkpoint)?;
-            // Clean operation pool
-            if let Some(beacon_state) = self
-                .db
-                .beacon_state_provider()
-                .get(finalized_checkpoint.root)?
-            {
-                self.operation_pool
-                    .clean_signed_voluntary_exits(&beacon_state);
-            }
+        let is_finalized_updated =
+            finalized_checkpoint.epoch > self.db.finalized_checkpoint_provider().get()?.epoch;
+        if is_finalized_updated {
+            batch.insert_finalized_checkpoint(finalized_checkpoint)?;
+        }
+
+        Ok(is_finalized_updated)
+    }
+
+    /// Remove operations from the pool that are stale as of ``finalized_checkpoint``.
+    pub fn clean_operation_pool(&self, finalized_checkpoint: Checkpoint) -> anyhow::Result<()> {
+        if let Some(beacon_state) = self
+            .db
+            .beacon_state_provider()
+            .get(finalized_checkpoint.root)?
+        {
+            self.operation_pool
+                .clean_signed_voluntary_exits(&beacon_state);
         }
 
         Ok(())
@@ -306,6 +485,31 @@ impl Store {
         Ok((committee_weight * PROPOSER_SCORE_BOOST) / 100)
     }
 
+    /// Return the indices of validators whose attestations were proven slashable. An equivocating
+    /// validator's latest message no longer contributes to fork choice weight.
+    pub fn get_equivocating_indices(&self) -> anyhow::Result<HashSet<u64>> {
+        match self.db.equivocating_indices_provider().get() {
+            Ok(equivocating_indices) => Ok(equivocating_indices),
+            Err(StoreError::FieldNotInitilized) => Ok(HashSet::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Return the root of the timely block boosted in the current slot, if any. The boost is
+    /// stored as ``B256::ZERO`` when unset and is cleared at every slot boundary by
+    /// ``on_tick_per_slot``.
+    pub fn get_proposer_boost_root(&self) -> anyhow::Result<Option<B256>> {
+        let proposer_boost_root = self.db.proposer_boost_root_provider().get()?;
+        Ok((proposer_boost_root != B256::ZERO).then_some(proposer_boost_root))
+    }
+
+    /// Clear ``store.proposer_boost_root``, which only applies within the slot of the boosted
+    /// block.
+    pub fn reset_proposer_boost(&self) -> anyhow::Result<()> {
+        self.db.proposer_boost_root_provider().insert(B256::ZERO)?;
+        Ok(())
+    }
+
     pub fn get_weight(&self, root: B256) -> anyhow::Result<u64> {
         let state = &self
             .db
@@ -319,14 +523,11 @@ impl Store {
             .filter(|&i| !state.validators[i as usize].slashed)
             .collect();
 
+        let equivocating_indices = self.get_equivocating_indices()?;
         let mut attestation_score: u64 = 0;
         for index in unslashed_and_active_indices {
             if self.db.latest_messages_provider().get(index)?.is_some()
-                && !self
-                    .db
-                    .equivocating_indices_provider()
-                    .get()?
-                    .contains(&index)
+                && !equivocating_indices.contains(&index)
                 && self.get_ancestor(
                     self.db
                         .latest_messages_provider()
@@ -345,16 +546,16 @@ impl Store {
             }
         }
 
-        if self.db.proposer_boost_root_provider().get()? == B256::ZERO {
+        let Some(proposer_boost_root) = self.get_proposer_boost_root()? else {
             // Return only attestation score if ``proposer_boost_root`` is not set
             return Ok(attestation_score);
-        }
+        };
 
         // Calculate proposer score if ``proposer_boost_root`` is set
         let mut proposer_score: u64 = 0;
         // Boost is applied if ``root`` is an ancestor of ``proposer_boost_root``
         if self.get_ancestor(
-            self.db.proposer_boost_root_provider().get()?,
+            proposer_boost_root,
             self.db
                 .beacon_block_provider()
                 .get(root)?
@@ -493,11 +694,7 @@ impl Store {
         let beacon_block_root = attestation.data.beacon_block_root;
         let mut non_equivocating_attesting_indices = vec![];
 
-        let equivocating = self
-            .db
-            .equivocating_indices_provider()
-            .get()
-            .unwrap_or_default();
+        let equivocating = self.get_equivocating_indices()?;
 
         for &index in &attesting_indices {
             if !equivocating.contains(&index) {
@@ -540,7 +737,8 @@ impl Store {
 
         // If this is a new slot, reset store.proposer_boost_root
         if current_slot > previous_slot {
-            self.db.proposer_boost_root_provider().insert(B256::ZERO)?;
+            self.reset_proposer_boost()?;
+            self.operation_pool.prune(current_slot);
         }
 
         // If a new epoch, pull-up justification and finalization from previous epoch
@@ -795,6 +993,8 @@ pub fn get_forkchoice_store(
         .insert(finalized_checkpoint)?;
     db.proposer_boost_root_provider()
         .insert(proposer_boost_root)?;
+    db.equivocating_indices_provider()
+        .insert(HashSet::default())?;
     db.beacon_block_provider()
         .insert(anchor_root, signed_anchor_block)?;
     db.beacon_state_provider()
@@ -816,3 +1016,39 @@ pub fn get_forkchoice_store(
 pub fn compute_slots_since_epoch_start(slot: u64) -> u64 {
     slot - compute_start_slot_at_epoch(compute_epoch_at_slot(slot))
 }
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::handlers::on_tick;
+
+    #[test]
+    fn test_current_slot_is_clamped_before_genesis() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_current_slot_is_clamped_before_genesis")?;
+        let mut store = Store::new(
+            ReamDB::new(tmp_dir.path().to_path_buf())?,
+            Arc::new(OperationPool::default()),
+        );
+        let genesis_time = 1_606_824_023;
+        store.db.genesis_time_provider().insert(genesis_time)?;
+        store
+            .db
+            .time_provider()
+            .insert(genesis_time - 5 * SECONDS_PER_SLOT)?;
+        assert_eq!(store.get_slots_since_genesis()?, 0);
+        assert_eq!(store.get_current_slot()?, GENESIS_SLOT);
+
+        on_tick(&mut store, genesis_time - 1)?;
+        assert_eq!(store.get_current_slot()?, GENESIS_SLOT);
+
+        // Ticks carry on from the genesis slot once genesis has passed
+        on_tick(&mut store, genesis_time)?;
+        assert_eq!(store.get_current_slot()?, GENESIS_SLOT);
+        on_tick(&mut store, genesis_time + SECONDS_PER_SLOT)?;
+        assert_eq!(store.get_current_slot()?, GENESIS_SLOT + 1);
+
+        Ok(())
+    }
+}
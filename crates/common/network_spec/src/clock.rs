@@ -0,0 +1,148 @@
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use ream_consensus::{constants::INTERVALS_PER_SLOT, misc::compute_epoch_at_slot};
+
+use crate::networks::NetworkSpec;
+
+/// Returns the current time as a duration since the Unix epoch.
+pub type TimeSource = Arc<dyn Fn() -> Duration + Send + Sync>;
+
+/// Wall-clock slot and epoch, derived from genesis time and `seconds_per_slot`.
+///
+/// Before genesis the clock reports the genesis slot.
+#[derive(Clone)]
+pub struct Clock {
+    genesis_time: u64,
+    seconds_per_slot: u64,
+    now: TimeSource,
+}
+
+impl Clock {
+    /// Creates a clock that reads the system time.
+    pub fn new(genesis_time: u64, seconds_per_slot: u64) -> Self {
+        Self::with_time_source(
+            genesis_time,
+            seconds_per_slot,
+            Arc::new(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+            }),
+        )
+    }
+
+    /// Creates a clock that reads the time from `now`, so tests can pin it.
+    pub fn with_time_source(genesis_time: u64, seconds_per_slot: u64, now: TimeSource) -> Self {
+        Self {
+            genesis_time,
+            seconds_per_slot,
+            now,
+        }
+    }
+
+    /// Creates a system time clock for `network_spec`, using `min_genesis_time` as genesis time.
+    pub fn from_network_spec(network_spec: &NetworkSpec) -> Self {
+        Self::new(network_spec.min_genesis_time, network_spec.seconds_per_slot)
+    }
+
+    pub fn genesis_time(&self) -> u64 {
+        self.genesis_time
+    }
+
+    pub fn current_slot(&self) -> u64 {
+        self.since_genesis().as_secs() / self.seconds_per_slot
+    }
+
+    pub fn current_epoch(&self) -> u64 {
+        compute_epoch_at_slot(self.current_slot())
+    }
+
+    /// Returns the Unix time in seconds at which `slot` starts.
+    pub fn slot_start_time(&self, slot: u64) -> u64 {
+        self.genesis_time + slot * self.seconds_per_slot
+    }
+
+    /// Returns whether the current time is in the first of the `INTERVALS_PER_SLOT` intervals of
+    /// its slot, the window in which blocks are expected to arrive.
+    pub fn is_within_first_third_of_slot(&self) -> bool {
+        if (self.now)() < Duration::from_secs(self.genesis_time) {
+            return false;
+        }
+
+        let slot_duration = Duration::from_secs(self.seconds_per_slot);
+        let time_into_slot = self.since_genesis().as_millis() % slot_duration.as_millis();
+        time_into_slot < slot_duration.as_millis() / INTERVALS_PER_SLOT as u128
+    }
+
+    fn since_genesis(&self) -> Duration {
+        (self.now)().saturating_sub(Duration::from_secs(self.genesis_time))
+    }
+}
+
+impl fmt::Debug for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Clock")
+            .field("genesis_time", &self.genesis_time)
+            .field("seconds_per_slot", &self.seconds_per_slot)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus::constants::SLOTS_PER_EPOCH;
+
+    use super::*;
+
+    const GENESIS_TIME: u64 = 1_606_824_023;
+
+    fn clock_at(now: Duration) -> Clock {
+        Clock::with_time_source(GENESIS_TIME, 12, Arc::new(move || now))
+    }
+
+    #[test]
+    fn test_slot_and_epoch_boundaries() {
+        let clock = clock_at(Duration::from_secs(GENESIS_TIME));
+        assert_eq!(clock.current_slot(), 0);
+        assert_eq!(clock.current_epoch(), 0);
+
+        let clock = clock_at(Duration::from_secs(GENESIS_TIME + 11));
+        assert_eq!(clock.current_slot(), 0);
+
+        let clock = clock_at(Duration::from_secs(GENESIS_TIME + 12));
+        assert_eq!(clock.current_slot(), 1);
+
+        let epoch_start = GENESIS_TIME + 12 * SLOTS_PER_EPOCH;
+        let clock = clock_at(Duration::from_secs(epoch_start - 1));
+        assert_eq!(clock.current_slot(), SLOTS_PER_EPOCH - 1);
+        assert_eq!(clock.current_epoch(), 0);
+
+        let clock = clock_at(Duration::from_secs(epoch_start));
+        assert_eq!(clock.current_slot(), SLOTS_PER_EPOCH);
+        assert_eq!(clock.current_epoch(), 1);
+        assert_eq!(clock.slot_start_time(SLOTS_PER_EPOCH), epoch_start);
+    }
+
+    #[test]
+    fn test_before_genesis() {
+        let clock = clock_at(Duration::from_secs(GENESIS_TIME - 100));
+        assert_eq!(clock.current_slot(), 0);
+        assert_eq!(clock.current_epoch(), 0);
+        assert!(!clock.is_within_first_third_of_slot());
+    }
+
+    #[test]
+    fn test_is_within_first_third_of_slot() {
+        let slot_start = Duration::from_secs(GENESIS_TIME + 12 * 5);
+        assert!(clock_at(slot_start).is_within_first_third_of_slot());
+        assert!(
+            clock_at(slot_start + Duration::from_millis(3_999)).is_within_first_third_of_slot()
+        );
+        assert!(!clock_at(slot_start + Duration::from_secs(4)).is_within_first_third_of_slot());
+        assert!(!clock_at(slot_start + Duration::from_secs(11)).is_within_first_third_of_slot());
+    }
+}
@@ -1,8 +1,49 @@
-use std::slice::Iter;
+use std::{fmt, slice::Iter};
 
 use ream_consensus::fork::Fork;
 use serde::{Deserialize, Serialize};
 
+/// Consensus forks in schedule order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForkName {
+    Phase0,
+    Altair,
+    Bellatrix,
+    Capella,
+    Deneb,
+    Electra,
+}
+
+impl ForkName {
+    pub const ALL: [ForkName; ForkSchedule::TOTAL] = [
+        ForkName::Phase0,
+        ForkName::Altair,
+        ForkName::Bellatrix,
+        ForkName::Capella,
+        ForkName::Deneb,
+        ForkName::Electra,
+    ];
+
+    /// Name of the fork as used by the Beacon API ``version`` field.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ForkName::Phase0 => "phase0",
+            ForkName::Altair => "altair",
+            ForkName::Bellatrix => "bellatrix",
+            ForkName::Capella => "capella",
+            ForkName::Deneb => "deneb",
+            ForkName::Electra => "electra",
+        }
+    }
+}
+
+impl fmt::Display for ForkName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ForkSchedule(pub [Fork; ForkSchedule::TOTAL]);
 
@@ -21,4 +62,170 @@ impl ForkSchedule {
         self.iter()
             .filter(|fork| fork.epoch != Fork::UNSCHEDULED_EPOCH)
     }
+
+    /// The latest fork activated at or before ``epoch`` together with its name.
+    pub fn fork_at_epoch(&self, epoch: u64) -> (&Fork, ForkName) {
+        self.iter()
+            .zip(ForkName::ALL)
+            .filter(|(fork, _)| fork.epoch <= epoch)
+            .last()
+            .unwrap_or((&self.0[0], ForkName::Phase0))
+    }
+
+    /// Name of the latest fork activated at or before ``epoch``.
+    pub fn fork_name_at_epoch(&self, epoch: u64) -> ForkName {
+        self.fork_at_epoch(epoch).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::fixed_bytes;
+    use ream_consensus::{constants::SLOTS_PER_EPOCH, fork::Fork};
+
+    use super::{ForkName, ForkSchedule};
+    use crate::networks::{MAINNET, Network, NetworkSpec};
+
+    #[test]
+    fn test_fork_name_as_str() {
+        let names = ForkName::ALL.map(|fork_name| fork_name.as_str());
+        assert_eq!(
+            names,
+            [
+                "phase0",
+                "altair",
+                "bellatrix",
+                "capella",
+                "deneb",
+                "electra"
+            ]
+        );
+
+        // The Beacon API name matches both `Display` and the serde name
+        for fork_name in ForkName::ALL {
+            assert_eq!(fork_name.to_string(), fork_name.as_str());
+            assert_eq!(
+                serde_yaml::to_string(&fork_name).unwrap().trim_end(),
+                fork_name.as_str()
+            );
+        }
+    }
+
+    #[test]
+    fn test_fork_name_at_epoch() {
+        let fork_schedule = MAINNET.fork_schedule();
+        assert_eq!(fork_schedule.fork_name_at_epoch(0), ForkName::Phase0);
+        assert_eq!(
+            fork_schedule.fork_name_at_epoch(MAINNET.altair_fork_epoch),
+            ForkName::Altair
+        );
+        assert_eq!(
+            fork_schedule.fork_name_at_epoch(MAINNET.electra_fork_epoch - 1),
+            ForkName::Deneb
+        );
+        assert_eq!(
+            fork_schedule.fork_name_at_epoch(MAINNET.electra_fork_epoch),
+            ForkName::Electra
+        );
+        assert_eq!(
+            fork_schedule.fork_name_at_epoch(u64::MAX - 1),
+            ForkName::Electra
+        );
+
+        // Forks activated at the same epoch resolve to the latest of them
+        let mut forks = MAINNET.fork_schedule().0;
+        for fork in forks.iter_mut() {
+            fork.epoch = 0;
+        }
+        assert_eq!(
+            ForkSchedule::new(forks).fork_name_at_epoch(0),
+            ForkName::Electra
+        );
+
+        // Unscheduled forks are never active
+        let mut forks = MAINNET.fork_schedule().0;
+        forks[5].epoch = Fork::UNSCHEDULED_EPOCH;
+        assert_eq!(
+            ForkSchedule::new(forks).fork_name_at_epoch(MAINNET.electra_fork_epoch),
+            ForkName::Deneb
+        );
+    }
+
+    #[test]
+    fn test_mainnet_fork_version_at_epoch() {
+        // Capella to Deneb and Deneb to Electra
+        for (epoch, version, name) in [
+            (
+                MAINNET.deneb_fork_epoch - 1,
+                fixed_bytes!("0x03000000"),
+                ForkName::Capella,
+            ),
+            (
+                MAINNET.deneb_fork_epoch,
+                fixed_bytes!("0x04000000"),
+                ForkName::Deneb,
+            ),
+            (
+                MAINNET.electra_fork_epoch - 1,
+                fixed_bytes!("0x04000000"),
+                ForkName::Deneb,
+            ),
+            (
+                MAINNET.electra_fork_epoch,
+                fixed_bytes!("0x05000000"),
+                ForkName::Electra,
+            ),
+        ] {
+            assert_eq!(MAINNET.fork_version_at_epoch(epoch), version);
+            assert_eq!(MAINNET.fork_name_at_epoch(epoch), name);
+        }
+
+        let electra_slot = MAINNET.electra_fork_epoch * SLOTS_PER_EPOCH;
+        assert_eq!(MAINNET.fork_name_at_slot(electra_slot - 1), ForkName::Deneb);
+        assert_eq!(MAINNET.fork_name_at_slot(electra_slot), ForkName::Electra);
+    }
+
+    #[test]
+    fn test_custom_testnet_fork_version_at_epoch() {
+        let testnet = NetworkSpec {
+            network: Network::Custom("testnet".to_string()),
+            genesis_fork_version: fixed_bytes!("0x10000038"),
+            altair_fork_version: fixed_bytes!("0x20000038"),
+            altair_fork_epoch: 0,
+            bellatrix_fork_version: fixed_bytes!("0x30000038"),
+            bellatrix_fork_epoch: 0,
+            capella_fork_version: fixed_bytes!("0x40000038"),
+            capella_fork_epoch: 0,
+            deneb_fork_version: fixed_bytes!("0x50000038"),
+            deneb_fork_epoch: 10,
+            electra_fork_version: fixed_bytes!("0x60000038"),
+            electra_fork_epoch: 20,
+            ..(**MAINNET).clone()
+        };
+
+        // Forks at genesis resolve to the latest of them
+        assert_eq!(testnet.fork_version_at_epoch(0), fixed_bytes!("0x40000038"));
+        assert_eq!(testnet.fork_name_at_slot(0), ForkName::Capella);
+        assert_eq!(testnet.fork_version_at_epoch(9), fixed_bytes!("0x40000038"));
+        assert_eq!(
+            testnet.fork_version_at_epoch(10),
+            fixed_bytes!("0x50000038")
+        );
+        assert_eq!(
+            testnet.fork_version_at_epoch(19),
+            fixed_bytes!("0x50000038")
+        );
+        assert_eq!(
+            testnet.fork_version_at_epoch(20),
+            fixed_bytes!("0x60000038")
+        );
+        assert_eq!(
+            testnet.fork_name_at_slot(20 * SLOTS_PER_EPOCH - 1),
+            ForkName::Deneb
+        );
+        assert_eq!(
+            testnet.fork_name_at_slot(20 * SLOTS_PER_EPOCH),
+            ForkName::Electra
+        );
+    }
 }
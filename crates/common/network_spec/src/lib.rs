@@ -1,4 +1,5 @@
 pub mod b32_hex;
 pub mod cli;
+pub mod clock;
 pub mod fork_schedule;
 pub mod networks;
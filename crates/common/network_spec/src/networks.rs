@@ -4,10 +4,13 @@ use std::{
 };
 
 use alloy_primitives::{Address, B256, U256, address, aliases::B32, b256, fixed_bytes};
-use ream_consensus::{fork::Fork, fork_data::ForkData, misc::checksummed_address};
+use ream_consensus::{
+    fork::Fork,
+    misc::{checksummed_address, compute_epoch_at_slot, compute_fork_digest},
+};
 use serde::Deserialize;
 
-use crate::fork_schedule::ForkSchedule;
+use crate::fork_schedule::{ForkName, ForkSchedule};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Network {
@@ -156,12 +159,29 @@ pub struct NetworkSpec {
 }
 
 impl NetworkSpec {
+    /// The fork digest of the current fork, Electra, on the chain with `genesis_validators_root`.
     pub fn fork_digest(&self, genesis_validators_root: B256) -> B32 {
-        ForkData {
-            current_version: self.electra_fork_version,
-            genesis_validators_root,
+        compute_fork_digest(self.electra_fork_version, genesis_validators_root)
+    }
+
+    /// The `genesis_validators_root` of the public networks, which the stored genesis state is
+    /// checked against. Dev and custom networks have no fixed genesis, so they return `None`.
+    pub fn genesis_validators_root(&self) -> Option<B256> {
+        match self.network {
+            Network::Mainnet => Some(b256!(
+                "0x4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95"
+            )),
+            Network::Holesky => Some(b256!(
+                "0x9143aa7c615a7f7115e2b6aac319c03529df8242ae705fba9df39b79c59fa8b1"
+            )),
+            Network::Sepolia => Some(b256!(
+                "0xd8ea171f3c94aea21ebc42a1ed61052acf3f9209c00e4efbaaddac09ed9b8078"
+            )),
+            Network::Hoodi => Some(b256!(
+                "0x212f13fc4df078b6cb7db228f1c8307566dcecf900867401a92023d7ba99cb5f"
+            )),
+            Network::Dev | Network::Custom(_) => None,
         }
-        .compute_fork_digest()
     }
 
     pub fn fork_schedule(&self) -> ForkSchedule {
@@ -198,6 +218,21 @@ impl NetworkSpec {
             },
         ])
     }
+
+    /// The version of the latest fork activated at or before `epoch`.
+    pub fn fork_version_at_epoch(&self, epoch: u64) -> B32 {
+        self.fork_schedule().fork_at_epoch(epoch).0.current_version
+    }
+
+    /// The latest fork activated at or before `epoch`.
+    pub fn fork_name_at_epoch(&self, epoch: u64) -> ForkName {
+        self.fork_schedule().fork_name_at_epoch(epoch)
+    }
+
+    /// The fork active at `slot`.
+    pub fn fork_name_at_slot(&self, slot: u64) -> ForkName {
+        self.fork_name_at_epoch(compute_epoch_at_slot(slot))
+    }
 }
 
 pub static MAINNET: LazyLock<Arc<NetworkSpec>> = LazyLock::new(|| {
@@ -1,14 +1,166 @@
-use std::collections::HashMap;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+};
 
+use alloy_primitives::B256;
+use anyhow::{bail, ensure};
 use parking_lot::RwLock;
-use ream_consensus::{electra::beacon_state::BeaconState, voluntary_exit::SignedVoluntaryExit};
+use ream_bls::{BLSSignature, traits::Aggregatable};
+use ream_consensus::{
+    attestation::Attestation,
+    attester_slashing::AttesterSlashing,
+    bls_to_execution_change::SignedBLSToExecutionChange,
+    constants::{FAR_FUTURE_EPOCH, MIN_ATTESTATION_INCLUSION_DELAY},
+    electra::beacon_state::BeaconState,
+    misc::compute_epoch_at_slot,
+    voluntary_exit::SignedVoluntaryExit,
+};
+use tree_hash::TreeHash;
 
+/// Reason an operation was rejected before entering the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectionReason {
+    /// The operation signature did not verify.
+    InvalidSignature,
+    /// The voluntary exit is for a validator that has already initiated an exit.
+    StaleVoluntaryExit,
+    /// The operation failed any other validation check.
+    InvalidOperation,
+}
+
+/// Number of rejected operations per [`RejectionReason`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RejectionStats {
+    pub invalid_signature: u64,
+    pub stale_voluntary_exit: u64,
+    pub invalid_operation: u64,
+}
+
+/// Default maximum number of attestations held in the pool.
+pub const DEFAULT_MAX_ATTESTATIONS: usize = 16_384;
+
+/// Default number of slots an attestation is kept after its slot, the mainnet
+/// ``ATTESTATION_PROPAGATION_SLOT_RANGE``.
+pub const DEFAULT_ATTESTATION_PROPAGATION_SLOT_RANGE: u64 = 32;
+
+/// Pooled attestations indexed by the tree hash root of their ``data``, so the attestations that
+/// can be aggregated with each other are found without scanning the pool.
 #[derive(Debug, Default)]
+struct AttestationIndex {
+    by_data_root: HashMap<B256, Vec<Attestation>>,
+    /// Data roots of the attestations of each slot, in insertion order.
+    data_roots_by_slot: BTreeMap<u64, Vec<B256>>,
+    len: usize,
+}
+
+impl AttestationIndex {
+    fn with_data_root(&self, data_root: B256) -> &[Attestation] {
+        self.by_data_root
+            .get(&data_root)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The attestations for ``slot``, grouped by data root.
+    fn at_slot(&self, slot: u64) -> impl Iterator<Item = &[Attestation]> {
+        self.data_roots_by_slot
+            .get(&slot)
+            .into_iter()
+            .flatten()
+            .map(|&data_root| self.with_data_root(data_root))
+    }
+
+    /// All attestations, oldest slot first.
+    fn iter(&self) -> impl Iterator<Item = &Attestation> {
+        self.data_roots_by_slot
+            .keys()
+            .flat_map(|&slot| self.at_slot(slot))
+            .flatten()
+    }
+
+    fn insert(&mut self, data_root: B256, attestation: Attestation) {
+        let attestations = self.by_data_root.entry(data_root).or_default();
+        if attestations.is_empty() {
+            self.data_roots_by_slot
+                .entry(attestation.data.slot)
+                .or_default()
+                .push(data_root);
+        }
+        attestations.push(attestation);
+        self.len += 1;
+    }
+
+    /// Remove the first attestation inserted for the oldest slot.
+    fn remove_oldest(&mut self) {
+        let Some(mut oldest_slot) = self.data_roots_by_slot.first_entry() else {
+            return;
+        };
+        let data_root = oldest_slot.get()[0];
+        let attestations = self
+            .by_data_root
+            .get_mut(&data_root)
+            .expect("Indexed data roots have attestations");
+        attestations.remove(0);
+        self.len -= 1;
+        if attestations.is_empty() {
+            self.by_data_root.remove(&data_root);
+            oldest_slot.get_mut().remove(0);
+            if oldest_slot.get().is_empty() {
+                oldest_slot.remove();
+            }
+        }
+    }
+
+    /// Remove the attestations of every slot before ``slot``.
+    fn remove_before(&mut self, slot: u64) {
+        let kept = self.data_roots_by_slot.split_off(&slot);
+        for data_root in std::mem::replace(&mut self.data_roots_by_slot, kept)
+            .into_values()
+            .flatten()
+        {
+            if let Some(attestations) = self.by_data_root.remove(&data_root) {
+                self.len -= attestations.len();
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
 pub struct OperationPool {
     signed_voluntary_exits: RwLock<HashMap<u64, SignedVoluntaryExit>>,
+    attestations: RwLock<AttestationIndex>,
+    attester_slashings: RwLock<Vec<AttesterSlashing>>,
+    bls_to_execution_changes: RwLock<HashMap<u64, SignedBLSToExecutionChange>>,
+    rejection_stats: RwLock<RejectionStats>,
+    max_attestations: usize,
+    attestation_propagation_slot_range: u64,
+}
+
+impl Default for OperationPool {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_MAX_ATTESTATIONS,
+            DEFAULT_ATTESTATION_PROPAGATION_SLOT_RANGE,
+        )
+    }
 }
 
 impl OperationPool {
+    /// Create a pool holding at most ``max_attestations`` attestations, each pruned once it is
+    /// more than ``attestation_propagation_slot_range`` slots old.
+    pub fn new(max_attestations: usize, attestation_propagation_slot_range: u64) -> Self {
+        Self {
+            signed_voluntary_exits: RwLock::default(),
+            attestations: RwLock::default(),
+            attester_slashings: RwLock::default(),
+            bls_to_execution_changes: RwLock::default(),
+            rejection_stats: RwLock::default(),
+            max_attestations,
+            attestation_propagation_slot_range,
+        }
+    }
+
     pub fn insert_signed_voluntary_exit(&self, signed_voluntary_exit: SignedVoluntaryExit) {
         self.signed_voluntary_exits.write().insert(
             signed_voluntary_exit.message.validator_index,
@@ -16,6 +168,25 @@ impl OperationPool {
         );
     }
 
+    /// Validate ``signed_voluntary_exit`` against ``beacon_state`` and insert it into the pool,
+    /// recording the rejection reason if validation fails.
+    pub fn validate_and_insert_signed_voluntary_exit(
+        &self,
+        beacon_state: &BeaconState,
+        signed_voluntary_exit: SignedVoluntaryExit,
+    ) -> anyhow::Result<()> {
+        if let Err(err) = beacon_state.validate_voluntary_exit(&signed_voluntary_exit) {
+            self.record_rejection(voluntary_exit_rejection_reason(
+                beacon_state,
+                &signed_voluntary_exit,
+            ));
+            return Err(err);
+        }
+
+        self.insert_signed_voluntary_exit(signed_voluntary_exit);
+        Ok(())
+    }
+
     pub fn get_signed_voluntary_exits(&self) -> Vec<SignedVoluntaryExit> {
         self.signed_voluntary_exits
             .read()
@@ -32,4 +203,512 @@ impl OperationPool {
                     >= beacon_state.finalized_checkpoint.epoch
             });
     }
+
+    /// Insert ``attestation`` unless the pool already holds it. When the pool is full the
+    /// attestation with the oldest slot is evicted to make room.
+    pub fn insert_attestation(&self, attestation: Attestation) {
+        if self.max_attestations == 0 {
+            return;
+        }
+
+        let mut attestations = self.attestations.write();
+        let data_root = attestation.data.tree_hash_root();
+        if attestations
+            .with_data_root(data_root)
+            .contains(&attestation)
+        {
+            return;
+        }
+        if attestations.len >= self.max_attestations {
+            attestations.remove_oldest();
+        }
+        attestations.insert(data_root, attestation);
+    }
+
+    /// Drop attestations more than ``attestation_propagation_slot_range`` slots older than
+    /// ``current_slot``. Called once per slot.
+    pub fn prune(&self, current_slot: u64) {
+        self.attestations
+            .write()
+            .remove_before(current_slot.saturating_sub(self.attestation_propagation_slot_range));
+    }
+
+    /// Return all pooled attestations, oldest slot first.
+    pub fn get_attestations(&self) -> Vec<Attestation> {
+        self.attestations.read().iter().cloned().collect()
+    }
+
+    /// Return the pooled attestations for ``slot``, looked up through the slot index.
+    pub fn get_attestations_at_slot(&self, slot: u64) -> Vec<Attestation> {
+        self.attestations
+            .read()
+            .at_slot(slot)
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Return the pooled attestations whose ``data`` has the tree hash root ``data_root``, the
+    /// candidates for aggregating an attestation to that data.
+    pub fn get_attestations_by_data_root(&self, data_root: B256) -> Vec<Attestation> {
+        self.attestations.read().with_data_root(data_root).to_vec()
+    }
+
+    /// Return up to ``max`` pooled attestations that are valid for inclusion in a block built on
+    /// ``beacon_state``, preferring those that set the most new participation flags. Attestations
+    /// whose participation is already recorded in ``beacon_state`` are skipped.
+    pub fn best_attestations(&self, beacon_state: &BeaconState, max: usize) -> Vec<Attestation> {
+        let mut candidates = self
+            .attestations
+            .read()
+            .iter()
+            .filter_map(|attestation| {
+                let new_participation = get_new_participation(beacon_state, attestation).ok()?;
+                (new_participation > 0).then(|| (new_participation, attestation.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        // Stable sort, so equally good attestations keep their pool order
+        candidates.sort_by(|(a, _), (b, _)| b.cmp(a));
+        candidates
+            .into_iter()
+            .take(max)
+            .map(|(_, attestation)| attestation)
+            .collect()
+    }
+
+    /// Return up to ``max`` aggregates of the pooled attestations for ``slot``, most attesters
+    /// first. Attestations with the same ``data`` and ``committee_bits`` are merged by OR-ing their
+    /// ``aggregation_bits`` and aggregating their signatures, but only when their aggregation bits
+    /// are disjoint, so no attester is counted twice. Only attestations with the same data root
+    /// are compared with each other.
+    pub fn get_aggregate_attestations(&self, slot: u64, max: usize) -> Vec<Attestation> {
+        let mut aggregates: Vec<Attestation> = vec![];
+        for candidates in self.attestations.read().at_slot(slot) {
+            let first_aggregate = aggregates.len();
+            for attestation in candidates {
+                let merged = aggregates[first_aggregate..].iter_mut().any(|aggregate| {
+                    let Some(merged) = merge_attestations(aggregate, attestation) else {
+                        return false;
+                    };
+                    *aggregate = merged;
+                    true
+                });
+                if !merged {
+                    aggregates.push(attestation.clone());
+                }
+            }
+        }
+
+        // Stable sort, so equally good aggregates keep their insertion order
+        aggregates.sort_by_key(|aggregate| Reverse(aggregate.aggregation_bits.num_set_bits()));
+        aggregates.truncate(max);
+        aggregates
+    }
+
+    pub fn insert_attester_slashing(&self, attester_slashing: AttesterSlashing) {
+        self.attester_slashings.write().push(attester_slashing);
+    }
+
+    pub fn get_attester_slashings(&self) -> Vec<AttesterSlashing> {
+        self.attester_slashings.read().clone()
+    }
+
+    /// Validate ``attester_slashing`` against ``beacon_state`` and insert it into the pool,
+    /// recording the rejection reason if validation fails. A slashing is valid if its attestations
+    /// conflict, both are valid indexed attestations and at least one attester is slashable.
+    pub fn validate_and_insert_attester_slashing(
+        &self,
+        beacon_state: &BeaconState,
+        attester_slashing: AttesterSlashing,
+    ) -> anyhow::Result<()> {
+        if let Err(err) = validate_attester_slashing(beacon_state, &attester_slashing) {
+            self.record_rejection(attester_slashing_rejection_reason(
+                beacon_state,
+                &attester_slashing,
+            ));
+            return Err(err);
+        }
+
+        self.insert_attester_slashing(attester_slashing);
+        Ok(())
+    }
+
+    /// Validate ``signed_bls_to_execution_change`` against ``beacon_state`` and insert it into the
+    /// pool, replacing any earlier change for the same validator. Records the rejection reason if
+    /// validation fails.
+    pub fn validate_and_insert_bls_to_execution_change(
+        &self,
+        beacon_state: &BeaconState,
+        signed_bls_to_execution_change: SignedBLSToExecutionChange,
+    ) -> anyhow::Result<()> {
+        if let Err(err) =
+            beacon_state.validate_bls_to_execution_change(&signed_bls_to_execution_change)
+        {
+            let reason = match beacon_state
+                .verify_bls_to_execution_change_signature(&signed_bls_to_execution_change)
+            {
+                Ok(true) => RejectionReason::InvalidOperation,
+                Ok(false) | Err(_) => RejectionReason::InvalidSignature,
+            };
+            self.record_rejection(reason);
+            return Err(err);
+        }
+
+        self.bls_to_execution_changes.write().insert(
+            signed_bls_to_execution_change.message.validator_index,
+            signed_bls_to_execution_change,
+        );
+        Ok(())
+    }
+
+    pub fn get_bls_to_execution_changes(&self) -> Vec<SignedBLSToExecutionChange> {
+        self.bls_to_execution_changes
+            .read()
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    pub fn record_rejection(&self, reason: RejectionReason) {
+        let mut rejection_stats = self.rejection_stats.write();
+        match reason {
+            RejectionReason::InvalidSignature => rejection_stats.invalid_signature += 1,
+            RejectionReason::StaleVoluntaryExit => rejection_stats.stale_voluntary_exit += 1,
+            RejectionReason::InvalidOperation => rejection_stats.invalid_operation += 1,
+        }
+    }
+
+    pub fn rejection_stats(&self) -> RejectionStats {
+        *self.rejection_stats.read()
+    }
+}
+
+/// Return the number of participation flags ``attestation`` would newly set if it were included in
+/// a block built on ``beacon_state``, or an error if ``process_attestation`` would reject it.
+/// Signatures are not checked, pooled attestations are verified before insertion.
+fn get_new_participation(
+    beacon_state: &BeaconState,
+    attestation: &Attestation,
+) -> anyhow::Result<usize> {
+    let data = &attestation.data;
+    let (justified_checkpoint, epoch_participation) =
+        if data.target.epoch == beacon_state.get_current_epoch() {
+            (
+                &beacon_state.current_justified_checkpoint,
+                &beacon_state.current_epoch_participation,
+            )
+        } else if data.target.epoch == beacon_state.get_previous_epoch() {
+            (
+                &beacon_state.previous_justified_checkpoint,
+                &beacon_state.previous_epoch_participation,
+            )
+        } else {
+            bail!("Target epoch must be the previous or current epoch");
+        };
+
+    ensure!(
+        data.target.epoch == compute_epoch_at_slot(data.slot),
+        "Target epoch must match the computed epoch at slot"
+    );
+    ensure!(
+        data.slot + MIN_ATTESTATION_INCLUSION_DELAY <= beacon_state.slot,
+        "Attestation must be included after the minimum delay"
+    );
+    ensure!(
+        data.source == *justified_checkpoint,
+        "Source checkpoint must match the justified checkpoint"
+    );
+
+    let participation_flag_indices = beacon_state
+        .get_attestation_participation_flag_indices(data, beacon_state.slot - data.slot)?;
+    let attesting_indices = beacon_state.get_attesting_indices(attestation)?;
+
+    Ok(attesting_indices
+        .iter()
+        .filter_map(|&index| epoch_participation.get(index as usize))
+        .map(|&flags| {
+            participation_flag_indices
+                .iter()
+                .filter(|&&flag_index| !BeaconState::has_flag(flags, flag_index))
+                .count()
+        })
+        .sum())
+}
+
+/// Merge ``b`` into ``a``, or return ``None`` if they attest to different data or committees, or
+/// share an attester.
+fn merge_attestations(a: &Attestation, b: &Attestation) -> Option<Attestation> {
+    if a.data != b.data
+        || a.committee_bits != b.committee_bits
+        || a.aggregation_bits.len() != b.aggregation_bits.len()
+        || !a
+            .aggregation_bits
+            .intersection(&b.aggregation_bits)
+            .is_zero()
+    {
+        return None;
+    }
+
+    Some(Attestation {
+        aggregation_bits: a.aggregation_bits.union(&b.aggregation_bits),
+        data: a.data.clone(),
+        signature: BLSSignature::aggregate(&[&a.signature, &b.signature]).ok()?,
+        committee_bits: a.committee_bits.clone(),
+    })
+}
+
+fn validate_attester_slashing(
+    beacon_state: &BeaconState,
+    attester_slashing: &AttesterSlashing,
+) -> anyhow::Result<()> {
+    ensure!(
+        attester_slashing.is_slashable(),
+        "Attestations are neither a double nor a surround vote"
+    );
+    for indexed_attestation in [
+        &attester_slashing.attestation_1,
+        &attester_slashing.attestation_2,
+    ] {
+        ensure!(
+            beacon_state.is_valid_indexed_attestation(indexed_attestation)?,
+            "Invalid indexed attestation"
+        );
+    }
+
+    let attesting_indices_2 = attester_slashing
+        .attestation_2
+        .attesting_indices
+        .iter()
+        .collect::<HashSet<_>>();
+    let current_epoch = beacon_state.get_current_epoch();
+    let has_slashable_attester = attester_slashing
+        .attestation_1
+        .attesting_indices
+        .iter()
+        .filter(|index| attesting_indices_2.contains(index))
+        .any(|&index| {
+            beacon_state
+                .validators
+                .get(index as usize)
+                .is_some_and(|validator| validator.is_slashable_validator(current_epoch))
+        });
+    ensure!(has_slashable_attester, "No attester is slashable");
+    Ok(())
+}
+
+/// The reason ``attester_slashing`` failed [`validate_attester_slashing`]. Attestations that do not
+/// conflict are rejected as invalid regardless of their signatures.
+fn attester_slashing_rejection_reason(
+    beacon_state: &BeaconState,
+    attester_slashing: &AttesterSlashing,
+) -> RejectionReason {
+    if !attester_slashing.is_slashable() {
+        return RejectionReason::InvalidOperation;
+    }
+
+    for indexed_attestation in [
+        &attester_slashing.attestation_1,
+        &attester_slashing.attestation_2,
+    ] {
+        if !matches!(indexed_attestation.verify_signature(beacon_state), Ok(true)) {
+            return RejectionReason::InvalidSignature;
+        }
+    }
+    RejectionReason::InvalidOperation
+}
+
+fn voluntary_exit_rejection_reason(
+    beacon_state: &BeaconState,
+    signed_voluntary_exit: &SignedVoluntaryExit,
+) -> RejectionReason {
+    let Some(validator) = beacon_state
+        .validators
+        .get(signed_voluntary_exit.message.validator_index as usize)
+    else {
+        return RejectionReason::InvalidOperation;
+    };
+
+    if validator.exit_epoch != FAR_FUTURE_EPOCH {
+        return RejectionReason::StaleVoluntaryExit;
+    }
+
+    match beacon_state.verify_voluntary_exit_signature(signed_voluntary_exit) {
+        Ok(true) => RejectionReason::InvalidOperation,
+        Ok(false) | Err(_) => RejectionReason::InvalidSignature,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus::{attestation_data::AttestationData, checkpoint::Checkpoint};
+    use ssz_types::{BitList, BitVector};
+
+    use super::*;
+
+    fn attestation(slot: u64, attesters: &[usize]) -> Attestation {
+        let mut aggregation_bits = BitList::with_capacity(8).unwrap();
+        for &attester in attesters {
+            aggregation_bits.set(attester, true).unwrap();
+        }
+        Attestation {
+            aggregation_bits,
+            data: AttestationData {
+                slot,
+                index: 0,
+                beacon_block_root: B256::ZERO,
+                source: Checkpoint::default(),
+                target: Checkpoint::default(),
+            },
+            signature: BLSSignature::infinity(),
+            committee_bits: BitVector::new(),
+        }
+    }
+
+    #[test]
+    fn test_rejection_stats() {
+        let operation_pool = OperationPool::default();
+        assert_eq!(operation_pool.rejection_stats(), RejectionStats::default());
+
+        operation_pool.record_rejection(RejectionReason::InvalidSignature);
+        operation_pool.record_rejection(RejectionReason::InvalidSignature);
+        operation_pool.record_rejection(RejectionReason::StaleVoluntaryExit);
+
+        assert_eq!(
+            operation_pool.rejection_stats(),
+            RejectionStats {
+                invalid_signature: 2,
+                stale_voluntary_exit: 1,
+                invalid_operation: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_aggregate_disjoint_attestations() {
+        let operation_pool = OperationPool::default();
+        operation_pool.insert_attestation(attestation(1, &[0, 2]));
+        operation_pool.insert_attestation(attestation(1, &[1]));
+        operation_pool.insert_attestation(attestation(2, &[3]));
+
+        let aggregates = operation_pool.get_aggregate_attestations(1, 8);
+        assert_eq!(aggregates.len(), 1);
+        assert_eq!(
+            aggregates[0].aggregation_bits,
+            attestation(1, &[0, 1, 2]).aggregation_bits
+        );
+        assert_eq!(
+            aggregates[0].signature,
+            BLSSignature::aggregate(&[&BLSSignature::infinity(), &BLSSignature::infinity()])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_overlapping_attestations_are_not_merged() {
+        let operation_pool = OperationPool::default();
+        operation_pool.insert_attestation(attestation(1, &[0]));
+        operation_pool.insert_attestation(attestation(1, &[0, 1]));
+        operation_pool.insert_attestation(attestation(1, &[2]));
+
+        let aggregates = operation_pool.get_aggregate_attestations(1, 8);
+        assert_eq!(aggregates.len(), 2);
+        assert_eq!(
+            aggregates[0].aggregation_bits,
+            attestation(1, &[0, 2]).aggregation_bits
+        );
+        assert_eq!(
+            aggregates[1].aggregation_bits,
+            attestation(1, &[0, 1]).aggregation_bits
+        );
+
+        assert_eq!(operation_pool.get_aggregate_attestations(1, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_duplicate_attestation_is_not_stored() {
+        let operation_pool = OperationPool::default();
+        operation_pool.insert_attestation(attestation(1, &[0]));
+        operation_pool.insert_attestation(attestation(1, &[0]));
+
+        assert_eq!(
+            operation_pool.get_attestations(),
+            vec![attestation(1, &[0])]
+        );
+    }
+
+    #[test]
+    fn test_prune_evicts_stale_attestations() {
+        let operation_pool = OperationPool::new(DEFAULT_MAX_ATTESTATIONS, 32);
+        operation_pool.insert_attestation(attestation(1, &[0]));
+        operation_pool.insert_attestation(attestation(2, &[0]));
+
+        operation_pool.prune(33);
+        assert_eq!(operation_pool.get_attestations().len(), 2);
+
+        operation_pool.prune(34);
+        assert_eq!(
+            operation_pool.get_attestations(),
+            vec![attestation(2, &[0])]
+        );
+    }
+
+    #[test]
+    fn test_aggregation_candidates_are_indexed_by_data_root() {
+        let operation_pool = OperationPool::default();
+        let mut other_data = attestation(1, &[0]);
+        other_data.data.index = 1;
+        for slot in 0..1_000 {
+            operation_pool.insert_attestation(attestation(slot, &[0]));
+            operation_pool.insert_attestation(attestation(slot, &[1]));
+        }
+        operation_pool.insert_attestation(other_data.clone());
+        assert_eq!(operation_pool.get_attestations().len(), 2_001);
+
+        let candidates = operation_pool
+            .get_attestations_by_data_root(attestation(500, &[]).data.tree_hash_root());
+        assert_eq!(
+            candidates,
+            vec![attestation(500, &[0]), attestation(500, &[1])]
+        );
+
+        let index = operation_pool.attestations.read();
+        // Slot 1 only holds the two data roots attested to at that slot
+        assert_eq!(index.data_roots_by_slot[&1].len(), 2);
+        assert_eq!(
+            index.at_slot(1).collect::<Vec<_>>(),
+            vec![
+                &[attestation(1, &[0]), attestation(1, &[1])][..],
+                &[other_data][..],
+            ]
+        );
+        assert!(index.at_slot(1_000).next().is_none());
+    }
+
+    #[test]
+    fn test_get_attestations_at_slot() {
+        let operation_pool = OperationPool::default();
+        operation_pool.insert_attestation(attestation(1, &[0]));
+        operation_pool.insert_attestation(attestation(2, &[0]));
+        operation_pool.insert_attestation(attestation(1, &[1]));
+
+        assert_eq!(
+            operation_pool.get_attestations_at_slot(1),
+            vec![attestation(1, &[0]), attestation(1, &[1])]
+        );
+        assert!(operation_pool.get_attestations_at_slot(3).is_empty());
+    }
+
+    #[test]
+    fn test_full_pool_evicts_oldest_attestation() {
+        let operation_pool = OperationPool::new(2, 32);
+        operation_pool.insert_attestation(attestation(2, &[0]));
+        operation_pool.insert_attestation(attestation(1, &[0]));
+        operation_pool.insert_attestation(attestation(3, &[0]));
+
+        assert_eq!(
+            operation_pool.get_attestations(),
+            vec![attestation(2, &[0]), attestation(3, &[0])]
+        );
+    }
 }
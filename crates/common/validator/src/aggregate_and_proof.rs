@@ -48,6 +48,7 @@ pub fn get_aggregate_and_proof_signature(
         Some(compute_epoch_at_slot(
             aggregate_and_proof.aggregate.data.slot,
         )),
+        None,
     );
     let signing_root = compute_signing_root(aggregate_and_proof, domain);
     Ok(private_key.sign(signing_root.as_ref())?)
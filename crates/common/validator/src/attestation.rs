@@ -1,4 +1,4 @@
-use std::{cmp::max, collections::HashSet};
+use std::collections::HashSet;
 
 use anyhow::{anyhow, ensure};
 use ream_bls::{
@@ -14,7 +14,9 @@ use ream_consensus::{
         SLOTS_PER_EPOCH,
     },
     electra::beacon_state::BeaconState,
-    misc::{compute_domain, compute_epoch_at_slot, compute_signing_root, get_committee_indices},
+    misc::{
+        self, compute_domain, compute_epoch_at_slot, compute_signing_root, get_committee_indices,
+    },
 };
 use ream_network_spec::networks::network_spec;
 use ssz_types::{
@@ -22,10 +24,7 @@ use ssz_types::{
     typenum::{U64, U131072},
 };
 
-use crate::{
-    constants::{DOMAIN_SELECTION_PROOF, TARGET_AGGREGATORS_PER_COMMITTEE},
-    hash_signature_prefix_to_u64,
-};
+use crate::constants::DOMAIN_SELECTION_PROOF;
 
 pub fn is_aggregator(
     state: &BeaconState,
@@ -33,13 +32,8 @@ pub fn is_aggregator(
     committee_index: u64,
     slot_signature: BLSSignature,
 ) -> anyhow::Result<bool> {
-    Ok(hash_signature_prefix_to_u64(slot_signature) as usize
-        % max(
-            1,
-            state.get_beacon_committee(slot, committee_index)?.len()
-                / TARGET_AGGREGATORS_PER_COMMITTEE as usize,
-        )
-        == 0)
+    let committee_length = state.get_beacon_committee(slot, committee_index)?.len() as u64;
+    Ok(misc::is_aggregator(committee_length, &slot_signature))
 }
 
 /// Compute the correct subnet for an attestation for Phase 0.
@@ -103,7 +97,11 @@ pub fn get_attestation_signature(
     attestation_data: AttestationData,
     private_key: PrivateKey,
 ) -> anyhow::Result<BLSSignature> {
-    let domain = state.get_domain(DOMAIN_BEACON_ATTESTER, Some(attestation_data.target.epoch));
+    let domain = state.get_domain(
+        DOMAIN_BEACON_ATTESTER,
+        Some(attestation_data.target.epoch),
+        None,
+    );
     let signing_root = compute_signing_root(attestation_data, domain);
     Ok(private_key.sign(signing_root.as_ref())?)
 }
@@ -113,7 +111,11 @@ pub fn get_slot_signature(
     slot: u64,
     private_key: PrivateKey,
 ) -> anyhow::Result<BLSSignature> {
-    let domain = state.get_domain(DOMAIN_SELECTION_PROOF, Some(compute_epoch_at_slot(slot)));
+    let domain = state.get_domain(
+        DOMAIN_SELECTION_PROOF,
+        Some(compute_epoch_at_slot(slot)),
+        None,
+    );
     let signing_root = compute_signing_root(slot, domain);
     Ok(private_key.sign(signing_root.as_ref())?)
 }
@@ -18,6 +18,7 @@ pub fn get_block_signature(
     let domain = state.get_domain(
         DOMAIN_BEACON_PROPOSER,
         Some(compute_epoch_at_slot(block.slot)),
+        None,
     );
     let signing_root = compute_signing_root(block, domain);
     Ok(private_key.sign(signing_root.as_ref())?)
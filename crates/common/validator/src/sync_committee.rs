@@ -7,7 +7,10 @@ use ream_bls::{
     traits::{Aggregatable, Signable},
 };
 use ream_consensus::{
-    constants::{DOMAIN_SYNC_COMMITTEE, EPOCHS_PER_SYNC_COMMITTEE_PERIOD, SYNC_COMMITTEE_SIZE},
+    constants::{
+        DOMAIN_SYNC_COMMITTEE, EPOCHS_PER_SYNC_COMMITTEE_PERIOD, SYNC_COMMITTEE_SIZE,
+        TARGET_AGGREGATORS_PER_COMMITTEE,
+    },
     electra::{beacon_block::BeaconBlock, beacon_state::BeaconState},
     misc::{compute_domain, compute_epoch_at_slot, compute_signing_root},
     sync_aggregate::SyncAggregate,
@@ -18,8 +21,7 @@ use ssz_types::{BitVector, typenum::U512};
 use tree_hash_derive::TreeHash;
 
 use crate::{
-    constants::{SYNC_COMMITTEE_SUBNET_COUNT, TARGET_AGGREGATORS_PER_COMMITTEE},
-    contribution_and_proof::SyncCommitteeContribution,
+    constants::SYNC_COMMITTEE_SUBNET_COUNT, contribution_and_proof::SyncCommitteeContribution,
     hash_signature_prefix_to_u64,
 };
 
@@ -169,7 +171,7 @@ pub fn get_sync_committee_message(
     private_key: PrivateKey,
 ) -> anyhow::Result<SyncCommitteeMessage> {
     let epoch = state.get_current_epoch();
-    let domain = state.get_domain(DOMAIN_SYNC_COMMITTEE, Some(epoch));
+    let domain = state.get_domain(DOMAIN_SYNC_COMMITTEE, Some(epoch), None);
     let signing_root = compute_signing_root(beacon_block_root, domain);
 
     Ok(SyncCommitteeMessage {
@@ -18,4 +18,6 @@ pub enum BLSError {
     InvalidSignature,
     #[error("invalid hex string")]
     InvalidHexString,
+    #[error("cannot aggregate an empty list of public keys")]
+    EmptyAggregate,
 }
@@ -3,12 +3,22 @@ use blst::min_pk::SecretKey as BlstSecretKey;
 use ssz_types::FixedVector;
 
 use crate::{
-    PrivateKey,
+    PrivateKey, PublicKey,
     constants::DST,
+    errors::BLSError,
     signature::BLSSignature,
     traits::{Signable, SupranationalSignable},
 };
 
+impl PrivateKey {
+    /// Returns the public key of this private key.
+    pub fn public_key(&self) -> Result<PublicKey, BLSError> {
+        let private_key = BlstSecretKey::from_bytes(self.inner.as_slice())
+            .map_err(|err| BLSError::BlstError(err.into()))?;
+        PublicKey::try_from(private_key.sk_to_pk())
+    }
+}
+
 impl Signable for PrivateKey {
     type Error = anyhow::Error;
 
@@ -23,6 +23,23 @@ impl PublicKey {
     pub fn to_blst_public_key(&self) -> Result<BlstPublicKey, BLSError> {
         BlstPublicKey::from_bytes(&self.inner).map_err(|err| BLSError::BlstError(err.into()))
     }
+
+    /// Aggregates the public keys of a committee into the single key its aggregate signature
+    /// verifies against. An empty committee has no verification key and is rejected.
+    pub fn aggregate_committee(public_keys: &[PublicKey]) -> Result<PublicKey, BLSError> {
+        if public_keys.is_empty() {
+            return Err(BLSError::EmptyAggregate);
+        }
+
+        let public_keys = public_keys
+            .iter()
+            .map(|public_key| public_key.to_blst_public_key())
+            .collect::<Result<Vec<_>, _>>()?;
+        let aggregate_public_key =
+            BlstAggregatePublicKey::aggregate(&public_keys.iter().collect::<Vec<_>>(), true)
+                .map_err(|err| BLSError::BlstError(err.into()))?;
+        PublicKey::try_from(aggregate_public_key.to_public_key())
+    }
 }
 
 impl Aggregatable<PublicKey> for PublicKey {
@@ -1,8 +1,9 @@
 use anyhow::anyhow;
 use blst::{
-    BLST_ERROR,
+    BLST_ERROR, blst_scalar,
     min_pk::{AggregateSignature as BlstAggregateSignature, Signature as BlstSignature},
 };
+use rand::Rng;
 use ssz_types::FixedVector;
 
 use crate::{
@@ -61,6 +62,41 @@ impl Verifiable for BLSSignature {
             &public_keys.iter().collect::<Vec<_>>(),
         ) == BLST_ERROR::BLST_SUCCESS)
     }
+
+    fn verify_multiple(sets: &[(&PublicKey, &[u8], &BLSSignature)]) -> Result<bool, BLSError> {
+        if sets.is_empty() {
+            return Ok(false);
+        }
+
+        let mut public_keys = Vec::with_capacity(sets.len());
+        let mut messages = Vec::with_capacity(sets.len());
+        let mut signatures = Vec::with_capacity(sets.len());
+        for (public_key, message, signature) in sets {
+            public_keys.push(public_key.to_blst_public_key()?);
+            messages.push(*message);
+            signatures.push(signature.to_blst_signature()?);
+        }
+
+        let mut rng = rand::thread_rng();
+        let scalars = (0..sets.len())
+            .map(|_| {
+                let mut scalar = blst_scalar::default();
+                scalar.b[..8].copy_from_slice(&rng.gen_range(1..=u64::MAX).to_le_bytes());
+                scalar
+            })
+            .collect::<Vec<_>>();
+
+        Ok(BlstSignature::verify_multiple_aggregate_signatures(
+            &messages,
+            DST,
+            &public_keys.iter().collect::<Vec<_>>(),
+            false,
+            &signatures.iter().collect::<Vec<_>>(),
+            true,
+            &scalars,
+            64,
+        ) == BLST_ERROR::BLST_SUCCESS)
+    }
 }
 
 impl Aggregatable<BLSSignature> for BLSSignature {
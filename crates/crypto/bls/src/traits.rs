@@ -82,6 +82,19 @@ pub trait Verifiable {
     ) -> Result<bool, Self::Error>
     where
         P: AsRef<[&'a PublicKey]>;
+
+    /// Verifies many signatures, each against its own public key and message, with a single
+    /// multi-pairing check. Every set is weighted by a random non-zero scalar so that invalid
+    /// signatures can't cancel each other out.
+    ///
+    /// # Arguments
+    /// * `sets` - Public key, message and signature of every signature to verify
+    ///
+    /// # Returns
+    /// * `Result<bool, BLSError>` - Ok(true) if every signature is valid, Ok(false) if any of them
+    ///   is invalid or `sets` is empty, or Err if there are issues with signature or public key
+    ///   bytes
+    fn verify_multiple(sets: &[(&PublicKey, &[u8], &BLSSignature)]) -> Result<bool, Self::Error>;
 }
 
 /// Marker trait for zkcrypto/bls12_381 BLS signature verification implementation
@@ -1,18 +1,36 @@
 use bls12_381::{
-    G2Projective, Scalar,
+    G1Projective, G2Projective, Scalar,
     hash_to_curve::{ExpandMsgXmd, HashToCurve},
 };
 use group::Curve;
 use ssz_types::FixedVector;
 
 use crate::{
-    PrivateKey,
+    PrivateKey, PublicKey,
     constants::DST,
     errors::BLSError,
     signature::BLSSignature,
     traits::{Signable, ZkcryptoSignable},
 };
 
+impl PrivateKey {
+    /// Returns the public key of this private key.
+    pub fn public_key(&self) -> Result<PublicKey, BLSError> {
+        Ok(PublicKey::from(
+            G1Projective::generator() * self.to_scalar()?,
+        ))
+    }
+
+    /// Private keys are big-endian, while `Scalar` reads little-endian bytes.
+    fn to_scalar(&self) -> Result<Scalar, BLSError> {
+        let mut bytes: [u8; 32] = self.inner.0;
+        bytes.reverse();
+        Scalar::from_bytes(&bytes)
+            .into_option()
+            .ok_or(BLSError::InvalidPrivateKey)
+    }
+}
+
 impl Signable for PrivateKey {
     type Error = BLSError;
 
@@ -22,10 +40,7 @@ impl Signable for PrivateKey {
             DST,
         );
 
-        let scalar = Scalar::from_bytes(self.inner.as_ref())
-            .into_option()
-            .ok_or(BLSError::InvalidPrivateKey)?;
-        let signature_point = hash_point * scalar;
+        let signature_point = hash_point * self.to_scalar()?;
         let signature_bytes = signature_point.to_affine().to_compressed();
 
         Ok(BLSSignature {
@@ -36,3 +51,31 @@ impl Signable for PrivateKey {
 }
 
 impl ZkcryptoSignable for PrivateKey {}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use blst::min_pk::SecretKey as BlstSecretKey;
+
+    use super::*;
+
+    #[test]
+    fn test_sign_and_public_key_match_blst() {
+        for ikm in [[1; 32], [0x42; 32], *b"zkcrypto and blst agree on keys!"] {
+            let blst_private_key = BlstSecretKey::key_gen(&ikm, &[]).unwrap();
+            let private_key = PrivateKey {
+                inner: B256::from(blst_private_key.to_bytes()),
+            };
+
+            assert_eq!(
+                private_key.public_key().unwrap().to_bytes(),
+                blst_private_key.sk_to_pk().compress().as_slice()
+            );
+            let message = b"same secret, same signature";
+            assert_eq!(
+                private_key.sign(message).unwrap().inner.to_vec(),
+                blst_private_key.sign(message, DST, &[]).compress().to_vec()
+            );
+        }
+    }
+}
@@ -48,3 +48,15 @@ impl Aggregatable<PublicKey> for PublicKey {
 }
 
 impl ZkcryptoAggregatable<PublicKey> for PublicKey {}
+
+impl PublicKey {
+    /// Aggregates the public keys of a committee into the single key its aggregate signature
+    /// verifies against. An empty committee has no verification key and is rejected.
+    pub fn aggregate_committee(public_keys: &[PublicKey]) -> Result<PublicKey, BLSError> {
+        if public_keys.is_empty() {
+            return Err(BLSError::EmptyAggregate);
+        }
+
+        Self::aggregate(&public_keys.iter().collect::<Vec<_>>())
+    }
+}
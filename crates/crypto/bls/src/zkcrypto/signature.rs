@@ -1,8 +1,9 @@
 use bls12_381::{
-    G1Affine, G2Affine, G2Projective,
+    G1Affine, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
     hash_to_curve::{ExpandMsgXmd, HashToCurve},
-    pairing,
+    multi_miller_loop, pairing,
 };
+use rand::Rng;
 
 use crate::{
     BLSSignature, PublicKey,
@@ -70,6 +71,39 @@ impl Verifiable for BLSSignature {
 
         Ok(gt1 == gt2)
     }
+
+    fn verify_multiple(sets: &[(&PublicKey, &[u8], &BLSSignature)]) -> Result<bool, BLSError> {
+        if sets.is_empty() {
+            return Ok(false);
+        }
+
+        // Check e(g1, sum(r_i * signature_i)) == prod(e(r_i * public_key_i, H(message_i)))
+        let mut rng = rand::thread_rng();
+        let mut signature_sum = G2Projective::identity();
+        let mut terms = Vec::with_capacity(sets.len() + 1);
+        for (public_key, message, signature) in sets {
+            let scalar = Scalar::from(rng.gen_range(1..=u64::MAX));
+            signature_sum += G2Affine::try_from(*signature)? * scalar;
+            let h = <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(
+                [*message],
+                DST,
+            );
+            terms.push((
+                G1Affine::from(G1Affine::try_from(*public_key)? * scalar),
+                G2Prepared::from(G2Affine::from(h)),
+            ));
+        }
+        terms.push((
+            -G1Affine::generator(),
+            G2Prepared::from(G2Affine::from(signature_sum)),
+        ));
+
+        let terms = terms
+            .iter()
+            .map(|(public_key, message)| (public_key, message))
+            .collect::<Vec<_>>();
+        Ok(multi_miller_loop(&terms).final_exponentiation() == Gt::identity())
+    }
 }
 
 impl Aggregatable<BLSSignature> for BLSSignature {
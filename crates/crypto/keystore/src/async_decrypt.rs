@@ -0,0 +1,134 @@
+use std::{num::NonZeroUsize, sync::Arc, thread::available_parallelism};
+
+use anyhow::anyhow;
+use tokio::{sync::Semaphore, task::spawn_blocking};
+
+use crate::keystore::{EncryptedKeystore, Keystore};
+
+/// Caps the number of keystores decrypted at the same time by [`EncryptedKeystore::decrypt_async`].
+/// The KDF runs on the blocking thread pool, so a batch import neither stalls the async workers
+/// nor runs more CPU bound KDFs than there are cores. Clones share the same cap.
+#[derive(Debug, Clone)]
+pub struct DecryptLimiter {
+    parallelism: usize,
+    permits: Arc<Semaphore>,
+}
+
+impl Default for DecryptLimiter {
+    /// One decryption per available core.
+    fn default() -> Self {
+        Self::new(available_parallelism().map_or(1, NonZeroUsize::get))
+    }
+}
+
+impl DecryptLimiter {
+    /// Allow ``parallelism`` decryptions at a time, at least one.
+    pub fn new(parallelism: usize) -> Self {
+        let parallelism = parallelism.max(1);
+        Self {
+            parallelism,
+            permits: Arc::new(Semaphore::new(parallelism)),
+        }
+    }
+
+    pub fn parallelism(&self) -> usize {
+        self.parallelism
+    }
+
+    /// Run ``task`` on the blocking thread pool once fewer than ``parallelism`` tasks are running.
+    async fn run<T: Send + 'static>(
+        &self,
+        task: impl FnOnce() -> anyhow::Result<T> + Send + 'static,
+    ) -> anyhow::Result<T> {
+        let permit = self.permits.clone().acquire_owned().await?;
+        spawn_blocking(move || {
+            let _permit = permit;
+            task()
+        })
+        .await
+        .map_err(|err| anyhow!("Keystore decryption task failed: {err}"))?
+    }
+}
+
+impl EncryptedKeystore {
+    /// Decrypt the keystore like [`EncryptedKeystore::decrypt`] without blocking the async
+    /// runtime, waiting for ``limiter`` to allow another decryption first.
+    pub async fn decrypt_async(
+        self,
+        password: Vec<u8>,
+        limiter: &DecryptLimiter,
+    ) -> anyhow::Result<Keystore> {
+        limiter.run(move || self.decrypt(&password)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        thread::sleep,
+        time::Duration,
+    };
+
+    use alloy_primitives::hex;
+
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_decryptions_respect_parallelism() {
+        let limiter = DecryptLimiter::new(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let tasks = (0..16).map(|_| {
+            let limiter = limiter.clone();
+            let running = running.clone();
+            let max_running = max_running.clone();
+            tokio::spawn(async move {
+                limiter
+                    .run(move || {
+                        let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_running.fetch_max(now_running, Ordering::SeqCst);
+                        sleep(Duration::from_millis(20));
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        Ok(())
+                    })
+                    .await
+            })
+        });
+        for task in tasks.collect::<Vec<_>>() {
+            task.await.unwrap().unwrap();
+        }
+
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn decrypt_async_matches_decrypt() {
+        let password = hex!("7465737470617373776f7264f09f9491").to_vec();
+        let keystore =
+            EncryptedKeystore::load_from_file("./assets/Pbkdf2TestKeystore.json").unwrap();
+        let expected = keystore.decrypt(&password).unwrap();
+
+        let limiter = DecryptLimiter::new(1);
+        let decrypted = keystore.decrypt_async(password, &limiter).await.unwrap();
+        assert_eq!(decrypted.private_key, expected.private_key);
+        assert_eq!(decrypted.public_key, expected.public_key);
+
+        let keystore =
+            EncryptedKeystore::load_from_file("./assets/Pbkdf2TestKeystore.json").unwrap();
+        assert!(
+            keystore
+                .decrypt_async(b"password123".to_vec(), &limiter)
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parallelism_is_at_least_one() {
+        assert_eq!(DecryptLimiter::new(0).parallelism(), 1);
+        assert!(DecryptLimiter::default().parallelism() >= 1);
+    }
+}
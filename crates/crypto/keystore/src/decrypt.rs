@@ -4,15 +4,39 @@ use aes::{
 };
 
 pub fn aes128_ctr(buffer: &mut [u8], key: [u8; 16], initial_vector: &[u8; 16]) {
-    let cipher = Aes128::new(&key.into());
-    let mut counter = u128::from_be_bytes(*initial_vector);
+    Aes128Ctr::new(key, initial_vector).apply_keystream(buffer);
+}
+
+/// AES-128-CTR keystream that can be applied to a message one chunk at a time. Applying it to
+/// consecutive chunks of any size gives the same result as [`aes128_ctr`] over the whole message.
+pub struct Aes128Ctr {
+    cipher: Aes128,
+    counter: u128,
+    block: [u8; 16],
+    block_offset: usize,
+}
+
+impl Aes128Ctr {
+    pub fn new(key: [u8; 16], initial_vector: &[u8; 16]) -> Self {
+        Self {
+            cipher: Aes128::new(&key.into()),
+            counter: u128::from_be_bytes(*initial_vector),
+            block: [0; 16],
+            block_offset: 16,
+        }
+    }
 
-    for chunk in buffer.chunks_mut(16) {
-        let mut block = GenericArray::from(counter.to_be_bytes());
-        cipher.encrypt_block(&mut block);
-        for (chunk_byte, block_byte) in chunk.iter_mut().zip(block.iter()) {
-            *chunk_byte ^= block_byte;
+    pub fn apply_keystream(&mut self, buffer: &mut [u8]) {
+        for byte in buffer {
+            if self.block_offset == self.block.len() {
+                let mut block = GenericArray::from(self.counter.to_be_bytes());
+                self.cipher.encrypt_block(&mut block);
+                self.block = block.into();
+                self.block_offset = 0;
+                self.counter = self.counter.wrapping_add(1);
+            }
+            *byte ^= self.block[self.block_offset];
+            self.block_offset += 1;
         }
-        counter = counter.wrapping_add(1);
     }
 }
@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum KeystoreError {
+    #[error("JSON is not an EIP-2335 keystore, missing fields: {0}")]
+    NotAKeystore(String),
+
+    #[error("Unsupported keystore KDF function {0}, expected pbkdf2 or scrypt")]
+    UnsupportedKdf(String),
+
+    #[error("Unsupported keystore cipher function {0}, expected aes-128-ctr")]
+    UnsupportedCipher(String),
+
+    #[error("Unsafe keystore KDF parameters: {0}")]
+    UnsafeKdfParams(String),
+
+    #[error("Invalid EIP-2334 key path {0}")]
+    InvalidKeyPath(String),
+
+    #[error("Password provided is invalid")]
+    InvalidPassword,
+
+    #[error("Keystore cipher message is {0} bytes, expected 32")]
+    InvalidSecretLength(usize),
+
+    #[error("Keystore secret does not match its pubkey {0}")]
+    PublicKeyMismatch(String),
+}
@@ -0,0 +1,126 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::KeystoreError;
+
+/// A KDF an EIP-2335 keystore can name in ``crypto.kdf.function``.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KdfFunction {
+    Pbkdf2,
+    Scrypt,
+}
+
+impl KdfFunction {
+    pub const ALL: [KdfFunction; 2] = [KdfFunction::Pbkdf2, KdfFunction::Scrypt];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KdfFunction::Pbkdf2 => "pbkdf2",
+            KdfFunction::Scrypt => "scrypt",
+        }
+    }
+}
+
+impl FromStr for KdfFunction {
+    type Err = KeystoreError;
+
+    fn from_str(function: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kdf| kdf.as_str() == function)
+            .ok_or_else(|| KeystoreError::UnsupportedKdf(function.to_string()))
+    }
+}
+
+impl fmt::Display for KdfFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A cipher an EIP-2335 keystore can name in ``crypto.cipher.function``.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CipherFunction {
+    #[serde(rename = "aes-128-ctr")]
+    Aes128Ctr,
+}
+
+impl CipherFunction {
+    pub const ALL: [CipherFunction; 1] = [CipherFunction::Aes128Ctr];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CipherFunction::Aes128Ctr => "aes-128-ctr",
+        }
+    }
+}
+
+impl FromStr for CipherFunction {
+    type Err = KeystoreError;
+
+    fn from_str(function: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|cipher| cipher.as_str() == function)
+            .ok_or_else(|| KeystoreError::UnsupportedCipher(function.to_string()))
+    }
+}
+
+impl fmt::Display for CipherFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn supported_functions_are_parsed() {
+        assert_eq!(
+            "pbkdf2".parse::<KdfFunction>().unwrap(),
+            KdfFunction::Pbkdf2
+        );
+        assert_eq!(
+            "scrypt".parse::<KdfFunction>().unwrap(),
+            KdfFunction::Scrypt
+        );
+        assert_eq!(
+            "aes-128-ctr".parse::<CipherFunction>().unwrap(),
+            CipherFunction::Aes128Ctr
+        );
+    }
+
+    #[test]
+    fn functions_round_trip_through_display_and_serde() {
+        for kdf in KdfFunction::ALL {
+            assert_eq!(kdf.to_string().parse::<KdfFunction>().unwrap(), kdf);
+            assert_eq!(serde_json::to_string(&kdf).unwrap(), format!("\"{kdf}\""));
+        }
+        for cipher in CipherFunction::ALL {
+            assert_eq!(
+                cipher.to_string().parse::<CipherFunction>().unwrap(),
+                cipher
+            );
+            assert_eq!(
+                serde_json::from_str::<CipherFunction>(&format!("\"{cipher}\"")).unwrap(),
+                cipher
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_functions_are_rejected() {
+        assert!(matches!(
+            "argon2id".parse::<KdfFunction>(),
+            Err(KeystoreError::UnsupportedKdf(function)) if function == "argon2id"
+        ));
+        assert!(matches!(
+            "aes-256-gcm".parse::<CipherFunction>(),
+            Err(KeystoreError::UnsupportedCipher(function)) if function == "aes-256-gcm"
+        ));
+    }
+}
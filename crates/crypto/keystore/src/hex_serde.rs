@@ -1,18 +1,140 @@
-use alloy_primitives::hex::{FromHex, ToHexExt};
+use alloy_primitives::hex::{self, ToHexExt};
 use serde::{Deserialize, Deserializer, Serializer, de};
 
+/// Serialize ``value`` as ``0x`` prefixed lowercase hex.
 pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
 where
     T: AsRef<[u8]>,
     S: Serializer,
 {
-    serializer.serialize_str(&value.encode_hex())
+    serializer.serialize_str(&value.encode_hex_with_prefix())
 }
 
+/// Deserialize hex with or without a ``0x`` or ``0X`` prefix, an empty string is an empty vector.
 pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
-    Vec::<u8>::from_hex(&s).map_err(|err| de::Error::custom(err.to_string()))
+    decode(&s).map_err(de::Error::custom)
+}
+
+fn decode(s: &str) -> Result<Vec<u8>, String> {
+    let stripped = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    hex::decode(stripped).map_err(|err| err.to_string())
+}
+
+/// Serde helpers for fixed length byte arrays, encoded as ``0x`` prefixed hex. Deserializing
+/// rejects input that does not decode to exactly ``N`` bytes, so a truncated field fails when the
+/// keystore is loaded rather than when it is used.
+pub mod fixed {
+    use alloy_primitives::hex::ToHexExt;
+    use serde::{Deserialize, Deserializer, Serializer, de};
+
+    pub fn serialize<const N: usize, S>(value: &[u8; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&value.encode_hex_with_prefix())
+    }
+
+    pub fn deserialize<'de, const N: usize, D>(deserializer: D) -> Result<[u8; N], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let bytes = super::decode(&s).map_err(de::Error::custom)?;
+        let length = bytes.len();
+        bytes.try_into().map_err(|_| {
+            de::Error::custom(format!("expected {N} bytes of hex, got {length} bytes"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Fixed {
+        #[serde(with = "super::fixed")]
+        iv: [u8; 4],
+    }
+
+    fn from_json(iv: &str) -> serde_json::Result<Fixed> {
+        serde_json::from_str(&format!(r#"{{"iv":"{iv}"}}"#))
+    }
+
+    #[test]
+    fn test_fixed_round_trip() {
+        let fixed = Fixed {
+            iv: [0xaa, 0xbb, 0xcc, 0xdd],
+        };
+        let json = serde_json::to_string(&fixed).unwrap();
+        assert_eq!(json, r#"{"iv":"0xaabbccdd"}"#);
+        assert_eq!(serde_json::from_str::<Fixed>(&json).unwrap(), fixed);
+    }
+
+    #[test]
+    fn test_fixed_wrong_length() {
+        let err = from_json("0xaabbcc").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("expected 4 bytes of hex, got 3 bytes")
+        );
+
+        let err = from_json("0xaabbccddee").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("expected 4 bytes of hex, got 5 bytes")
+        );
+    }
+
+    #[test]
+    fn test_fixed_malformed() {
+        assert!(from_json("0xaabbccd").is_err());
+        assert!(from_json("0xaabbccgg").is_err());
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Bytes {
+        #[serde(with = "super")]
+        message: Vec<u8>,
+    }
+
+    fn bytes_from_json(message: &str) -> Vec<u8> {
+        serde_json::from_str::<Bytes>(&format!(r#"{{"message":"{message}"}}"#))
+            .unwrap()
+            .message
+    }
+
+    #[test]
+    fn test_optional_prefix() {
+        assert_eq!(bytes_from_json("0xaabbccdd"), vec![0xaa, 0xbb, 0xcc, 0xdd]);
+        assert_eq!(bytes_from_json("aabbccdd"), vec![0xaa, 0xbb, 0xcc, 0xdd]);
+        assert_eq!(bytes_from_json("0XAABBCCDD"), vec![0xaa, 0xbb, 0xcc, 0xdd]);
+        assert_eq!(bytes_from_json(""), Vec::<u8>::new());
+        assert_eq!(bytes_from_json("0x"), Vec::<u8>::new());
+
+        let fixed = from_json("aabbccdd").unwrap();
+        assert_eq!(fixed.iv, [0xaa, 0xbb, 0xcc, 0xdd]);
+    }
+
+    #[test]
+    fn test_serialization_is_canonical() {
+        let bytes = Bytes {
+            message: vec![0xaa, 0xbb, 0xcc, 0xdd],
+        };
+        assert_eq!(
+            serde_json::to_string(&bytes).unwrap(),
+            r#"{"message":"0xaabbccdd"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Bytes { message: vec![] }).unwrap(),
+            r#"{"message":"0x"}"#
+        );
+    }
 }
@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use ream_bls::PublicKey;
+
+use crate::{
+    function::{CipherFunction, KdfFunction},
+    keystore::EncryptedKeystore,
+};
+
+/// Metadata of an EIP-2335 keystore, enough to identify it without decrypting it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeystoreInfo {
+    pub version: u64,
+    pub uuid: String,
+    pub kdf_function: KdfFunction,
+    pub cipher_function: CipherFunction,
+    pub public_key: PublicKey,
+    /// The EIP-2334 path as written in the keystore, which may be empty.
+    pub path: String,
+}
+
+impl From<&EncryptedKeystore> for KeystoreInfo {
+    fn from(keystore: &EncryptedKeystore) -> Self {
+        Self {
+            version: keystore.version,
+            uuid: keystore.uuid.clone(),
+            kdf_function: keystore.crypto.kdf.params.function(),
+            cipher_function: keystore.crypto.cipher.params.function(),
+            public_key: keystore.public_key.clone(),
+            path: keystore.path.clone(),
+        }
+    }
+}
+
+/// Read the metadata of the keystore at ``path`` without a password.
+pub fn inspect_keystore(path: &Path) -> anyhow::Result<KeystoreInfo> {
+    Ok(KeystoreInfo::from(&EncryptedKeystore::load_from_file(
+        path,
+    )?))
+}
+
+/// Decrypt the keystore at ``path`` with ``password`` and derive the public key of its secret.
+/// The returned ``public_key`` is the derived one, so callers can check it against the validator
+/// they expect. A wrong password fails with ``KeystoreError::InvalidPassword``, and a secret that
+/// does not match the ``pubkey`` the keystore declares with ``KeystoreError::PublicKeyMismatch``.
+pub fn verify_keystore(path: &Path, password: &str) -> anyhow::Result<KeystoreInfo> {
+    let encrypted_keystore = EncryptedKeystore::load_from_file(path)?;
+    let keystore = encrypted_keystore.decrypt(password.as_bytes())?;
+
+    Ok(KeystoreInfo {
+        public_key: keystore.public_key,
+        ..KeystoreInfo::from(&encrypted_keystore)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::KeystoreError;
+
+    const PBKDF2_KEYSTORE: &str = "./assets/Pbkdf2TestKeystore.json";
+
+    fn expected_public_key() -> PublicKey {
+        EncryptedKeystore::load_from_file(PBKDF2_KEYSTORE)
+            .unwrap()
+            .public_key
+    }
+
+    #[test]
+    fn inspect_reads_metadata() {
+        let info = inspect_keystore(Path::new(PBKDF2_KEYSTORE)).unwrap();
+
+        assert_eq!(info.version, 4);
+        assert_eq!(info.uuid, "64625def-3331-4eea-ab6f-782f3ed16a83");
+        assert_eq!(info.kdf_function, KdfFunction::Pbkdf2);
+        assert_eq!(info.cipher_function, CipherFunction::Aes128Ctr);
+        assert_eq!(info.public_key, expected_public_key());
+        assert_eq!(info.path, "m/12381/60/0/0");
+    }
+
+    #[test]
+    fn verify_returns_derived_public_key() {
+        let info = verify_keystore(Path::new(PBKDF2_KEYSTORE), "testpassword\u{1f511}").unwrap();
+        assert_eq!(info.public_key, expected_public_key());
+        assert_eq!(info.kdf_function, KdfFunction::Pbkdf2);
+    }
+
+    #[test]
+    fn verify_rejects_wrong_password() {
+        let err = verify_keystore(Path::new(PBKDF2_KEYSTORE), "password123").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<KeystoreError>(),
+            Some(KeystoreError::InvalidPassword)
+        ));
+    }
+}
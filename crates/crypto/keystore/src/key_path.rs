@@ -0,0 +1,141 @@
+use std::{fmt, str::FromStr};
+
+use crate::error::KeystoreError;
+
+/// Purpose of EIP-2334 paths, the first index after the master node.
+pub const PURPOSE: u32 = 12381;
+
+/// Coin type of Ethereum validator keys in EIP-2334 paths.
+pub const COIN_TYPE: u32 = 3600;
+
+/// Which key of a validator an EIP-2334 path derives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    /// ``m/12381/3600/i/0``
+    Withdrawal,
+    /// ``m/12381/3600/i/0/0``
+    Signing,
+}
+
+/// An EIP-2334 validator key derivation path, ``m/12381/3600/i/0`` for the withdrawal key of
+/// account ``i`` and ``m/12381/3600/i/0/0`` for its signing key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPath {
+    pub account_index: u32,
+    pub key_type: KeyType,
+}
+
+impl KeyPath {
+    pub fn signing(account_index: u32) -> Self {
+        Self {
+            account_index,
+            key_type: KeyType::Signing,
+        }
+    }
+
+    pub fn withdrawal(account_index: u32) -> Self {
+        Self {
+            account_index,
+            key_type: KeyType::Withdrawal,
+        }
+    }
+}
+
+impl FromStr for KeyPath {
+    type Err = KeystoreError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| KeystoreError::InvalidKeyPath(format!("{path}: {reason}"));
+
+        let mut nodes = path.split('/');
+        if nodes.next() != Some("m") {
+            return Err(invalid("must start at the master node `m`"));
+        }
+        let indices = nodes
+            .map(|node| {
+                node.parse::<u32>()
+                    .map_err(|_| invalid(&format!("`{node}` is not a path index")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (purpose, coin_type, account_index, rest) = match indices.as_slice() {
+            [purpose, coin_type, account_index, rest @ ..] => {
+                (*purpose, *coin_type, *account_index, rest)
+            }
+            _ => return Err(invalid("too few path indices")),
+        };
+        if purpose != PURPOSE {
+            return Err(invalid(&format!(
+                "purpose must be {PURPOSE}, got {purpose}"
+            )));
+        }
+        if coin_type != COIN_TYPE {
+            return Err(invalid(&format!(
+                "coin type must be {COIN_TYPE}, got {coin_type}"
+            )));
+        }
+
+        match rest {
+            [0] => Ok(Self::withdrawal(account_index)),
+            [0, 0] => Ok(Self::signing(account_index)),
+            _ => Err(invalid("not a withdrawal or signing key path")),
+        }
+    }
+}
+
+impl fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m/{PURPOSE}/{COIN_TYPE}/{}/0", self.account_index)?;
+        if self.key_type == KeyType::Signing {
+            write!(f, "/0")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signing_path_is_parsed() {
+        let key_path = "m/12381/3600/7/0/0".parse::<KeyPath>().unwrap();
+        assert_eq!(key_path, KeyPath::signing(7));
+        assert_eq!(key_path.to_string(), "m/12381/3600/7/0/0");
+    }
+
+    #[test]
+    fn withdrawal_path_is_parsed() {
+        let key_path = "m/12381/3600/0/0".parse::<KeyPath>().unwrap();
+        assert_eq!(key_path, KeyPath::withdrawal(0));
+        assert_eq!(key_path.to_string(), "m/12381/3600/0/0");
+    }
+
+    #[test]
+    fn wrong_purpose_or_coin_type_is_rejected() {
+        for path in ["m/44/3600/0/0/0", "m/12381/60/0/0/0"] {
+            assert!(matches!(
+                path.parse::<KeyPath>(),
+                Err(KeystoreError::InvalidKeyPath(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn malformed_path_is_rejected() {
+        for path in [
+            "",
+            "12381/3600/0/0/0",
+            "m/12381/3600",
+            "m/12381/3600/0",
+            "m/12381/3600/0/1/0",
+            "m/12381/3600/0/0/0/0",
+            "m/12381/3600/x/0/0",
+        ] {
+            assert!(matches!(
+                path.parse::<KeyPath>(),
+                Err(KeystoreError::InvalidKeyPath(_))
+            ));
+        }
+    }
+}
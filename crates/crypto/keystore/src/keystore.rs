@@ -1,20 +1,124 @@
-use std::{fs, path::Path};
+use std::{fs, io::Write, path::Path};
 
-use alloy_primitives::B256;
+use alloy_primitives::{B256, hex};
 use anyhow::{Result, anyhow, ensure};
 use ream_bls::{PrivateKey, PublicKey};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::{
+    decrypt::{Aes128Ctr, aes128_ctr},
+    error::KeystoreError,
+    function::{CipherFunction, KdfFunction},
+    hex_serde,
+    key_path::KeyPath,
+    pbkdf2::pbkdf2,
+    scrypt::scrypt,
+};
+
+/// Top level fields every EIP-2335 keystore carries.
+const KEYSTORE_FIELDS: [&str; 2] = ["crypto", "version"];
+
+/// Fields of the ``crypto`` module of an EIP-2335 keystore.
+const CRYPTO_FIELDS: [&str; 3] = ["kdf", "checksum", "cipher"];
+
+/// Length of the key derived by the KDF, which is split into the cipher key and checksum key.
+const DERIVED_KEY_LENGTH: u64 = 32;
+
+/// Number of cipher message bytes [`EncryptedKeystore::decrypt_into`] decrypts at a time.
+const DECRYPT_CHUNK_SIZE: usize = 4096;
+
+/// Bounds on the KDF parameters of a keystore, checked before the key is derived so an untrusted
+/// keystore cannot make the node spend unbounded time or memory. The defaults allow the EIP-2335
+/// recommended parameters and nothing more expensive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfBounds {
+    pub min_scrypt_n: u64,
+    pub max_scrypt_n: u64,
+    pub max_scrypt_r: u64,
+    pub max_scrypt_p: u64,
+    pub min_pbkdf2_c: u64,
+    pub max_pbkdf2_c: u64,
+}
+
+impl Default for KdfBounds {
+    fn default() -> Self {
+        Self {
+            min_scrypt_n: 1 << 14,
+            max_scrypt_n: 1 << 18,
+            max_scrypt_r: 8,
+            max_scrypt_p: 1,
+            min_pbkdf2_c: 1 << 14,
+            max_pbkdf2_c: 1 << 18,
+        }
+    }
+}
+
+impl KdfBounds {
+    /// Check ``params`` against these bounds, returning [`KeystoreError::UnsafeKdfParams`]
+    /// describing the first parameter out of range.
+    pub fn validate(&self, params: &KdfParams) -> Result<(), KeystoreError> {
+        let unsafe_param = |message: String| Err(KeystoreError::UnsafeKdfParams(message));
+        match params {
+            KdfParams::Pbkdf2 { c, dklen, .. } => {
+                if *dklen != DERIVED_KEY_LENGTH {
+                    return unsafe_param(format!(
+                        "dklen must be {DERIVED_KEY_LENGTH}, got {dklen}"
+                    ));
+                }
+                if !(self.min_pbkdf2_c..=self.max_pbkdf2_c).contains(c) {
+                    return unsafe_param(format!(
+                        "pbkdf2 c must be between {} and {}, got {c}",
+                        self.min_pbkdf2_c, self.max_pbkdf2_c
+                    ));
+                }
+            }
+            KdfParams::Scrypt { dklen, n, p, r, .. } => {
+                if *dklen != DERIVED_KEY_LENGTH {
+                    return unsafe_param(format!(
+                        "dklen must be {DERIVED_KEY_LENGTH}, got {dklen}"
+                    ));
+                }
+                if !(self.min_scrypt_n..=self.max_scrypt_n).contains(n) {
+                    return unsafe_param(format!(
+                        "scrypt n must be between {} and {}, got {n}",
+                        self.min_scrypt_n, self.max_scrypt_n
+                    ));
+                }
+                if !(1..=self.max_scrypt_r).contains(r) {
+                    return unsafe_param(format!(
+                        "scrypt r must be between 1 and {}, got {r}",
+                        self.max_scrypt_r
+                    ));
+                }
+                if !(1..=self.max_scrypt_p).contains(p) {
+                    return unsafe_param(format!(
+                        "scrypt p must be between 1 and {}, got {p}",
+                        self.max_scrypt_p
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
 
-use crate::{decrypt::aes128_ctr, hex_serde, pbkdf2::pbkdf2, scrypt::scrypt};
+/// Version of the EIP-2335 keystore format.
+const KEYSTORE_VERSION: u64 = 4;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct EncryptedKeystore {
     pub crypto: Crypto,
+    #[serde(default)]
     pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     #[serde(rename = "pubkey")]
     pub public_key: PublicKey,
     pub path: String,
+    #[serde(default)]
     pub uuid: String,
     pub version: u64,
 }
@@ -24,9 +128,156 @@ pub struct Keystore {
     pub private_key: PrivateKey,
 }
 
+/// The fields of an EIP-2335 keystore that describe the key rather than encrypt it. They are
+/// carried over unchanged when a keystore is re-encrypted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeystoreMetadata {
+    pub description: String,
+    pub name: Option<String>,
+    pub path: String,
+    /// A random version 4 UUID is generated on encryption if this is ``None``.
+    pub uuid: Option<String>,
+}
+
+impl Keystore {
+    /// Encrypt the private key with ``password``, using scrypt with the EIP-2335 recommended
+    /// parameters and a random salt.
+    pub fn encrypt(
+        &self,
+        password: &[u8],
+        metadata: KeystoreMetadata,
+    ) -> anyhow::Result<EncryptedKeystore> {
+        let kdf_params = KdfParams::Scrypt {
+            dklen: DERIVED_KEY_LENGTH,
+            n: 1 << 18,
+            p: 1,
+            r: 8,
+            salt: rand::random::<[u8; 32]>().to_vec(),
+        };
+        self.encrypt_with_kdf_params(password, kdf_params, metadata)
+    }
+
+    /// Encrypt the private key with ``password``, deriving the key with ``kdf_params``.
+    pub fn encrypt_with_kdf_params(
+        &self,
+        password: &[u8],
+        kdf_params: KdfParams,
+        metadata: KeystoreMetadata,
+    ) -> anyhow::Result<EncryptedKeystore> {
+        let derived_key = derive_key(password, &kdf_params)?;
+        let key: [u8; 16] = derived_key[0..16]
+            .try_into()
+            .map_err(|err| anyhow!("Failed to convert derived key into 16 byte array: {err:?}"))?;
+        let iv = rand::random::<[u8; 16]>();
+
+        let mut message = self.private_key.inner.to_vec();
+        aes128_ctr(&mut message, key, &iv);
+        let checksum = Sha256::digest([&derived_key[16..32], &message].concat()).to_vec();
+
+        Ok(EncryptedKeystore {
+            crypto: Crypto {
+                kdf: FunctionBlock {
+                    params: kdf_params,
+                    message: vec![],
+                },
+                checksum: FunctionBlock {
+                    params: ChecksumParams::Sha256 {},
+                    message: checksum,
+                },
+                cipher: FunctionBlock {
+                    params: CipherParams::Aes128Ctr { iv },
+                    message,
+                },
+            },
+            description: metadata.description,
+            name: metadata.name,
+            public_key: self.public_key.clone(),
+            path: metadata.path,
+            uuid: metadata.uuid.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            version: KEYSTORE_VERSION,
+        })
+    }
+}
+
 impl EncryptedKeystore {
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        Ok(serde_json::from_str(fs::read_to_string(path)?.as_str())?)
+        Self::from_json_str(fs::read_to_string(path)?.as_str())
+    }
+
+    /// Parse an EIP-2335 keystore, returning [`KeystoreError::NotAKeystore`] when the JSON lacks
+    /// the keystore structure (e.g. a wallet file or a keystore nested in another object).
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+
+        let mut missing_fields = KEYSTORE_FIELDS
+            .iter()
+            .filter(|field| value.get(field).is_none())
+            .map(|field| field.to_string())
+            .collect::<Vec<_>>();
+        if let Some(crypto) = value.get("crypto") {
+            missing_fields.extend(
+                CRYPTO_FIELDS
+                    .iter()
+                    .filter(|field| crypto.get(field).is_none())
+                    .map(|field| format!("crypto.{field}")),
+            );
+        }
+        if !missing_fields.is_empty() {
+            return Err(KeystoreError::NotAKeystore(missing_fields.join(", ")).into());
+        }
+
+        // Checked up front, serde would only report an unknown variant of the params
+        let function = |module: &str| {
+            value["crypto"][module]["function"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        };
+        function("kdf").parse::<KdfFunction>()?;
+        function("cipher").parse::<CipherFunction>()?;
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Parse ``path`` as an EIP-2334 key path. Keystores may leave ``path`` empty, in which case
+    /// ``None`` is returned.
+    pub fn key_path(&self) -> Result<Option<KeyPath>, KeystoreError> {
+        if self.path.is_empty() {
+            return Ok(None);
+        }
+        self.path.parse().map(Some)
+    }
+
+    /// The fields of this keystore that [`EncryptedKeystore::reencrypt`] carries over.
+    pub fn metadata(&self) -> KeystoreMetadata {
+        KeystoreMetadata {
+            description: self.description.clone(),
+            name: self.name.clone(),
+            path: self.path.clone(),
+            uuid: (!self.uuid.is_empty()).then(|| self.uuid.clone()),
+        }
+    }
+
+    /// Decrypt the keystore with ``password`` and encrypt it again with ``new_password``, using
+    /// the same KDF with a fresh salt. ``description``, ``name``, ``path`` and ``uuid`` are kept.
+    pub fn reencrypt(&self, password: &[u8], new_password: &[u8]) -> anyhow::Result<Self> {
+        let keystore = self.decrypt(password)?;
+        let kdf_params = match &self.crypto.kdf.params {
+            KdfParams::Pbkdf2 { c, dklen, .. } => KdfParams::Pbkdf2 {
+                c: *c,
+                dklen: *dklen,
+                prf: Prf::HmacSha256,
+                salt: rand::random::<[u8; 32]>().to_vec(),
+            },
+            KdfParams::Scrypt { dklen, n, p, r, .. } => KdfParams::Scrypt {
+                dklen: *dklen,
+                n: *n,
+                p: *p,
+                r: *r,
+                salt: rand::random::<[u8; 32]>().to_vec(),
+            },
+        };
+        keystore.encrypt_with_kdf_params(new_password, kdf_params, self.metadata())
     }
 
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
@@ -35,52 +286,32 @@ impl EncryptedKeystore {
     }
 
     pub fn validate_password(&self, password: &[u8]) -> anyhow::Result<bool> {
-        let derived_key = match &self.crypto.kdf.params {
-            KdfParams::Pbkdf2 {
-                c,
-                dklen,
-                prf: _,
-                salt,
-            } => pbkdf2(password, salt, *c, *dklen)?,
-            KdfParams::Scrypt {
-                n,
-                p,
-                r,
-                dklen,
-                salt,
-            } => scrypt(password, salt, *n, *p, *r, *dklen)?,
-        };
-        let derived_key_slice = &derived_key[16..32];
-        let pre_image = [derived_key_slice, &self.crypto.cipher.message].concat();
-        let checksum = Sha256::digest(&pre_image);
-        let valid_password = checksum.as_slice() == self.crypto.checksum.message.as_slice();
-        Ok(valid_password)
+        let derived_key = self.derive_key(password, &KdfBounds::default())?;
+        Ok(self.verify_checksum(&derived_key))
     }
 
     pub fn decrypt(&self, password: &[u8]) -> anyhow::Result<Keystore> {
-        let derived_key = match &self.crypto.kdf.params {
-            KdfParams::Pbkdf2 {
-                c,
-                dklen,
-                prf: _,
-                salt,
-            } => pbkdf2(password, salt, *c, *dklen)?,
-            KdfParams::Scrypt {
-                n,
-                p,
-                r,
-                dklen,
-                salt,
-            } => scrypt(password, salt, *n, *p, *r, *dklen)?,
-        };
-        let derived_key_slice = &derived_key[16..32];
-        let pre_image = [derived_key_slice, &self.crypto.cipher.message].concat();
-        let checksum = Sha256::digest(&pre_image);
+        self.decrypt_with_kdf_bounds(password, &KdfBounds::default())
+    }
+
+    /// Decrypt the keystore, rejecting it before running the KDF if its parameters fall outside
+    /// ``kdf_bounds``.
+    pub fn decrypt_with_kdf_bounds(
+        &self,
+        password: &[u8],
+        kdf_bounds: &KdfBounds,
+    ) -> anyhow::Result<Keystore> {
+        let derived_key = self.derive_key(password, kdf_bounds)?;
         ensure!(
-            checksum.as_slice() == self.crypto.checksum.message.as_slice(),
-            "Password provided is invalid!"
+            self.verify_checksum(&derived_key),
+            KeystoreError::InvalidPassword
         );
 
+        // The checksum only proves the message was encrypted with this password, not its length
+        ensure!(
+            self.crypto.cipher.message.len() == B256::len_bytes(),
+            KeystoreError::InvalidSecretLength(self.crypto.cipher.message.len())
+        );
         let mut private_key = PrivateKey {
             inner: B256::from_slice(self.crypto.cipher.message.as_slice()),
         };
@@ -89,17 +320,94 @@ impl EncryptedKeystore {
                 let key_param: [u8; 16] = derived_key[0..16].try_into().map_err(|err| {
                     anyhow!("Failed to convert derived key into 16 byte array: {err:?}")
                 })?;
-                let iv_param: &[u8; 16] = iv.as_slice().try_into().map_err(|err| {
-                    anyhow!("Failed to convert derived key into 16 byte array: {err:?}")
-                })?;
-                aes128_ctr(private_key.inner.as_mut_slice(), key_param, iv_param);
+                aes128_ctr(private_key.inner.as_mut_slice(), key_param, iv);
             }
         };
+
+        // A secret that does not match ``pubkey`` means the keystore is corrupt or mislabeled
+        let public_key = private_key
+            .public_key()
+            .map_err(|err| anyhow!("Failed to derive public key: {err:?}"))?;
+        ensure!(
+            public_key == self.public_key,
+            KeystoreError::PublicKeyMismatch(format!(
+                "0x{}",
+                hex::encode(self.public_key.to_bytes())
+            ))
+        );
+
         Ok(Keystore {
-            public_key: self.public_key.clone(),
+            public_key,
             private_key,
         })
     }
+
+    /// Decrypt the cipher message into ``out`` in chunks of ``DECRYPT_CHUNK_SIZE`` bytes, without
+    /// buffering the whole secret. The checksum is verified over the full cipher message before
+    /// any plaintext is written, so a wrong password writes nothing.
+    pub fn decrypt_into<W: Write>(&self, password: &[u8], out: &mut W) -> anyhow::Result<()> {
+        let derived_key = self.derive_key(password, &KdfBounds::default())?;
+        ensure!(
+            self.verify_checksum(&derived_key),
+            KeystoreError::InvalidPassword
+        );
+
+        match &self.crypto.cipher.params {
+            CipherParams::Aes128Ctr { iv } => {
+                let key_param: [u8; 16] = derived_key[0..16].try_into().map_err(|err| {
+                    anyhow!("Failed to convert derived key into 16 byte array: {err:?}")
+                })?;
+                let mut keystream = Aes128Ctr::new(key_param, iv);
+                let mut buffer = [0; DECRYPT_CHUNK_SIZE];
+                for chunk in self.crypto.cipher.message.chunks(DECRYPT_CHUNK_SIZE) {
+                    let buffer = &mut buffer[..chunk.len()];
+                    buffer.copy_from_slice(chunk);
+                    keystream.apply_keystream(buffer);
+                    out.write_all(buffer)?;
+                }
+                buffer.fill(0);
+            }
+        };
+        Ok(())
+    }
+
+    fn derive_key(&self, password: &[u8], kdf_bounds: &KdfBounds) -> anyhow::Result<Vec<u8>> {
+        kdf_bounds.validate(&self.crypto.kdf.params)?;
+        derive_key(password, &self.crypto.kdf.params)
+    }
+
+    /// Check the EIP-2335 checksum of the cipher message against ``derived_key``. The comparison
+    /// runs in constant time, so its duration does not reveal how much of the checksum matched.
+    fn verify_checksum(&self, derived_key: &[u8]) -> bool {
+        let mut hasher = Sha256::new();
+        hasher.update(&derived_key[16..32]);
+        for chunk in self.crypto.cipher.message.chunks(DECRYPT_CHUNK_SIZE) {
+            hasher.update(chunk);
+        }
+        hasher
+            .finalize()
+            .as_slice()
+            .ct_eq(self.crypto.checksum.message.as_slice())
+            .into()
+    }
+}
+
+fn derive_key(password: &[u8], kdf_params: &KdfParams) -> anyhow::Result<Vec<u8>> {
+    match kdf_params {
+        KdfParams::Pbkdf2 {
+            c,
+            dklen,
+            prf: _,
+            salt,
+        } => pbkdf2(password, salt, *c, *dklen),
+        KdfParams::Scrypt {
+            n,
+            p,
+            r,
+            dklen,
+            salt,
+        } => scrypt(password, salt, *n, *p, *r, *dklen),
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -117,6 +425,8 @@ pub struct FunctionBlock<ParamType> {
     pub message: Vec<u8>,
 }
 
+/// Parameters of the KDF. EIP-2335 does not fix the length of the salt, so unlike the cipher IV
+/// it is kept as a vector.
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(tag = "function", content = "params", rename_all = "lowercase")]
 pub enum KdfParams {
@@ -137,6 +447,15 @@ pub enum KdfParams {
     },
 }
 
+impl KdfParams {
+    pub fn function(&self) -> KdfFunction {
+        match self {
+            KdfParams::Pbkdf2 { .. } => KdfFunction::Pbkdf2,
+            KdfParams::Scrypt { .. } => KdfFunction::Scrypt,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum Prf {
@@ -148,11 +467,19 @@ pub enum Prf {
 pub enum CipherParams {
     #[serde(rename = "aes-128-ctr")]
     Aes128Ctr {
-        #[serde(with = "hex_serde")]
-        iv: Vec<u8>,
+        #[serde(with = "hex_serde::fixed")]
+        iv: [u8; 16],
     },
 }
 
+impl CipherParams {
+    pub fn function(&self) -> CipherFunction {
+        match self {
+            CipherParams::Aes128Ctr { .. } => CipherFunction::Aes128Ctr,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(tag = "function", content = "params", rename_all = "lowercase")]
 pub enum ChecksumParams {
@@ -186,13 +513,12 @@ mod tests {
                     message: vec![0x01, 0x02, 0x03, 0x04],
                 },
                 cipher: FunctionBlock {
-                    params: CipherParams::Aes128Ctr {
-                        iv: vec![0xAA, 0xBB, 0xCC, 0xDD],
-                    },
+                    params: CipherParams::Aes128Ctr { iv: [0xAA; 16] },
                     message: vec![0x11, 0x22, 0x33, 0x44],
                 },
             },
             description: "Test Keystore".to_string(),
+            name: None,
             public_key: PublicKey {
                 inner: FixedVector::from(vec![0x12; 48]),
             },
@@ -201,7 +527,7 @@ mod tests {
             version: 4,
         };
 
-        let keystore_as_string = r#"{"crypto":{"kdf":{"function":"scrypt","params":{"dklen":32,"n":262144,"p":1,"r":8,"salt":"12345678"},"message":"90abcdef"},"checksum":{"function":"sha256","params":{},"message":"01020304"},"cipher":{"function":"aes-128-ctr","params":{"iv":"aabbccdd"},"message":"11223344"}},"description":"Test Keystore","pubkey":"0x121212121212121212121212121212121212121212121212121212121212121212121212121212121212121212121212","path":"m/44'/60'/0'/0/0","uuid":"123e4567-e89b-12d3-a456-426614174000","version":4}"#;
+        let keystore_as_string = r#"{"crypto":{"kdf":{"function":"scrypt","params":{"dklen":32,"n":262144,"p":1,"r":8,"salt":"0x12345678"},"message":"0x90abcdef"},"checksum":{"function":"sha256","params":{},"message":"0x01020304"},"cipher":{"function":"aes-128-ctr","params":{"iv":"0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"},"message":"0x11223344"}},"description":"Test Keystore","pubkey":"0x121212121212121212121212121212121212121212121212121212121212121212121212121212121212121212121212","path":"m/44'/60'/0'/0/0","uuid":"123e4567-e89b-12d3-a456-426614174000","version":4}"#;
 
         let serialized = serde_json::to_string(&keystore).expect("Failed to serialize keystore");
         assert_eq!(serialized, keystore_as_string);
@@ -233,13 +559,14 @@ mod tests {
                         },
                         cipher: FunctionBlock {
                             params: CipherParams::Aes128Ctr {
-                                iv: hex::decode("180742384a64fedc51147799da529dd0").expect("Failed to decode IV"),
+                                iv: hex!("180742384a64fedc51147799da529dd0"),
                             },
                             message: hex::decode("ae3a00597d61d570b767704edb925e2fe2dd474ea1145c62ac04a2484a322e3d")
                                 .expect("Failed to decode cipher message"),
                         },
                     },
                     description: "".to_string(),
+                    name: None,
                     public_key: PublicKey {
                         inner: FixedVector::from(
                             hex::decode(
@@ -261,6 +588,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_not_a_keystore() {
+        let wallet = r#"{"name":"wallet","nextaccount":1,"keystore":{"crypto":{},"version":4}}"#;
+
+        let err = EncryptedKeystore::from_json_str(wallet).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<KeystoreError>(),
+            Some(KeystoreError::NotAKeystore(fields)) if fields == "crypto, version"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "JSON is not an EIP-2335 keystore, missing fields: crypto, version"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_crypto_functions() {
+        let keystore = r#"{"crypto":{"kdf":{"function":"argon2id","params":{},"message":""},"checksum":{"function":"sha256","params":{},"message":""},"cipher":{"function":"aes-128-ctr","params":{"iv":"0xaabbccddaabbccddaabbccddaabbccdd"},"message":""}},"pubkey":"","path":"","version":4}"#;
+        let err = EncryptedKeystore::from_json_str(keystore).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<KeystoreError>(),
+            Some(KeystoreError::UnsupportedKdf(function)) if function == "argon2id"
+        ));
+
+        let keystore = keystore
+            .replace("argon2id", "scrypt")
+            .replace("aes-128-ctr", "aes-256-gcm");
+        let err = EncryptedKeystore::from_json_str(&keystore).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<KeystoreError>(),
+            Some(KeystoreError::UnsupportedCipher(function)) if function == "aes-256-gcm"
+        ));
+    }
+
+    #[test]
+    fn test_truncated_iv_is_rejected_on_load() {
+        let keystore = std::fs::read_to_string("./assets/Pbkdf2TestKeystore.json").unwrap();
+        let truncated = keystore.replace(
+            "264daa3f303d7259501c93d997d84fe6",
+            "264daa3f303d7259501c93d997d84f",
+        );
+        assert_ne!(truncated, keystore);
+
+        let err = EncryptedKeystore::from_json_str(&truncated).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("expected 16 bytes of hex, got 15 bytes"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn password_validation_pbkdf2() {
         let keystore =
@@ -287,6 +665,59 @@ mod tests {
         assert!(!keystore.validate_password(password).unwrap());
     }
 
+    #[test]
+    fn wrong_checksum_is_rejected() {
+        let mut keystore =
+            EncryptedKeystore::load_from_file("./assets/Pbkdf2TestKeystore.json").unwrap();
+        let password = hex!("7465737470617373776f7264f09f9491");
+        keystore.crypto.checksum.message[31] ^= 1;
+
+        assert!(!keystore.validate_password(&password).unwrap());
+        assert!(keystore.decrypt(&password).is_err());
+    }
+
+    #[test]
+    fn too_large_scrypt_n_is_rejected() {
+        let mut keystore =
+            EncryptedKeystore::load_from_file("./assets/ScryptDecryptionTest.json").unwrap();
+        let password = hex!("7465737470617373776f7264f09f9491");
+        if let KdfParams::Scrypt { n, .. } = &mut keystore.crypto.kdf.params {
+            *n = 1 << 40;
+        }
+
+        let err = keystore.decrypt(&password).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<KeystoreError>(),
+            Some(KeystoreError::UnsafeKdfParams(_))
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Unsafe keystore KDF parameters: scrypt n must be between 16384 and 262144, got 1099511627776"
+        );
+        assert!(keystore.validate_password(&password).is_err());
+    }
+
+    #[test]
+    fn standard_kdf_params_are_accepted() {
+        for path in [
+            "./assets/Pbkdf2TestKeystore.json",
+            "./assets/ScryptDecryptionTest.json",
+        ] {
+            let keystore = EncryptedKeystore::load_from_file(path).unwrap();
+            KdfBounds::default()
+                .validate(&keystore.crypto.kdf.params)
+                .unwrap();
+        }
+
+        let custom_bounds = KdfBounds {
+            max_scrypt_n: 1 << 17,
+            ..Default::default()
+        };
+        let keystore =
+            EncryptedKeystore::load_from_file("./assets/ScryptDecryptionTest.json").unwrap();
+        assert!(custom_bounds.validate(&keystore.crypto.kdf.params).is_err());
+    }
+
     #[test]
     fn decrypt_pbkdf2() {
         let keystore =
@@ -322,4 +753,192 @@ mod tests {
             private_key
         );
     }
+
+    #[test]
+    fn decrypt_into_matches_buffered_decrypt() {
+        let keystore =
+            EncryptedKeystore::load_from_file("./assets/Pbkdf2TestKeystore.json").unwrap();
+        let password = hex!("7465737470617373776f7264f09f9491");
+
+        let mut plaintext = vec![];
+        keystore.decrypt_into(&password, &mut plaintext).unwrap();
+        assert_eq!(
+            plaintext,
+            keystore
+                .decrypt(&password)
+                .unwrap()
+                .private_key
+                .inner
+                .as_slice()
+        );
+    }
+
+    #[test]
+    fn decrypt_into_large_secret() {
+        let mut keystore =
+            EncryptedKeystore::load_from_file("./assets/Pbkdf2TestKeystore.json").unwrap();
+        let password = hex!("7465737470617373776f7264f09f9491");
+        let derived_key = keystore
+            .derive_key(&password, &KdfBounds::default())
+            .unwrap();
+        let CipherParams::Aes128Ctr { iv } = &keystore.crypto.cipher.params;
+        let key: [u8; 16] = derived_key[0..16].try_into().unwrap();
+        let iv = *iv;
+
+        // Not a multiple of the chunk or AES block size, so the last chunk is partial
+        let secret = (0..3 * DECRYPT_CHUNK_SIZE + 7)
+            .map(|index| (index % 251) as u8)
+            .collect::<Vec<_>>();
+        let mut ciphertext = secret.clone();
+        aes128_ctr(&mut ciphertext, key, &iv);
+        keystore.crypto.checksum.message =
+            Sha256::digest([&derived_key[16..32], &ciphertext].concat()).to_vec();
+        keystore.crypto.cipher.message = ciphertext.clone();
+
+        let mut buffered = ciphertext;
+        aes128_ctr(&mut buffered, key, &iv);
+        let mut streamed = vec![];
+        keystore.decrypt_into(&password, &mut streamed).unwrap();
+        assert_eq!(streamed, buffered);
+        assert_eq!(streamed, secret);
+
+        let mut streamed = vec![];
+        assert!(
+            keystore
+                .decrypt_into(b"password123", &mut streamed)
+                .is_err()
+        );
+        assert!(streamed.is_empty());
+    }
+
+    fn pbkdf2_params() -> KdfParams {
+        KdfParams::Pbkdf2 {
+            c: 1 << 14,
+            dklen: 32,
+            prf: Prf::HmacSha256,
+            salt: vec![0x42; 32],
+        }
+    }
+
+    #[test]
+    fn encrypt_generates_uuid_v4() {
+        let keystore =
+            EncryptedKeystore::load_from_file("./assets/Pbkdf2TestKeystore.json").unwrap();
+        let password = hex!("7465737470617373776f7264f09f9491");
+        let keystore = keystore.decrypt(&password).unwrap();
+
+        let first = keystore
+            .encrypt_with_kdf_params(b"password123", pbkdf2_params(), KeystoreMetadata::default())
+            .unwrap();
+        let second = keystore
+            .encrypt_with_kdf_params(b"password123", pbkdf2_params(), KeystoreMetadata::default())
+            .unwrap();
+        assert_ne!(first.uuid, second.uuid);
+        for uuid in [&first.uuid, &second.uuid] {
+            let groups = uuid.split('-').map(str::len).collect::<Vec<_>>();
+            assert_eq!(groups, vec![8, 4, 4, 4, 12]);
+            assert_eq!(&uuid[14..15], "4");
+            assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+        }
+
+        let uuid = "8f6774f8-3b29-448f-b407-499fb1e98a20".to_string();
+        let encrypted = keystore
+            .encrypt_with_kdf_params(
+                b"password123",
+                pbkdf2_params(),
+                KeystoreMetadata {
+                    uuid: Some(uuid.clone()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(encrypted.uuid, uuid);
+    }
+
+    #[test]
+    fn metadata_round_trips_through_reencrypt() {
+        let keystore =
+            EncryptedKeystore::load_from_file("./assets/Pbkdf2TestKeystore.json").unwrap();
+        let password = hex!("7465737470617373776f7264f09f9491");
+        let metadata = KeystoreMetadata {
+            description: "Validator 0".to_string(),
+            name: Some("validator-0".to_string()),
+            path: "m/12381/3600/0/0/0".to_string(),
+            uuid: None,
+        };
+        let encrypted = keystore
+            .decrypt(&password)
+            .unwrap()
+            .encrypt_with_kdf_params(b"password123", pbkdf2_params(), metadata.clone())
+            .unwrap();
+
+        let parsed =
+            EncryptedKeystore::from_json_str(&serde_json::to_string(&encrypted).unwrap()).unwrap();
+        assert_eq!(parsed, encrypted);
+        assert_eq!(parsed.description, metadata.description);
+        assert_eq!(parsed.name, metadata.name);
+
+        let reencrypted = parsed.reencrypt(b"password123", b"new password").unwrap();
+        assert_eq!(reencrypted.metadata(), parsed.metadata());
+        assert_ne!(reencrypted.crypto, parsed.crypto);
+        assert_eq!(
+            reencrypted
+                .decrypt(b"new password")
+                .unwrap()
+                .private_key
+                .inner,
+            keystore.decrypt(&password).unwrap().private_key.inner
+        );
+        assert!(reencrypted.decrypt(b"password123").is_err());
+    }
+
+    #[test]
+    fn keystore_without_name_omits_it() {
+        let keystore = EncryptedKeystore::load_from_file("./assets/ScryptKeystore.json").unwrap();
+        assert_eq!(keystore.name, None);
+        assert!(
+            !serde_json::to_string(&keystore)
+                .unwrap()
+                .contains("\"name\"")
+        );
+    }
+
+    #[test]
+    fn mismatched_public_key_is_rejected() {
+        let mut keystore =
+            EncryptedKeystore::load_from_file("./assets/Pbkdf2TestKeystore.json").unwrap();
+        let password = hex!("7465737470617373776f7264f09f9491");
+        keystore.public_key = EncryptedKeystore::load_from_file("./assets/ScryptKeystore.json")
+            .unwrap()
+            .public_key;
+
+        let err = keystore.decrypt(&password).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<KeystoreError>(),
+            Some(KeystoreError::PublicKeyMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn short_secret_is_rejected() {
+        let mut keystore =
+            EncryptedKeystore::load_from_file("./assets/Pbkdf2TestKeystore.json").unwrap();
+        let password = hex!("7465737470617373776f7264f09f9491");
+        keystore.crypto.cipher.message.truncate(31);
+        // Recompute the checksum, so the truncated message passes the password check
+        let derived_key = keystore
+            .derive_key(&password, &KdfBounds::default())
+            .unwrap();
+        keystore.crypto.checksum.message = Sha256::new()
+            .chain_update(&derived_key[16..32])
+            .chain_update(&keystore.crypto.cipher.message)
+            .finalize()
+            .to_vec();
+
+        let err = keystore.decrypt(&password).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<KeystoreError>(),
+            Some(KeystoreError::InvalidSecretLength(31))
+        ));
+    }
 }
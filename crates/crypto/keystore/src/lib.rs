@@ -1,6 +1,11 @@
+pub mod async_decrypt;
 pub mod decrypt;
+pub mod error;
+pub mod function;
 pub mod hex_serde;
 pub mod hmac;
+pub mod inspect;
+pub mod key_path;
 pub mod keystore;
 pub mod pbkdf2;
 pub mod salsa;
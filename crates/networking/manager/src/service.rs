@@ -7,6 +7,7 @@ use std::{
 use anyhow::anyhow;
 use discv5::multiaddr::PeerId;
 use libp2p::swarm::ConnectionId;
+use ream_beacon_api_types::events::BeaconEvent;
 use ream_beacon_chain::beacon_chain::BeaconChain;
 use ream_consensus::{blob_sidecar::BlobIdentifier, constants::genesis_validators_root};
 use ream_discv5::{
@@ -39,7 +40,10 @@ use ream_p2p::{
 };
 use ream_storage::{db::ReamDB, tables::Table};
 use ream_syncer::block_range::BlockRangeSyncer;
-use tokio::{sync::mpsc, time::interval};
+use tokio::{
+    sync::{broadcast, mpsc},
+    time::interval,
+};
 use tracing::{error, info, trace, warn};
 use tree_hash::TreeHash;
 
@@ -61,6 +65,7 @@ impl ManagerService {
         ream_db: ReamDB,
         ream_dir: PathBuf,
         operation_pool: Arc<OperationPool>,
+        event_sender: broadcast::Sender<BeaconEvent>,
     ) -> anyhow::Result<Self> {
         let discv5_config = discv5::ConfigBuilder::new(discv5::ListenConfig::from_ip(
             config.socket_address,
@@ -154,6 +159,7 @@ impl ManagerService {
             ream_db.clone(),
             operation_pool,
             execution_engine,
+            event_sender,
         ));
         let status = beacon_chain.build_status_request().await?;
 
@@ -195,7 +195,15 @@ impl Network {
                 .build()
         };
 
+        let peer_id = PeerId::from_public_key(&PublicKey::from(local_key.public().clone()));
         let network_state = Arc::new(NetworkState {
+            local_peer_id: peer_id,
+            local_enr: swarm.behaviour().discovery.local_enr().clone(),
+            p2p_addresses: vec![
+                Multiaddr::from(config.socket_address)
+                    .with(Protocol::Tcp(config.socket_port))
+                    .with(Protocol::P2p(peer_id)),
+            ],
             peer_table: RwLock::new(HashMap::new()),
             meta_data: RwLock::new(
                 read_meta_data_from_disk(config.data_dir.clone()).unwrap_or_else(|err| {
@@ -208,7 +216,7 @@ impl Network {
         });
 
         let mut network = Network {
-            peer_id: PeerId::from_public_key(&PublicKey::from(local_key.public().clone())),
+            peer_id,
             swarm,
             subscribed_topics: Arc::new(Mutex::new(HashSet::new())),
             callbacks: HashMap::new(),
@@ -2,7 +2,7 @@ use std::{collections::HashMap, fs, path::PathBuf};
 
 use anyhow::anyhow;
 use discv5::Enr;
-use libp2p::{Multiaddr, PeerId};
+use libp2p::{Multiaddr, PeerId, multiaddr::Protocol};
 use parking_lot::RwLock;
 use ssz::Encode;
 
@@ -13,6 +13,10 @@ use crate::{
 };
 
 pub struct NetworkState {
+    pub local_peer_id: PeerId,
+    pub local_enr: Enr,
+    /// Multiaddrs libp2p listens on, including the local peer id
+    pub p2p_addresses: Vec<Multiaddr>,
     pub peer_table: RwLock<HashMap<PeerId, CachedPeer>>,
     pub meta_data: RwLock<GetMetaDataV2>,
     pub status: RwLock<Status>,
@@ -20,6 +24,26 @@ pub struct NetworkState {
 }
 
 impl NetworkState {
+    /// Multiaddrs of the UDP sockets advertised for discovery in the local ENR.
+    pub fn discovery_addresses(&self) -> Vec<Multiaddr> {
+        let mut discovery_addresses = vec![];
+        if let Some(socket) = self.local_enr.udp4_socket() {
+            discovery_addresses.push(
+                Multiaddr::from(*socket.ip())
+                    .with(Protocol::Udp(socket.port()))
+                    .with(Protocol::P2p(self.local_peer_id)),
+            );
+        }
+        if let Some(socket) = self.local_enr.udp6_socket() {
+            discovery_addresses.push(
+                Multiaddr::from(*socket.ip())
+                    .with(Protocol::Udp(socket.port()))
+                    .with(Protocol::P2p(self.local_peer_id)),
+            );
+        }
+        discovery_addresses
+    }
+
     pub fn upsert_peer(
         &self,
         peer_id: PeerId,
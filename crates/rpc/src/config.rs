@@ -1,9 +1,17 @@
-use std::net::{IpAddr, SocketAddr};
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use crate::{rate_limit::RateLimitConfig, shutdown::DEFAULT_SHUTDOWN_TIMEOUT};
 
 #[derive(Debug, Clone)]
 pub struct RpcServerConfig {
     pub http_socket_address: SocketAddr,
     pub http_allow_origin: bool,
+    pub rate_limit: RateLimitConfig,
+    /// How long in-flight requests get to complete on shutdown
+    pub shutdown_timeout: Duration,
 }
 
 impl RpcServerConfig {
@@ -12,6 +20,8 @@ impl RpcServerConfig {
         Self {
             http_socket_address: SocketAddr::new(http_address, http_port),
             http_allow_origin,
+            rate_limit: RateLimitConfig::default(),
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
         }
     }
 }
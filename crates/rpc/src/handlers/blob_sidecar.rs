@@ -7,58 +7,80 @@ use ream_beacon_api_types::{
     error::ApiError, id::ID, query::BlobSidecarQuery, responses::BeaconVersionedResponse,
 };
 use ream_consensus::blob_sidecar::BlobIdentifier;
-use ream_storage::{db::ReamDB, tables::Table};
-use tree_hash::TreeHash;
+use ream_storage::db::ReamDB;
 
-use crate::handlers::block::get_beacon_block_from_id;
+use crate::handlers::block::{get_beacon_block_from_id, get_block_root_from_id};
 
+/// Called by `/eth/v1/beacon/blob_sidecars/{block_id}` to get the blob sidecars of a block.
+/// Optional parameter `indices` selects specific blob indices, a block without blobs returns an
+/// empty list.
 #[get("/beacon/blob_sidecars/{block_id}")]
 pub async fn get_blob_sidecars(
     db: Data<ReamDB>,
     block_id: Path<ID>,
     query: Query<BlobSidecarQuery>,
 ) -> Result<impl Responder, ApiError> {
-    let beacon_block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
-    let block_root = beacon_block.message.tree_hash_root();
-
-    let indices = if let Some(indices) = &query.indices {
-        let max_index = beacon_block.message.body.blob_kzg_commitments.len() as u64;
-        for index in indices {
-            if index >= &max_index {
-                return Err(ApiError::BadRequest(format!(
-                    "Invalid blob index: {index}, max index is {max_index}"
-                )));
-            }
-        }
-        indices
-    } else {
-        &(0..beacon_block.message.body.blob_kzg_commitments.len() as u64).collect()
-    };
-
-    let mut blob_sidecars = vec![];
+    let block_root = get_block_root_from_id(block_id.into_inner(), db.get_ref()).await?;
+    let beacon_block = get_beacon_block_from_id(ID::Root(block_root), db.get_ref()).await?;
+    let indices = select_blob_indices(
+        beacon_block.message.body.blob_kzg_commitments.len() as u64,
+        query.indices.as_deref(),
+    )?;
 
+    let blob_sidecar_provider = db.blob_sidecar_provider();
+    let mut blob_sidecars = Vec::with_capacity(indices.len());
     for index in indices {
-        let blob_and_proof = db
-            .blobs_and_proofs_provider()
-            .get(BlobIdentifier::new(block_root, *index))
+        let blob_sidecar = blob_sidecar_provider
+            .get(BlobIdentifier::new(block_root, index))
             .map_err(|err| {
                 ApiError::InternalError(format!(
-                    "Failed to get blob and proof for index: {index}, error: {err:?}"
+                    "Failed to get blob sidecar for index: {index}, error: {err:?}"
                 ))
             })?
             .ok_or(ApiError::NotFound(format!(
-                "Failed to get blob and proof for index: {index}"
+                "Failed to get blob sidecar for index: {index}"
             )))?;
-        blob_sidecars.push(
-            beacon_block
-                .blob_sidecar(blob_and_proof, *index)
-                .map_err(|err| {
-                    ApiError::InternalError(format!(
-                        "Failed to create blob sidecar for index: {index}, error: {err:?}"
-                    ))
-                })?,
-        );
+        blob_sidecars.push(blob_sidecar);
     }
 
     Ok(HttpResponse::Ok().json(BeaconVersionedResponse::new(blob_sidecars)))
 }
+
+/// Returns the requested blob `indices` of a block with `blob_count` blobs, or all of them if no
+/// indices were requested.
+fn select_blob_indices(blob_count: u64, indices: Option<&[u64]>) -> Result<Vec<u64>, ApiError> {
+    let Some(indices) = indices else {
+        return Ok((0..blob_count).collect());
+    };
+
+    if let Some(index) = indices.iter().find(|&&index| index >= blob_count) {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid blob index: {index}, max index is {blob_count}"
+        )));
+    }
+
+    Ok(indices.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_blob_indices_subset() {
+        assert_eq!(select_blob_indices(6, Some(&[1, 4])).unwrap(), vec![1, 4]);
+        assert_eq!(
+            select_blob_indices(6, None).unwrap(),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+        assert!(matches!(
+            select_blob_indices(6, Some(&[2, 6])),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_select_blob_indices_without_blobs() {
+        assert_eq!(select_blob_indices(0, None).unwrap(), Vec::<u64>::new());
+    }
+}
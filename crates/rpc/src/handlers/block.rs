@@ -5,37 +5,79 @@ use std::{
 
 use actix_web::{
     HttpResponse, Responder, get,
-    web::{Data, Path},
+    web::{Data, Path, Query},
 };
 use alloy_primitives::B256;
 use hashbrown::HashMap;
 use ream_beacon_api_types::{
     error::ApiError,
     id::ID,
+    query::DedupQuery,
     responses::{
-        BeaconHeadResponse, BeaconResponse, BeaconVersionedResponse, DataResponse, RootResponse,
+        ApiResponse, BeaconHeadResponse, ETH_CONSENSUS_VERSION_HEADER, RootResponse, VERSION,
     },
 };
 use ream_consensus::{
     attester_slashing::AttesterSlashing,
+    checkpoint::Checkpoint,
     constants::{
-        EFFECTIVE_BALANCE_INCREMENT, PROPOSER_WEIGHT, SLOTS_PER_EPOCH, SYNC_COMMITTEE_SIZE,
-        SYNC_REWARD_WEIGHT, WEIGHT_DENOMINATOR, WHISTLEBLOWER_REWARD_QUOTIENT,
-        genesis_validators_root,
+        EFFECTIVE_BALANCE_INCREMENT, GENESIS_SLOT, PARTICIPATION_FLAG_WEIGHTS, PROPOSER_WEIGHT,
+        WEIGHT_DENOMINATOR, WHISTLEBLOWER_REWARD_QUOTIENT_ELECTRA, genesis_validators_root,
+    },
+    electra::{
+        beacon_block::{BeaconBlock, SignedBeaconBlock},
+        beacon_state::BeaconState,
     },
-    electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
     genesis::Genesis,
 };
-use ream_fork_choice::store::Store;
 use ream_network_spec::networks::network_spec;
-use ream_operation_pool::OperationPool;
-use ream_storage::{
-    db::ReamDB,
-    tables::{Field, Table},
-};
+use ream_storage::errors::StoreError;
 use serde::{Deserialize, Serialize};
-use tree_hash::TreeHash;
+use thiserror::Error;
+
+use crate::{metrics::time_db_read, retry::read_with_retry, store::BeaconStore};
+
+/// Errors raised while reading blocks and their states for the block handlers.
+///
+/// Missing values map to a 404, a node that is still syncing to a 503 and every other failure to
+/// a 500, with the source error in the message.
+#[derive(Debug, Error)]
+pub enum BlockHandlerError {
+    #[error("Storage error: {0}")]
+    Store(#[from] StoreError),
+
+    #[error("Fork choice error: {0:?}")]
+    ForkChoice(#[from] anyhow::Error),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    NotSynced(String),
+
+    #[error("Database inconsistency: {0}")]
+    Inconsistent(String),
+}
+
+impl From<BlockHandlerError> for ApiError {
+    fn from(err: BlockHandlerError) -> Self {
+        match err {
+            BlockHandlerError::NotFound(message)
+            | BlockHandlerError::Store(StoreError::NotFound(message)) => {
+                ApiError::NotFound(message)
+            }
+            BlockHandlerError::NotSynced(message) => ApiError::NotSynced(message),
+            err => ApiError::InternalError(err.to_string()),
+        }
+    }
+}
 
+/// Proposer rewards for a block, in Gwei.
+///
+/// Electra blocks pay the proposer only for the included attestations, sync aggregate and
+/// slashings; deposits, voluntary exits, BLS changes and execution requests carry no proposer
+/// reward. `total` is therefore always
+/// `attestations + sync_aggregate + proposer_slashings + attester_slashings`.
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct BlockRewards {
     #[serde(with = "serde_utils::quoted_u64")]
@@ -52,79 +94,188 @@ pub struct BlockRewards {
     pub attester_slashings: u64,
 }
 
-pub async fn get_block_root_from_id(block_id: ID, db: &ReamDB) -> Result<B256, ApiError> {
-    let block_root = match block_id {
-        ID::Finalized => {
-            let finalized_checkpoint = db.finalized_checkpoint_provider().get().map_err(|err| {
-                ApiError::InternalError(format!(
-                    "Failed to get block by block_root, error: {err:?}"
-                ))
-            })?;
-
-            Ok(Some(finalized_checkpoint.root))
+impl BlockRewards {
+    pub fn new(
+        proposer_index: u64,
+        attestations: u64,
+        sync_aggregate: u64,
+        proposer_slashings: u64,
+        attester_slashings: u64,
+    ) -> Self {
+        Self {
+            proposer_index,
+            total: attestations + sync_aggregate + proposer_slashings + attester_slashings,
+            attestations,
+            sync_aggregate,
+            proposer_slashings,
+            attester_slashings,
         }
-        ID::Justified => {
-            let justified_checkpoint = db.justified_checkpoint_provider().get().map_err(|err| {
-                ApiError::InternalError(format!(
-                    "Failed to get block by block_root, error: {err:?}"
-                ))
-            })?;
+    }
+}
 
-            Ok(Some(justified_checkpoint.root))
+/// Resolves `block_id` to a block root. A skipped slot has no block, so `ID::Slot` of a skipped
+/// slot is `NotFound`. States do exist at skipped slots, see `get_state_from_id`.
+pub async fn get_block_root_from_id(
+    block_id: ID,
+    store: &dyn BeaconStore,
+) -> Result<B256, BlockHandlerError> {
+    let block_root = match block_id {
+        ID::Finalized => Some(
+            get_checkpoint_block_root(
+                "finalized",
+                read_with_retry(|| {
+                    time_db_read("finalized_checkpoint", || store.finalized_checkpoint())
+                })
+                .await,
+                store,
+            )
+            .await?,
+        ),
+        ID::Justified => Some(
+            get_checkpoint_block_root(
+                "justified",
+                read_with_retry(|| {
+                    time_db_read("justified_checkpoint", || store.justified_checkpoint())
+                })
+                .await,
+                store,
+            )
+            .await?,
+        ),
+        ID::Head => {
+            // Fork choice starts from the justified checkpoint, there is no head without it
+            get_synced_checkpoint(
+                "justified",
+                read_with_retry(|| {
+                    time_db_read("justified_checkpoint", || store.justified_checkpoint())
+                })
+                .await,
+            )?;
+            Some(time_db_read("head", || store.head_root()).map_err(BlockHandlerError::from)?)
         }
-        ID::Head | ID::Genesis => {
-            return Err(ApiError::NotFound(format!(
-                "This ID type is currently not supported: {block_id:?}"
-            )));
+        ID::Genesis => {
+            read_with_retry(|| {
+                time_db_read("slot_index", || store.block_root_at_slot(GENESIS_SLOT))
+            })
+            .await?
         }
-        ID::Slot(slot) => db.slot_index_provider().get(slot),
-        ID::Root(root) => Ok(Some(root)),
-    }
-    .map_err(|err| {
-        ApiError::InternalError(format!("Failed to get block by block_root, error: {err:?}"))
-    })?
-    .ok_or_else(|| ApiError::NotFound(format!("Failed to find `block_root` from {block_id:?}")))?;
+        ID::Slot(slot) => {
+            read_with_retry(|| time_db_read("slot_index", || store.block_root_at_slot(slot)))
+                .await?
+        }
+        ID::Root(root) => Some(root),
+    };
+
+    block_root.ok_or_else(|| {
+        BlockHandlerError::NotFound(format!("Failed to find `block_root` from {block_id:?}"))
+    })
+}
 
-    Ok(block_root)
+/// Returns the `name` checkpoint read from the store. A checkpoint that is not set yet, or still
+/// has a zero root, means the node is still syncing.
+pub fn get_synced_checkpoint(
+    name: &str,
+    checkpoint: Result<Checkpoint, StoreError>,
+) -> Result<Checkpoint, BlockHandlerError> {
+    match checkpoint {
+        Ok(checkpoint) if checkpoint.root != B256::ZERO => Ok(checkpoint),
+        Ok(_) | Err(StoreError::FieldNotInitilized) => Err(BlockHandlerError::NotSynced(format!(
+            "No {name} checkpoint is set yet"
+        ))),
+        Err(err) => Err(err.into()),
+    }
 }
 
-async fn get_beacon_state(block_id: ID, db: &ReamDB) -> Result<BeaconState, ApiError> {
-    let block_root = get_block_root_from_id(block_id, db).await?;
+/// Resolves the `name` checkpoint read from the store to its block root. A checkpoint that is not
+/// set yet means the node is still syncing, while a checkpoint whose block is not stored means
+/// the database is inconsistent, neither of which is a 404 for a wrong block ID.
+async fn get_checkpoint_block_root(
+    name: &str,
+    checkpoint: Result<Checkpoint, StoreError>,
+    store: &dyn BeaconStore,
+) -> Result<B256, BlockHandlerError> {
+    let checkpoint = get_synced_checkpoint(name, checkpoint)?;
+
+    let block =
+        read_with_retry(|| time_db_read("beacon_block", || store.beacon_block(checkpoint.root)))
+            .await?;
+    if block.is_none() {
+        return Err(BlockHandlerError::Inconsistent(format!(
+            "Block {:?} of the {name} checkpoint is not stored",
+            checkpoint.root
+        )));
+    }
 
-    db.beacon_state_provider()
-        .get(block_root)
-        .map_err(|err| {
-            ApiError::InternalError(format!(
-                "Failed to get beacon_state by block_root, error: {err:?}"
-            ))
-        })?
-        .ok_or(ApiError::NotFound(format!(
-            "Failed to find `beacon_state` from {block_root:?}"
-        )))
+    Ok(checkpoint.root)
 }
 
-fn get_attestations_rewards(beacon_state: &BeaconState, beacon_block: &SignedBeaconBlock) -> u64 {
+/// Returns the proposer reward for the attestations in `beacon_block`, computed on its pre-state
+/// `beacon_state` the way `process_attestation` does. Only participation flags an attestation
+/// newly sets are rewarded, so attestations repeating participation already recorded in the state
+/// or earlier in the block earn nothing.
+pub fn get_attestations_rewards(
+    beacon_state: &BeaconState,
+    beacon_block: &SignedBeaconBlock,
+) -> u64 {
+    let proposer_reward_denominator =
+        (WEIGHT_DENOMINATOR - PROPOSER_WEIGHT) * WEIGHT_DENOMINATOR / PROPOSER_WEIGHT;
+    let mut current_epoch_participation = beacon_state.current_epoch_participation.clone();
+    let mut previous_epoch_participation = beacon_state.previous_epoch_participation.clone();
+    let mut committee_cache = std::collections::HashMap::new();
+    // `get_base_reward` recomputes the total active balance on every call, which only depends on
+    // the state, so the base reward of each attester is computed once per request
+    let base_reward_per_increment = beacon_state.get_base_reward_per_increment();
+    let mut base_rewards = HashMap::new();
+
     let mut attester_reward = 0;
-    let attestations = &beacon_block.message.body.attestations;
-    for attestation in attestations {
-        if let Ok(attesting_indices) = beacon_state.get_attesting_indices(attestation) {
-            for index in attesting_indices {
-                attester_reward += beacon_state.get_proposer_reward(index);
+    for attestation in &beacon_block.message.body.attestations {
+        let data = &attestation.data;
+        let Ok(participation_flag_indices) = beacon_state
+            .get_attestation_participation_flag_indices(
+                data,
+                beacon_block.message.slot.saturating_sub(data.slot),
+            )
+        else {
+            continue;
+        };
+        let Ok(attesting_indices) =
+            beacon_state.get_attesting_indices_with_cache(attestation, &mut committee_cache)
+        else {
+            continue;
+        };
+        let epoch_participation = if data.target.epoch == beacon_state.get_current_epoch() {
+            &mut current_epoch_participation
+        } else {
+            &mut previous_epoch_participation
+        };
+
+        let mut proposer_reward_numerator = 0;
+        for index in attesting_indices {
+            let Some(flags) = epoch_participation.get_mut(index as usize) else {
+                continue;
+            };
+            let base_reward = *base_rewards.entry(index).or_insert_with(|| {
+                beacon_state.validators[index as usize].effective_balance
+                    / EFFECTIVE_BALANCE_INCREMENT
+                    * base_reward_per_increment
+            });
+            for (flag_index, &weight) in PARTICIPATION_FLAG_WEIGHTS.iter().enumerate() {
+                let flag_index = flag_index as u8;
+                if participation_flag_indices.contains(&flag_index)
+                    && !BeaconState::has_flag(*flags, flag_index)
+                {
+                    *flags = BeaconState::add_flag(*flags, flag_index);
+                    proposer_reward_numerator += base_reward * weight;
+                }
             }
         }
+        attester_reward += proposer_reward_numerator / proposer_reward_denominator;
     }
     attester_reward
 }
 
 fn get_sync_committee_rewards(beacon_state: &BeaconState, beacon_block: &SignedBeaconBlock) -> u64 {
-    let total_active_balance = beacon_state.get_total_active_balance();
-    let total_active_increments = total_active_balance / EFFECTIVE_BALANCE_INCREMENT;
-    let total_base_rewards = beacon_state.get_base_reward_per_increment() * total_active_increments;
-    let max_participant_rewards =
-        total_base_rewards * SYNC_REWARD_WEIGHT / WEIGHT_DENOMINATOR / SLOTS_PER_EPOCH;
-    let participant_reward = max_participant_rewards / SYNC_COMMITTEE_SIZE;
-    let proposer_reward =
-        participant_reward * PROPOSER_WEIGHT / (WEIGHT_DENOMINATOR - PROPOSER_WEIGHT);
+    let (_, proposer_reward) = beacon_state.get_sync_aggregate_rewards();
 
     beacon_block
         .message
@@ -135,7 +286,9 @@ fn get_sync_committee_rewards(beacon_state: &BeaconState, beacon_block: &SignedB
         * proposer_reward
 }
 
-fn get_slashable_attester_indices(
+/// Returns the validators attesting in both attestations of `attester_shashing` that are still
+/// slashable in `beacon_state`.
+pub fn get_slashable_attester_indices(
     beacon_state: &BeaconState,
     attester_shashing: &AttesterSlashing,
 ) -> Vec<u64> {
@@ -166,7 +319,14 @@ fn get_slashable_attester_indices(
     slashing_indices
 }
 
-fn get_proposer_slashing_rewards(
+/// The proposer's reward for slashing a validator with `effective_balance`. Block operations call
+/// `slash_validator` without a whistleblower, so the proposer is paid both the proposer share and
+/// the whistleblower share, i.e. the whole whistleblower reward.
+fn get_slashing_proposer_reward(effective_balance: u64) -> u64 {
+    effective_balance / WHISTLEBLOWER_REWARD_QUOTIENT_ELECTRA
+}
+
+pub fn get_proposer_slashing_rewards(
     beacon_state: &BeaconState,
     beacon_block: &SignedBeaconBlock,
 ) -> u64 {
@@ -174,13 +334,13 @@ fn get_proposer_slashing_rewards(
     let proposer_slashings = &beacon_block.message.body.proposer_slashings;
     for proposer_slashing in proposer_slashings {
         let index = proposer_slashing.signed_header_1.message.proposer_index;
-        let reward = beacon_state.validators[index as usize].effective_balance;
-        proposer_slashing_reward += reward;
+        proposer_slashing_reward +=
+            get_slashing_proposer_reward(beacon_state.validators[index as usize].effective_balance);
     }
     proposer_slashing_reward
 }
 
-fn get_attester_slashing_rewards(
+pub fn get_attester_slashing_rewards(
     beacon_state: &BeaconState,
     beacon_block: &SignedBeaconBlock,
 ) -> u64 {
@@ -188,9 +348,9 @@ fn get_attester_slashing_rewards(
     let attester_shashings = &beacon_block.message.body.attester_slashings;
     for attester_shashing in attester_shashings {
         for index in get_slashable_attester_indices(beacon_state, attester_shashing) {
-            let reward = beacon_state.validators[index as usize].effective_balance
-                / WHISTLEBLOWER_REWARD_QUOTIENT;
-            attester_slashing_reward += reward;
+            attester_slashing_reward += get_slashing_proposer_reward(
+                beacon_state.validators[index as usize].effective_balance,
+            );
         }
     }
 
@@ -199,134 +359,397 @@ fn get_attester_slashing_rewards(
 
 pub async fn get_beacon_block_from_id(
     block_id: ID,
-    db: &ReamDB,
-) -> Result<SignedBeaconBlock, ApiError> {
-    let block_root = get_block_root_from_id(block_id, db).await?;
-
-    db.beacon_block_provider()
-        .get(block_root)
-        .map_err(|err| {
-            ApiError::InternalError(format!("Failed to get block by block_root, error: {err:?}"))
-        })?
+    store: &dyn BeaconStore,
+) -> Result<SignedBeaconBlock, BlockHandlerError> {
+    let block_root = get_block_root_from_id(block_id, store).await?;
+
+    read_with_retry(|| time_db_read("beacon_block", || store.beacon_block(block_root)))
+        .await?
         .ok_or_else(|| {
-            ApiError::NotFound(format!("Failed to find `beacon block` from {block_root:?}"))
+            BlockHandlerError::NotFound(format!(
+                "Failed to find `beacon block` from {block_root:?}"
+            ))
         })
 }
 
+/// Returns whether the block at `block_root` was imported without its payload, or the payload
+/// of one of its ancestors, being verified by the execution layer.
+pub fn is_optimistic_block(
+    block_root: B256,
+    store: &dyn BeaconStore,
+) -> Result<bool, BlockHandlerError> {
+    Ok(time_db_read("execution_status", || {
+        store.is_optimistic(block_root)
+    })?)
+}
+
+/// Returns whether the block at `block_root` is the finalized checkpoint block or one of its
+/// ancestors. Blocks that are not stored are reported as not finalized.
+///
+/// The finalized chain is canonical, so a block at or before the finalized slot is finalized
+/// exactly when the slot index points at it.
+pub fn is_finalized_block(
+    block_root: B256,
+    store: &dyn BeaconStore,
+) -> Result<bool, BlockHandlerError> {
+    let Some(block) = time_db_read("beacon_block", || store.beacon_block(block_root))? else {
+        return Ok(false);
+    };
+
+    let finalized_checkpoint = get_synced_checkpoint(
+        "finalized",
+        time_db_read("finalized_checkpoint", || store.finalized_checkpoint()),
+    )?;
+    if finalized_checkpoint.root == block_root {
+        return Ok(true);
+    }
+    let finalized_block = time_db_read("beacon_block", || {
+        store.beacon_block(finalized_checkpoint.root)
+    })?
+    .ok_or_else(|| {
+        BlockHandlerError::Inconsistent(format!(
+            "Block {:?} of the finalized checkpoint is not stored",
+            finalized_checkpoint.root
+        ))
+    })?;
+    if block.message.slot > finalized_block.message.slot {
+        return Ok(false);
+    }
+
+    let canonical_root = time_db_read("slot_index", || {
+        store.block_root_at_slot(block.message.slot)
+    })?;
+    Ok(canonical_root == Some(block_root))
+}
+
+/// Returns the `genesis_time` of the stored genesis state, if the genesis state is stored.
+fn get_stored_genesis_time(store: &dyn BeaconStore) -> Result<Option<u64>, BlockHandlerError> {
+    let Some(genesis_block_root) =
+        time_db_read("slot_index", || store.block_root_at_slot(GENESIS_SLOT))?
+    else {
+        return Ok(None);
+    };
+
+    let genesis_state = time_db_read("beacon_state", || store.beacon_state(genesis_block_root))?;
+
+    Ok(genesis_state.map(|state| state.genesis_time))
+}
+
 /// Called by `/genesis` to get the Genesis Config of Beacon Chain.
 #[get("/beacon/genesis")]
-pub async fn get_genesis() -> Result<impl Responder, ApiError> {
-    Ok(HttpResponse::Ok().json(DataResponse::new(Genesis {
-        genesis_time: network_spec().min_genesis_time,
+pub async fn get_genesis(store: Data<Arc<dyn BeaconStore>>) -> Result<impl Responder, ApiError> {
+    let genesis_time = get_stored_genesis_time(store.get_ref().as_ref())?
+        .unwrap_or(network_spec().min_genesis_time);
+
+    Ok(HttpResponse::Ok().json(ApiResponse::data(Genesis {
+        genesis_time,
         genesis_validators_root: genesis_validators_root(),
         genesis_fork_version: network_spec().genesis_fork_version,
     })))
 }
 
-/// Called by `/eth/v2/beacon/blocks/{block_id}/attestations` to get block attestations
+/// Called by `/eth/v2/beacon/blocks/{block_id}/attestations` to get block attestations.
+/// Attestations are returned as included in the block unless `dedup=true` is set, in which case
+/// byte-identical attestations are collapsed into one.
 #[get("/beacon/blocks/{block_id}/attestations")]
 pub async fn get_block_attestations(
-    db: Data<ReamDB>,
+    store: Data<Arc<dyn BeaconStore>>,
     block_id: Path<ID>,
+    query: Query<DedupQuery>,
 ) -> Result<impl Responder, ApiError> {
-    let beacon_block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
+    let store = store.get_ref().as_ref();
+    let block_root = get_block_root_from_id(block_id.into_inner(), store).await?;
+    let beacon_block = get_beacon_block_from_id(ID::Root(block_root), store).await?;
+    let execution_optimistic = is_optimistic_block(block_root, store)?;
+    let finalized = is_finalized_block(block_root, store)?;
+
+    let mut attestations = beacon_block.message.body.attestations.to_vec();
+    if query.dedup.unwrap_or(false) {
+        let mut unique_attestations = Vec::with_capacity(attestations.len());
+        for attestation in attestations {
+            if !unique_attestations.contains(&attestation) {
+                unique_attestations.push(attestation);
+            }
+        }
+        attestations = unique_attestations;
+    }
 
-    Ok(HttpResponse::Ok().json(BeaconVersionedResponse::new(
-        beacon_block.message.body.attestations,
-    )))
+    Ok(HttpResponse::Ok().json(
+        ApiResponse::data(attestations)
+            .with_version(VERSION)
+            .with_optimistic(execution_optimistic)
+            .with_finalized(finalized),
+    ))
 }
 
 /// Called by `/blocks/<block_id>/root` to get the Tree hash of the Block.
 #[get("/beacon/blocks/{block_id}/root")]
 pub async fn get_block_root(
-    db: Data<ReamDB>,
+    store: Data<Arc<dyn BeaconStore>>,
     block_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let block_root = get_block_root_from_id(block_id.into_inner(), &db).await?;
+    let store = store.get_ref().as_ref();
+    let block_root = get_block_root_from_id(block_id.into_inner(), store).await?;
+    let execution_optimistic = is_optimistic_block(block_root, store)?;
+    let finalized = is_finalized_block(block_root, store)?;
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponse::data(RootResponse::new(block_root))
+            .with_optimistic(execution_optimistic)
+            .with_finalized(finalized),
+    ))
+}
+
+/// Rewards are computed against the pre-state of a block, i.e. its parent's post-state advanced
+/// through the skipped slots up to the slot of the block, as `state_transition` does before
+/// processing it.
+pub async fn get_block_pre_state(
+    beacon_block: &SignedBeaconBlock,
+    store: &dyn BeaconStore,
+) -> Result<BeaconState, BlockHandlerError> {
+    let parent_root = beacon_block.message.parent_root;
+    let mut beacon_state =
+        read_with_retry(|| time_db_read("beacon_state", || store.beacon_state(parent_root)))
+            .await?
+            .ok_or_else(|| {
+                BlockHandlerError::NotFound(format!(
+                    "Failed to find `beacon_state` from {parent_root:?}"
+                ))
+            })?;
+
+    if beacon_state.slot < beacon_block.message.slot {
+        beacon_state.process_slots(beacon_block.message.slot)?;
+    }
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(RootResponse::new(block_root))))
+    Ok(beacon_state)
 }
 
 /// Called by `/beacon/blocks/{block_id}/rewards` to get the block rewards response
 #[get("/beacon/blocks/{block_id}/rewards")]
 pub async fn get_block_rewards(
-    db: Data<ReamDB>,
+    store: Data<Arc<dyn BeaconStore>>,
     block_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let block_id_value = block_id.into_inner();
-    let beacon_block = get_beacon_block_from_id(block_id_value.clone(), &db).await?;
-    let beacon_state = get_beacon_state(block_id_value.clone(), &db).await?;
+    let store = store.get_ref().as_ref();
+    let block_root = get_block_root_from_id(block_id.into_inner(), store).await?;
+    let beacon_block = get_beacon_block_from_id(ID::Root(block_root), store).await?;
+    let beacon_state = get_block_pre_state(&beacon_block, store).await?;
+    let execution_optimistic = is_optimistic_block(block_root, store)?;
+    let finalized = is_finalized_block(block_root, store)?;
 
     let attestation_reward = get_attestations_rewards(&beacon_state, &beacon_block);
     let attester_slashing_reward = get_attester_slashing_rewards(&beacon_state, &beacon_block);
     let proposer_slashing_reward = get_proposer_slashing_rewards(&beacon_state, &beacon_block);
     let sync_committee_reward = get_sync_committee_rewards(&beacon_state, &beacon_block);
 
-    let total = attestation_reward
-        + sync_committee_reward
-        + proposer_slashing_reward
-        + attester_slashing_reward;
-
-    let response = BlockRewards {
-        proposer_index: beacon_block.message.proposer_index,
-        total,
-        attestations: attestation_reward,
-        sync_aggregate: sync_committee_reward,
-        proposer_slashings: proposer_slashing_reward,
-        attester_slashings: attester_slashing_reward,
-    };
-
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(response)))
+    let response = BlockRewards::new(
+        beacon_block.message.proposer_index,
+        attestation_reward,
+        sync_committee_reward,
+        proposer_slashing_reward,
+        attester_slashing_reward,
+    );
+
+    Ok(HttpResponse::Ok().json(
+        ApiResponse::data(response)
+            .with_optimistic(execution_optimistic)
+            .with_finalized(finalized),
+    ))
 }
 
 /// Called by `/blocks/<block_id>` to get the Beacon Block.
 #[get("/beacon/blocks/{block_id}")]
 pub async fn get_block_from_id(
-    db: Data<ReamDB>,
+    store: Data<Arc<dyn BeaconStore>>,
     block_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
-    let beacon_block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
+    let store = store.get_ref().as_ref();
+    let block_root = get_block_root_from_id(block_id.into_inner(), store).await?;
+    let beacon_block = get_beacon_block_from_id(ID::Root(block_root), store).await?;
+    let execution_optimistic = is_optimistic_block(block_root, store)?;
+    let finalized = is_finalized_block(block_root, store)?;
+    let version = network_spec()
+        .fork_name_at_slot(beacon_block.message.slot)
+        .as_str();
+
+    Ok(HttpResponse::Ok()
+        .insert_header((ETH_CONSENSUS_VERSION_HEADER, version))
+        .json(
+            ApiResponse::data(beacon_block)
+                .with_version(version)
+                .with_optimistic(execution_optimistic)
+                .with_finalized(finalized),
+        ))
+}
+
+/// Returns the roots and blocks of the leaves of `blocks`, the blocks no other block in `blocks`
+/// builds on, ordered by `(slot, root)`.
+pub fn get_fork_choice_leaves(blocks: &HashMap<B256, BeaconBlock>) -> Vec<(B256, &BeaconBlock)> {
+    let referenced_parents = blocks
+        .values()
+        .map(|block| block.parent_root)
+        .collect::<HashSet<_>>();
 
-    Ok(HttpResponse::Ok().json(BeaconVersionedResponse::new(beacon_block)))
+    let mut leaves = blocks
+        .iter()
+        .filter(|(block_root, _)| !referenced_parents.contains(*block_root))
+        .map(|(block_root, block)| (*block_root, block))
+        .collect::<Vec<_>>();
+    leaves.sort_by_key(|(block_root, block)| (block.slot, *block_root));
+    leaves
 }
 
 /// Called by `/beacon/heads` to get fork choice leaves.
 #[get("/beacon/heads")]
-pub async fn get_beacon_heads(db: Data<ReamDB>) -> Result<impl Responder, ApiError> {
-    let justified_checkpoint = db.justified_checkpoint_provider().get().map_err(|err| {
-        ApiError::InternalError(format!(
-            "Failed to get justified_checkpoint, error: {err:?}"
-        ))
-    })?;
+pub async fn get_beacon_heads(
+    store: Data<Arc<dyn BeaconStore>>,
+) -> Result<impl Responder, ApiError> {
+    // The block tree is rooted at the justified checkpoint, there is no tree without it
+    get_synced_checkpoint(
+        "justified",
+        time_db_read("justified_checkpoint", || {
+            store.get_ref().justified_checkpoint()
+        }),
+    )?;
+    let blocks = time_db_read("justified_block_tree", || {
+        store.get_ref().justified_block_tree()
+    })
+    .map_err(BlockHandlerError::from)?;
+
+    let leaves = get_fork_choice_leaves(&blocks)
+        .into_iter()
+        .map(|(block_root, block)| BeaconHeadResponse::new(block_root, block.slot))
+        .collect::<Vec<_>>();
+
+    Ok(HttpResponse::Ok().json(ApiResponse::data(leaves)))
+}
 
-    let mut blocks = HashMap::new();
-    let store = Store {
-        db: db.get_ref().clone(),
-        operation_pool: Arc::new(OperationPool::default()),
-    };
+#[cfg(test)]
+mod tests {
+    use actix_web::{ResponseError, http::StatusCode, test};
+    use ream_beacon_api_types::error::ErrorResponse;
+
+    use super::*;
+    use crate::test_utils::{TestDB, test_app};
+
+    #[test]
+    fn test_storage_io_error_maps_to_internal_error() {
+        let err = ApiError::from(BlockHandlerError::from(StoreError::Io(
+            std::io::Error::other("disk unavailable"),
+        )));
+        assert_eq!(err.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(err.to_string().contains("disk unavailable"));
+    }
 
-    store
-        .filter_block_tree(justified_checkpoint.root, &mut blocks)
-        .map_err(|err| {
-            ApiError::InternalError(format!("Failed to filter block tree, error: {err:?}"))
-        })?;
+    #[test]
+    fn test_missing_root_maps_to_not_found() {
+        let err = ApiError::from(BlockHandlerError::NotFound(format!(
+            "Failed to find `beacon block` from {:?}",
+            B256::ZERO
+        )));
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+
+        let err = ApiError::from(BlockHandlerError::from(StoreError::NotFound(
+            "block".to_string(),
+        )));
+        assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_slashing_proposer_reward() {
+        // 32 ETH / 4096 = 7_812_500 Gwei whistleblower reward, all of it paid to the proposer
+        assert_eq!(get_slashing_proposer_reward(32_000_000_000), 7_812_500);
+        assert_eq!(get_slashing_proposer_reward(2_048_000_000_000), 500_000_000);
+        assert_eq!(get_slashing_proposer_reward(0), 0);
+    }
 
-    let mut leaves = vec![];
-    let mut referenced_parents = HashSet::new();
+    const UNSYNCED_URIS: [&str; 7] = [
+        "/eth/v2/beacon/blocks/finalized",
+        "/eth/v2/beacon/blocks/justified",
+        "/eth/v2/beacon/blocks/head",
+        "/eth/v1/beacon/states/finalized/root",
+        "/eth/v1/beacon/states/head/root",
+        "/eth/v1/beacon/states/head/fork",
+        "/eth/v1/debug/beacon/heads",
+    ];
+
+    #[actix_web::test]
+    async fn test_unsynced_checkpoint_is_service_unavailable() {
+        let test_db = TestDB::new().unwrap();
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+
+        for uri in UNSYNCED_URIS {
+            let request = test::TestRequest::get().uri(uri).to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE, "{uri}");
+
+            let body: ErrorResponse = test::read_body_json(response).await;
+            assert_eq!(body.code, 503, "{uri}");
+            assert!(body.message.starts_with("Node is syncing: "), "{uri}");
+        }
 
-    for block in blocks.values() {
-        referenced_parents.insert(block.parent_root);
+        // A state at a slot can't be rebuilt before any block is stored
+        let request = test::TestRequest::get()
+            .uri("/eth/v1/beacon/states/1/root")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
     }
 
-    for (block_root, block) in &blocks {
-        if !referenced_parents.contains(block_root) {
-            leaves.push(BeaconHeadResponse {
-                root: block.tree_hash_root(),
-                slot: block.slot,
-                execution_optimistic: false,
-            });
+    #[actix_web::test]
+    async fn test_zero_checkpoint_is_service_unavailable() {
+        let test_db = TestDB::new().unwrap();
+        test_db.set_checkpoints(Checkpoint::default()).unwrap();
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+
+        for uri in UNSYNCED_URIS {
+            let request = test::TestRequest::get().uri(uri).to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE, "{uri}");
         }
     }
 
-    Ok(HttpResponse::Ok().json(DataResponse::new(leaves)))
+    #[actix_web::test]
+    async fn test_missing_checkpoint_block_is_internal_error() {
+        let test_db = TestDB::new().unwrap();
+        test_db
+            .set_checkpoints(Checkpoint {
+                epoch: 1,
+                root: B256::repeat_byte(0xab),
+            })
+            .unwrap();
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+
+        let request = test::TestRequest::get()
+            .uri("/eth/v2/beacon/blocks/finalized")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+        assert!(
+            body.contains("finalized checkpoint is not stored"),
+            "{body}"
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_malformed_block_id_is_bad_request() {
+        let test_db = TestDB::new().unwrap();
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+
+        for block_id in ["0xnothex", "0xabcd", "latest"] {
+            let request = test::TestRequest::get()
+                .uri(&format!("/eth/v2/beacon/blocks/{block_id}"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{block_id}");
+        }
+
+        // Well formed but unknown
+        let request = test::TestRequest::get()
+            .uri(&format!("/eth/v2/beacon/blocks/0x{}", "ab".repeat(32)))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
 }
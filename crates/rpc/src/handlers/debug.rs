@@ -0,0 +1,293 @@
+use std::{cmp::Reverse, collections::BTreeMap, sync::Arc};
+
+use actix_web::{
+    HttpRequest, HttpResponse, Responder, get,
+    http::header::{ETAG, IF_NONE_MATCH},
+    web::{Bytes, Data, Path, Query},
+};
+use futures::stream;
+use ream_beacon_api_types::{
+    error::ApiError,
+    id::ID,
+    query::StateIdQuery,
+    responses::{
+        ApiResponse, DataVersionedResponse, DebugHeadResponse, DebugHeadsResponse,
+        ETH_CONSENSUS_VERSION_HEADER, EffectiveBalanceBucket, JSON_CONTENT_TYPE, SSZ_CONTENT_TYPE,
+        ValidatorStatsResponse, ValidatorStatusCount,
+    },
+    validator::ValidatorStatus,
+};
+use ream_consensus::{
+    constants::EFFECTIVE_BALANCE_INCREMENT, electra::beacon_state::BeaconState,
+    validator::Validator,
+};
+use ream_fork_choice::store::Store;
+use ream_network_spec::networks::network_spec;
+use ream_operation_pool::OperationPool;
+use ream_storage::{db::ReamDB, tables::Field};
+use ssz::Encode;
+
+use super::{
+    block::{
+        BlockHandlerError, get_fork_choice_leaves, get_synced_checkpoint, is_optimistic_block,
+    },
+    state::{get_state_from_id, get_state_with_root_from_id},
+};
+use crate::state_cache::StateCache;
+
+/// Size of the body chunks a SSZ encoded state is streamed in.
+const SSZ_CHUNK_SIZE: usize = 1 << 20;
+
+/// Called by `/eth/v2/debug/beacon/states/{state_id}` to get the full `BeaconState`. States are
+/// large, so clients should prefer `application/octet-stream`.
+#[get("/beacon/states/{state_id}")]
+pub async fn get_debug_state(
+    http_request: HttpRequest,
+    db: Data<ReamDB>,
+    state_id: Path<ID>,
+) -> Result<impl Responder, ApiError> {
+    let state = get_state_from_id(state_id.into_inner(), &db).await?;
+
+    Ok(debug_state_response(&http_request, &state))
+}
+
+/// Builds the response for `state`, streaming its SSZ encoding in `SSZ_CHUNK_SIZE` chunks when
+/// the request accepts SSZ and falling back to JSON otherwise. The version is the fork active at
+/// the slot of `state`.
+pub fn debug_state_response(http_request: &HttpRequest, state: &BeaconState) -> HttpResponse {
+    let version = network_spec().fork_name_at_slot(state.slot).as_str();
+    match http_request
+        .headers()
+        .get("accept")
+        .and_then(|header| header.to_str().ok())
+    {
+        Some(SSZ_CONTENT_TYPE) => {
+            let bytes = Bytes::from(state.as_ssz_bytes());
+            let length = bytes.len();
+            let chunks = (0..length).step_by(SSZ_CHUNK_SIZE).map(move |start| {
+                Ok::<_, ApiError>(bytes.slice(start..(start + SSZ_CHUNK_SIZE).min(length)))
+            });
+            HttpResponse::Ok()
+                .content_type(SSZ_CONTENT_TYPE)
+                .insert_header((ETH_CONSENSUS_VERSION_HEADER, version))
+                .streaming(stream::iter(chunks))
+        }
+        _ => HttpResponse::Ok()
+            .content_type(JSON_CONTENT_TYPE)
+            .insert_header((ETH_CONSENSUS_VERSION_HEADER, version))
+            .json(DataVersionedResponse::new(state).with_version(version)),
+    }
+}
+
+/// Called by `/eth/v1/debug/beacon/heads` to get the fork choice leaves along with their branch
+/// weights and the checkpoints the store filters the block tree by.
+#[get("/beacon/heads")]
+pub async fn get_debug_heads(
+    db: Data<ReamDB>,
+    operation_pool: Data<Arc<OperationPool>>,
+) -> Result<impl Responder, ApiError> {
+    let store = Store::new(db.get_ref().clone(), operation_pool.get_ref().clone());
+
+    Ok(HttpResponse::Ok().json(ApiResponse::data(get_debug_heads_from_store(&store)?)))
+}
+
+/// Returns the leaves of the filtered block tree of `store`, heaviest first. The weight of a leaf
+/// is the attestation weight of its branch as computed by `Store::get_weight`.
+pub fn get_debug_heads_from_store(store: &Store) -> Result<DebugHeadsResponse, BlockHandlerError> {
+    let justified_checkpoint =
+        get_synced_checkpoint("justified", store.db.justified_checkpoint_provider().get())?;
+    let blocks = store.get_filtered_block_tree()?;
+
+    let mut heads = get_fork_choice_leaves(&blocks)
+        .into_iter()
+        .map(|(root, block)| {
+            Ok(DebugHeadResponse {
+                root,
+                slot: block.slot,
+                execution_optimistic: is_optimistic_block(root, &store.db)?,
+                weight: store.get_weight(root)?,
+            })
+        })
+        .collect::<Result<Vec<_>, BlockHandlerError>>()?;
+    heads.sort_by_key(|head| (Reverse(head.weight), head.root));
+
+    Ok(DebugHeadsResponse {
+        justified_checkpoint,
+        finalized_checkpoint: store.db.finalized_checkpoint_provider().get()?,
+        heads,
+    })
+}
+
+/// Called by `/eth/v1/debug/beacon/validator_stats` to get aggregate statistics over the validators
+/// of the state with `state_id`, the head state by default. The statistics only depend on the state
+/// root, which is sent as the `ETag` so clients can cache them. Skipped slots resolve to the state
+/// rebuilt at them.
+#[get("/beacon/validator_stats")]
+pub async fn get_validator_stats(
+    http_request: HttpRequest,
+    db: Data<ReamDB>,
+    state_cache: Data<StateCache>,
+    query: Query<StateIdQuery>,
+) -> Result<impl Responder, ApiError> {
+    let state_id = query.into_inner().state_id.unwrap_or(ID::Head);
+    let (state_root, state) = get_state_with_root_from_id(state_id, &db).await?;
+    let etag = format!("\"{state_root}\"");
+    let is_cached = http_request
+        .headers()
+        .get(IF_NONE_MATCH)
+        .and_then(|header| header.to_str().ok())
+        .is_some_and(|tags| {
+            tags.split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag == etag)
+        });
+    if is_cached {
+        return Ok(HttpResponse::NotModified()
+            .insert_header((ETAG, etag))
+            .finish());
+    }
+
+    let state = state_cache
+        .get_or_load(state_root, || async { Ok::<_, ApiError>(state) })
+        .await?;
+    let stats = compute_validator_stats(
+        &state.validators,
+        &state.balances,
+        state.get_current_epoch(),
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header((ETAG, etag))
+        .json(ApiResponse::data(stats)))
+}
+
+/// Aggregates `validators` with their `balances` at `epoch` in a single pass. Statuses are the
+/// ones reported by the validators endpoint and effective balances are bucketed by
+/// `EFFECTIVE_BALANCE_INCREMENT`.
+pub fn compute_validator_stats(
+    validators: &[Validator],
+    balances: &[u64],
+    epoch: u64,
+) -> ValidatorStatsResponse {
+    let mut stats = ValidatorStatsResponse::default();
+    let mut statuses = BTreeMap::<ValidatorStatus, u64>::new();
+    let mut effective_balances = BTreeMap::<u64, u64>::new();
+    for (validator, balance) in validators.iter().zip(balances) {
+        let status = ValidatorStatus::new(validator, *balance, epoch);
+        stats.total_validators += 1;
+        stats.total_effective_balance += validator.effective_balance;
+        if status.matches(&ValidatorStatus::Active) {
+            stats.active_validators += 1;
+            stats.active_effective_balance += validator.effective_balance;
+        }
+        *statuses.entry(status).or_default() += 1;
+        let bucket =
+            validator.effective_balance / EFFECTIVE_BALANCE_INCREMENT * EFFECTIVE_BALANCE_INCREMENT;
+        *effective_balances.entry(bucket).or_default() += 1;
+    }
+
+    stats.average_effective_balance = stats
+        .total_effective_balance
+        .checked_div(stats.total_validators)
+        .unwrap_or_default();
+    stats.statuses = statuses
+        .into_iter()
+        .map(|(status, count)| ValidatorStatusCount { status, count })
+        .collect();
+    stats.effective_balances = effective_balances
+        .into_iter()
+        .map(|(effective_balance, count)| EffectiveBalanceBucket {
+            effective_balance,
+            count,
+        })
+        .collect();
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_bls::PublicKey;
+    use ream_consensus::constants::FAR_FUTURE_EPOCH;
+
+    use super::*;
+
+    fn validator(effective_balance: u64, activation_epoch: u64, exit_epoch: u64) -> Validator {
+        Validator {
+            public_key: PublicKey::default(),
+            withdrawal_credentials: B256::ZERO,
+            effective_balance,
+            slashed: false,
+            activation_eligibility_epoch: 0,
+            activation_epoch,
+            exit_epoch,
+            withdrawable_epoch: FAR_FUTURE_EPOCH,
+        }
+    }
+
+    #[test]
+    fn test_validator_stats() {
+        let increment = EFFECTIVE_BALANCE_INCREMENT;
+        let validators = [
+            validator(32 * increment, 0, FAR_FUTURE_EPOCH),
+            validator(32 * increment, 0, FAR_FUTURE_EPOCH),
+            validator(31 * increment, 0, 20),
+            validator(32 * increment, 0, 5),
+            validator(16 * increment, 20, FAR_FUTURE_EPOCH),
+        ];
+        let balances = [32 * increment; 5];
+
+        let stats = compute_validator_stats(&validators, &balances, 10);
+        assert_eq!(
+            stats,
+            ValidatorStatsResponse {
+                total_validators: 5,
+                active_validators: 3,
+                total_effective_balance: 143 * increment,
+                active_effective_balance: 95 * increment,
+                average_effective_balance: 143 * increment / 5,
+                statuses: vec![
+                    ValidatorStatusCount {
+                        status: ValidatorStatus::PendingQueued,
+                        count: 1,
+                    },
+                    ValidatorStatusCount {
+                        status: ValidatorStatus::ActiveOngoing,
+                        count: 2,
+                    },
+                    ValidatorStatusCount {
+                        status: ValidatorStatus::ActiveExiting,
+                        count: 1,
+                    },
+                    ValidatorStatusCount {
+                        status: ValidatorStatus::ExitedUnslashed,
+                        count: 1,
+                    },
+                ],
+                effective_balances: vec![
+                    EffectiveBalanceBucket {
+                        effective_balance: 16 * increment,
+                        count: 1,
+                    },
+                    EffectiveBalanceBucket {
+                        effective_balance: 31 * increment,
+                        count: 1,
+                    },
+                    EffectiveBalanceBucket {
+                        effective_balance: 32 * increment,
+                        count: 3,
+                    },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_validator_stats_of_empty_registry() {
+        assert_eq!(
+            compute_validator_stats(&[], &[], 0),
+            ValidatorStatsResponse::default()
+        );
+    }
+}
@@ -1,36 +1,73 @@
+use std::sync::Arc;
+
 use actix_web::{
     HttpResponse, Responder, get, post,
     web::{Data, Json, Path},
 };
+use alloy_primitives::B256;
 use ream_beacon_api_types::{
     duties::{AttesterDuty, ProposerDuty},
     error::ApiError,
     id::ID,
     responses::DutiesResponse,
 };
-use ream_consensus::{constants::SLOTS_PER_EPOCH, misc::compute_start_slot_at_epoch};
+use ream_consensus::{
+    constants::SLOTS_PER_EPOCH, electra::beacon_state::BeaconState,
+    misc::compute_start_slot_at_epoch,
+};
 use ream_storage::db::ReamDB;
 
-use crate::handlers::state::get_state_from_id;
+use crate::{
+    handlers::{
+        block::{get_beacon_block_from_id, get_block_root_from_id},
+        state::get_state_from_id,
+    },
+    proposer_cache::{EpochProposers, ProposerCache},
+    state_cache::StateCache,
+};
 
 #[get("/validator/duties/proposer/{epoch}")]
 pub async fn get_proposer_duties(
     db: Data<ReamDB>,
+    state_cache: Data<StateCache>,
+    proposer_cache: Data<ProposerCache>,
     epoch: Path<u64>,
 ) -> Result<impl Responder, ApiError> {
-    let epoch = epoch.into_inner();
-    let state = get_state_from_id(ID::Slot(compute_start_slot_at_epoch(epoch)), &db).await?;
-    let dependent_root = state
-        .get_block_root_at_slot(compute_start_slot_at_epoch(epoch) - 1)
-        .map_err(|err| ApiError::BadRequest(format!("Failed to get dependent root {err:?}")))?;
+    let (head_root, state) = get_head_state(&db, &state_cache).await?;
+
+    Ok(HttpResponse::Ok().json(compute_proposer_duties(
+        &state,
+        epoch.into_inner(),
+        head_root,
+        &proposer_cache,
+    )?))
+}
+
+/// Computes the proposer of every slot in `epoch` from the head `state`. The proposers are
+/// looked up in `proposer_cache` by the dependent root returned to the client, so they are only
+/// computed again after that root changed.
+pub fn compute_proposer_duties(
+    state: &BeaconState,
+    epoch: u64,
+    head_root: B256,
+    proposer_cache: &ProposerCache,
+) -> Result<DutiesResponse<ProposerDuty>, ApiError> {
+    check_duties_epoch(state, epoch)?;
+    let dependent_root = get_dependent_root(state, epoch, head_root)?;
 
     let start_slot = compute_start_slot_at_epoch(epoch);
-    let end_slot = start_slot + SLOTS_PER_EPOCH;
+    let proposers = proposer_cache.get_or_compute(epoch, dependent_root, || {
+        let mut proposers: EpochProposers = [0; SLOTS_PER_EPOCH as usize];
+        for (proposer, slot) in proposers.iter_mut().zip(start_slot..) {
+            *proposer = state
+                .get_beacon_proposer_index(Some(slot))
+                .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+        }
+        Ok(proposers)
+    })?;
+
     let mut duties = vec![];
-    for slot in start_slot..end_slot {
-        let validator_index = state
-            .get_beacon_proposer_index(Some(slot))
-            .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+    for (validator_index, slot) in proposers.into_iter().zip(start_slot..) {
         let Some(validator) = state.validators.get(validator_index as usize) else {
             return Err(ApiError::ValidatorNotFound(format!("{validator_index}")));
         };
@@ -40,26 +77,42 @@ pub async fn get_proposer_duties(
             slot,
         });
     }
-    Ok(HttpResponse::Ok().json(DutiesResponse::new(dependent_root, duties)))
+
+    Ok(DutiesResponse::new(dependent_root, duties))
 }
 
 #[post("/validator/duties/attester/{epoch}")]
 pub async fn get_attester_duties(
     db: Data<ReamDB>,
+    state_cache: Data<StateCache>,
     epoch: Path<u64>,
     validator_indices: Json<Vec<u64>>,
 ) -> Result<impl Responder, ApiError> {
-    let epoch = epoch.into_inner();
-    let state = get_state_from_id(ID::Slot(compute_start_slot_at_epoch(epoch)), &db).await?;
-    let dependent_root = state
-        .get_block_root_at_slot(compute_start_slot_at_epoch(epoch) - 1)
-        .map_err(|err| ApiError::BadRequest(format!("Failed to get dependent root {err:?}")))?;
+    let (head_root, state) = get_head_state(&db, &state_cache).await?;
+
+    Ok(HttpResponse::Ok().json(compute_attester_duties(
+        &state,
+        epoch.into_inner(),
+        head_root,
+        &validator_indices,
+    )?))
+}
+
+/// Computes the committee assignment in `epoch` of each of `validator_indices` from the head
+/// `state`. Validators without an assignment are left out.
+pub fn compute_attester_duties(
+    state: &BeaconState,
+    epoch: u64,
+    head_root: B256,
+    validator_indices: &[u64],
+) -> Result<DutiesResponse<AttesterDuty>, ApiError> {
+    check_duties_epoch(state, epoch)?;
+    // Committees are shuffled with a seed fixed one epoch earlier than the proposer seed
+    let dependent_root = get_dependent_root(state, epoch.saturating_sub(1), head_root)?;
 
-    let validator_indices = validator_indices.into_inner();
     let committees_at_slot = state.get_committee_count_per_slot(epoch);
     let mut duties = vec![];
-
-    for validator_index in validator_indices {
+    for &validator_index in validator_indices {
         let Some(validator) = state.validators.get(validator_index as usize) else {
             return Err(ApiError::ValidatorNotFound(format!(
                 "Validator with index {validator_index} not found in state at epoch {epoch}"
@@ -85,11 +138,58 @@ pub async fn get_attester_duties(
                 public_key: validator.public_key.clone(),
                 validator_index,
                 committee_index,
+                committee_length: committee.len() as u64,
                 committees_at_slot,
                 validator_committee_index: validator_committee_index as u64,
                 slot,
             });
         }
     }
-    Ok(HttpResponse::Ok().json(DutiesResponse::new(dependent_root, duties)))
+
+    Ok(DutiesResponse::new(dependent_root, duties))
+}
+
+/// Returns the head block root together with its post-state, shared with concurrent requests for
+/// the same head through `state_cache`.
+async fn get_head_state(
+    db: &ReamDB,
+    state_cache: &StateCache,
+) -> Result<(B256, Arc<BeaconState>), ApiError> {
+    let head_root = get_block_root_from_id(ID::Head, db).await?;
+    let head_block = get_beacon_block_from_id(ID::Root(head_root), db).await?;
+    let state_root = head_block.message.state_root;
+    let state = state_cache
+        .get_or_load(state_root, || get_state_from_id(ID::Root(state_root), db))
+        .await?;
+
+    Ok((head_root, state))
+}
+
+/// Duties are only known for the current and next epoch of the head state.
+fn check_duties_epoch(state: &BeaconState, epoch: u64) -> Result<(), ApiError> {
+    let current_epoch = state.get_current_epoch();
+    if epoch < current_epoch || epoch > current_epoch + 1 {
+        return Err(ApiError::BadRequest(format!(
+            "Duties for epoch {epoch} are unknown, the current epoch is {current_epoch}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns the block root at the last slot of the epoch before `epoch`. That slot may not be in
+/// the past of `state` yet, in which case the chain so far ends at `head_root`.
+pub fn get_dependent_root(
+    state: &BeaconState,
+    epoch: u64,
+    head_root: B256,
+) -> Result<B256, ApiError> {
+    let dependent_slot = compute_start_slot_at_epoch(epoch).saturating_sub(1);
+    if dependent_slot >= state.slot {
+        return Ok(head_root);
+    }
+
+    state
+        .get_block_root_at_slot(dependent_slot)
+        .map_err(|err| ApiError::BadRequest(format!("Failed to get dependent root {err:?}")))
 }
@@ -0,0 +1,160 @@
+use std::time::{Duration, Instant};
+
+use actix_web::{
+    HttpRequest, HttpResponse, get, rt,
+    web::{Data, Payload},
+};
+use actix_web_lab::extract::Query;
+use actix_ws::{CloseReason, Message, MessageStream, Session};
+use futures::StreamExt;
+use ream_beacon_api_types::{events::BeaconEvent, query::TopicsQuery};
+use tokio::{
+    select,
+    sync::broadcast::{self, error::RecvError},
+    time::interval,
+};
+use tracing::warn;
+
+/// Number of events buffered for each subscriber. A subscriber that falls further behind misses
+/// the oldest events rather than growing the buffer.
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// How often WebSocket clients are pinged.
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long a WebSocket client may go without answering a ping before it is disconnected.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Called by `/ws/events` to stream beacon node events over a WebSocket. Each event matching the
+/// `topics` query parameter is sent as a JSON text frame.
+#[get("/events")]
+pub async fn get_events_ws(
+    http_request: HttpRequest,
+    body: Payload,
+    event_sender: Data<broadcast::Sender<BeaconEvent>>,
+    query: Query<TopicsQuery>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let (response, session, message_stream) = actix_ws::handle(&http_request, body)?;
+
+    rt::spawn(forward_events(
+        session,
+        message_stream,
+        event_sender.subscribe(),
+        query.into_inner(),
+    ));
+
+    Ok(response)
+}
+
+/// Forwards `events` to `session` until the client disconnects, stops answering pings, or the
+/// event channel closes.
+async fn forward_events(
+    mut session: Session,
+    mut message_stream: MessageStream,
+    mut events: broadcast::Receiver<BeaconEvent>,
+    topics: TopicsQuery,
+) {
+    let mut last_heartbeat = Instant::now();
+    let mut ping_interval = interval(PING_INTERVAL);
+
+    let close_reason: Option<CloseReason> = loop {
+        select! {
+            _ = ping_interval.tick() => {
+                if last_heartbeat.elapsed() > CLIENT_TIMEOUT {
+                    break None;
+                }
+                if session.ping(b"").await.is_err() {
+                    return;
+                }
+            }
+            message = message_stream.next() => match message {
+                Some(Ok(Message::Pong(_))) => last_heartbeat = Instant::now(),
+                Some(Ok(Message::Ping(bytes))) => {
+                    last_heartbeat = Instant::now();
+                    if session.pong(&bytes).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Close(reason))) => break reason,
+                Some(Ok(_)) => {}
+                Some(Err(_)) | None => break None,
+            },
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if !topics.contains_topic(event.topic()) {
+                        continue;
+                    }
+                    let text = match serde_json::to_string(&event) {
+                        Ok(text) => text,
+                        Err(err) => {
+                            warn!("Failed to serialize event: {err:?}");
+                            continue;
+                        }
+                    };
+                    if session.text(text).await.is_err() {
+                        return;
+                    }
+                }
+                // The client is reading slower than events arrive, the skipped events are dropped
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("WebSocket client lagged behind, dropped {skipped} events");
+                }
+                Err(RecvError::Closed) => break None,
+            },
+        }
+    };
+
+    let _ = session.close(close_reason).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, web::scope};
+    use alloy_primitives::B256;
+    use awc::ws::Frame;
+    use ream_beacon_api_types::events::HeadEvent;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_head_event_is_forwarded() {
+        let (event_sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        let server = actix_test::start({
+            let event_sender = event_sender.clone();
+            move || {
+                App::new()
+                    .app_data(Data::new(event_sender.clone()))
+                    .service(scope("/ws").service(get_events_ws))
+            }
+        });
+        let mut framed = server.ws_at("/ws/events?topics=head").await.unwrap();
+
+        let head_event = HeadEvent {
+            slot: 10,
+            block: B256::repeat_byte(1),
+            state: B256::repeat_byte(2),
+            epoch_transition: false,
+            previous_duty_dependent_root: B256::repeat_byte(3),
+            current_duty_dependent_root: B256::repeat_byte(4),
+            execution_optimistic: false,
+        };
+        event_sender
+            .send(BeaconEvent::Head(head_event.clone()))
+            .unwrap();
+
+        // Skip the heartbeat pings sent before the event
+        let text = loop {
+            match framed.next().await.unwrap().unwrap() {
+                Frame::Text(text) => break text,
+                Frame::Ping(_) => {}
+                frame => panic!("Unexpected frame {frame:?}"),
+            }
+        };
+        let json = serde_json::from_slice::<serde_json::Value>(&text).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "event": "head", "data": head_event })
+        );
+        assert_eq!(json["data"]["slot"], "10");
+    }
+}
@@ -9,12 +9,17 @@ use ream_beacon_api_types::{
     query::{ParentRootQuery, SlotQuery},
     responses::BeaconResponse,
 };
-use ream_consensus::beacon_block_header::SignedBeaconBlockHeader;
-use ream_storage::{db::ReamDB, tables::Table};
+use ream_consensus::{
+    beacon_block_header::SignedBeaconBlockHeader, electra::beacon_block::SignedBeaconBlock,
+};
+use ream_fork_choice::store::Store;
+use ream_storage::{
+    db::ReamDB,
+    tables::{MultimapTable, Table},
+};
 use serde::{Deserialize, Serialize};
-use tree_hash::TreeHash;
 
-use super::block::get_beacon_block_from_id;
+use super::block::{get_beacon_block_from_id, get_block_root_from_id};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HeaderData {
@@ -41,44 +46,45 @@ pub async fn get_headers(
     slot: Query<SlotQuery>,
     parent_root: Query<ParentRootQuery>,
 ) -> Result<impl Responder, ApiError> {
-    let (header, root) = match (slot.slot, parent_root.parent_root) {
-        (None, None) => get_header_from_slot(None, &db).await?,
-        (None, Some(parent_root)) => {
-            // get parent block to have access to `slot`
-            let parent_block = db
-                .beacon_block_provider()
+    let block_roots = match (slot.slot, parent_root.parent_root) {
+        (None, None) => vec![get_block_root_from_id(ID::Head, db.get_ref()).await?],
+        (slot, Some(parent_root)) => {
+            let child_roots = db
+                .parent_root_index_multimap_provider()
                 .get(parent_root)
                 .map_err(|err| {
                     ApiError::InternalError(format!("Failed to get headers, error: {err:?}"))
                 })?
-                .ok_or_else(|| ApiError::NotFound(String::from("Unable to fetch parent block")))?;
-
-            // fetch block header at `slot+1`
-            let (child_header, child_block_root) =
-                get_header_from_slot(Some(parent_block.message.slot + 1), &db).await?;
-
-            if child_header.message.parent_root != parent_root {
-                return Err(ApiError::NotFound(format!(
-                    "Header with parent root :{parent_root:?}"
-                )))?;
-            }
+                .unwrap_or_default();
 
-            (child_header, child_block_root)
-        }
-        (Some(slot), None) => get_header_from_slot(Some(slot), &db).await?,
-        (Some(slot), Some(parent_root)) => {
-            let (header, root) = get_header_from_slot(Some(slot), &db).await?;
-            if header.message.parent_root == parent_root {
-                (header, root)
-            } else {
-                return Err(ApiError::NotFound(format!(
-                    "Header at slot: {slot} with parent root: {parent_root:?} not found"
-                )))?;
+            let mut block_roots = vec![];
+            for child_root in child_roots {
+                let child_block =
+                    get_beacon_block_from_id(ID::Root(child_root), db.get_ref()).await?;
+                if slot.is_none_or(|slot| child_block.message.slot == slot) {
+                    block_roots.push(child_root);
+                }
             }
+            block_roots
         }
+        (Some(slot), None) => db
+            .slot_index_provider()
+            .get(slot)
+            .map_err(|err| {
+                ApiError::InternalError(format!("Failed to get headers, error: {err:?}"))
+            })?
+            .into_iter()
+            .collect(),
     };
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(HeaderData::new(root, true, header))))
+    let head_root = get_block_root_from_id(ID::Head, db.get_ref()).await?;
+    let mut headers = Vec::with_capacity(block_roots.len());
+    for block_root in block_roots {
+        let block = get_beacon_block_from_id(ID::Root(block_root), db.get_ref()).await?;
+        headers.push(get_header_data(block_root, &block, head_root, &db)?);
+    }
+
+    Ok(HttpResponse::Ok().json(BeaconResponse::new(headers)))
 }
 
 /// Called using `/eth/v1/beacon/headers/{block_id}`
@@ -87,35 +93,33 @@ pub async fn get_headers_from_block(
     block_id: Path<ID>,
     db: Data<ReamDB>,
 ) -> Result<impl Responder, ApiError> {
-    let block = get_beacon_block_from_id(block_id.into_inner(), &db).await?;
-    let header = block.signed_header();
+    let block_root = get_block_root_from_id(block_id.into_inner(), db.get_ref()).await?;
+    let block = get_beacon_block_from_id(ID::Root(block_root), db.get_ref()).await?;
+    let head_root = get_block_root_from_id(ID::Head, db.get_ref()).await?;
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(HeaderData::new(
-        header.message.tree_hash_root(),
-        true,
-        header,
-    ))))
+    Ok(HttpResponse::Ok().json(BeaconResponse::new(get_header_data(
+        block_root, &block, head_root, &db,
+    )?)))
 }
 
-pub async fn get_header_from_slot(
-    slot: Option<u64>,
+/// Builds the header response for `block`, which is canonical if it is an ancestor of the
+/// current head.
+fn get_header_data(
+    block_root: B256,
+    block: &SignedBeaconBlock,
+    head_root: B256,
     db: &ReamDB,
-) -> Result<(SignedBeaconBlockHeader, B256), ApiError> {
-    let slot = match slot {
-        Some(slot) => slot,
-        None => db
-            .slot_index_provider()
-            .get_highest_slot()
-            .map_err(|err| {
-                ApiError::InternalError(format!("Failed to get headers, error: {err:?}"))
-            })?
-            .ok_or_else(|| ApiError::NotFound(String::from("Unable to fetch latest slot")))?,
-    };
-
-    let beacon_block = get_beacon_block_from_id(ID::Slot(slot), db).await?;
-
-    let header = beacon_block.signed_header();
-    let root = header.tree_hash_root();
+) -> Result<HeaderData, ApiError> {
+    let canonical = Store::read_only(db.clone())
+        .get_ancestor(head_root, block.message.slot)
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to get ancestor, error: {err:?}"))
+        })?
+        == block_root;
 
-    Ok((header, root))
+    Ok(HeaderData::new(
+        block_root,
+        canonical,
+        block.signed_header(),
+    ))
 }
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, Responder, get, web::Data};
+use alloy_primitives::aliases::B32;
+use discv5::Enr;
+use libp2p::{Multiaddr, PeerId};
+use ream_beacon_api_types::{error::ApiError, responses::DataResponse};
+use ream_consensus::constants::genesis_validators_root;
+use ream_network_spec::networks::network_spec;
+use ream_p2p::{
+    network_state::NetworkState,
+    req_resp::{
+        configurations::{AttestationSubnetCount, SyncCommitteeSubnetCount},
+        messages::meta_data::GetMetaDataV2,
+    },
+};
+use serde::Serialize;
+use ssz_types::BitVector;
+
+/// GET /eth/v1/node/identity
+#[get("/node/identity")]
+pub async fn get_node_identity(
+    network_state: Data<Arc<NetworkState>>,
+) -> Result<impl Responder, ApiError> {
+    Ok(HttpResponse::Ok().json(DataResponse::new(NodeIdentity::new(&network_state))))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeIdentity {
+    pub peer_id: PeerId,
+    /// Serialized as the base64 `enr:` text encoding
+    pub enr: Enr,
+    pub p2p_addresses: Vec<Multiaddr>,
+    pub discovery_addresses: Vec<Multiaddr>,
+    pub metadata: NodeMetadata,
+}
+
+impl NodeIdentity {
+    pub fn new(network_state: &NetworkState) -> Self {
+        Self {
+            peer_id: network_state.local_peer_id,
+            enr: network_state.local_enr.clone(),
+            p2p_addresses: network_state.p2p_addresses.clone(),
+            discovery_addresses: network_state.discovery_addresses(),
+            metadata: NodeMetadata::new(
+                network_state.meta_data.read().clone(),
+                network_spec().fork_digest(genesis_validators_root()),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeMetadata {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub seq_number: u64,
+    pub attnets: BitVector<AttestationSubnetCount>,
+    pub syncnets: BitVector<SyncCommitteeSubnetCount>,
+    /// Fork digest of the current fork, the one the node's gossip topics are on
+    pub fork_digest: B32,
+}
+
+impl NodeMetadata {
+    pub fn new(meta_data: GetMetaDataV2, fork_digest: B32) -> Self {
+        Self {
+            seq_number: meta_data.seq_number,
+            attnets: meta_data.attnets,
+            syncnets: meta_data.syncnets,
+            fork_digest,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use discv5::enr::CombinedKey;
+    use libp2p::multiaddr::Protocol;
+
+    use super::*;
+
+    #[test]
+    fn test_serialize_node_identity() {
+        let enr = Enr::builder()
+            .ip4("127.0.0.1".parse().unwrap())
+            .udp4(9000)
+            .build(&CombinedKey::generate_secp256k1())
+            .unwrap();
+        let peer_id = PeerId::random();
+        let mut attnets = BitVector::new();
+        attnets.set(1, true).unwrap();
+        let identity = NodeIdentity {
+            peer_id,
+            enr: enr.clone(),
+            p2p_addresses: vec![
+                Multiaddr::from([127, 0, 0, 1])
+                    .with(Protocol::Tcp(9000))
+                    .with(Protocol::P2p(peer_id)),
+            ],
+            discovery_addresses: vec![],
+            metadata: NodeMetadata {
+                seq_number: 3,
+                attnets,
+                syncnets: BitVector::new(),
+                fork_digest: B32::from([0xad, 0x53, 0x2c, 0xeb]),
+            },
+        };
+
+        let json = serde_json::to_value(&identity).unwrap();
+        let encoded_enr = json["enr"].as_str().unwrap();
+        assert!(encoded_enr.starts_with("enr:"));
+        assert_eq!(encoded_enr, enr.to_base64());
+        assert_eq!(json["peer_id"], peer_id.to_string());
+        assert_eq!(
+            json["p2p_addresses"][0],
+            format!("/ip4/127.0.0.1/tcp/9000/p2p/{peer_id}")
+        );
+        assert_eq!(json["metadata"]["seq_number"], "3");
+        assert_eq!(json["metadata"]["attnets"], "0x0200000000000000");
+        assert_eq!(json["metadata"]["syncnets"], "0x00");
+        assert_eq!(json["metadata"]["fork_digest"], "0xad532ceb");
+    }
+}
@@ -22,6 +22,8 @@ use ream_storage::{
 use ssz::Encode;
 use tree_hash::TreeHash;
 
+use crate::handlers::block::get_synced_checkpoint;
+
 pub const MAX_REQUEST_LIGHT_CLIENT_UPDATES: u64 = 128;
 
 #[get("/beacon/light_client/bootstrap/{block_root}")]
@@ -180,11 +182,8 @@ pub async fn get_light_client_finality_update(
     http_request: HttpRequest,
 ) -> Result<impl Responder, ApiError> {
     // Get the latest finalized checkpoint
-    let finalized_checkpoint = db.finalized_checkpoint_provider().get().map_err(|err| {
-        ApiError::InternalError(format!(
-            "Failed to get finalized checkpoint, error: {err:?}"
-        ))
-    })?;
+    let finalized_checkpoint =
+        get_synced_checkpoint("finalized", db.finalized_checkpoint_provider().get())?;
 
     // Get the latest head block root from the latest slot
     let latest_slot = db
@@ -2,11 +2,15 @@ pub mod blob_sidecar;
 pub mod block;
 pub mod committee;
 pub mod config;
+pub mod debug;
 pub mod duties;
+pub mod events;
 pub mod header;
+pub mod identity;
 pub mod light_client;
 pub mod peers;
 pub mod pool;
+pub mod rewards;
 pub mod state;
 pub mod syncing;
 pub mod validator;
@@ -1,17 +1,35 @@
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 use actix_web::{
     HttpResponse, Responder, get,
     web::{Data, Path},
 };
+use actix_web_lab::extract::Query;
 use discv5::Enr;
 use libp2p::{Multiaddr, PeerId};
 use ream_beacon_api_types::{error::ApiError, responses::DataResponse};
 use ream_p2p::{
     network_state::NetworkState,
-    peer::{ConnectionState, Direction},
+    peer::{CachedPeer, ConnectionState, Direction},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+/// GET /eth/v1/node/peers
+/// Optional parameters `state` and/or `direction`, each may be repeated
+#[get("/node/peers")]
+pub async fn get_peers(
+    network_state: Data<Arc<NetworkState>>,
+    query: Query<PeersQuery>,
+) -> Result<impl Responder, ApiError> {
+    let peers = filter_peers(&network_state.peer_table.read(), &query);
+
+    Ok(HttpResponse::Ok().json(PeersResponse {
+        meta: PeersMeta {
+            count: peers.len() as u64,
+        },
+        data: peers,
+    }))
+}
 
 /// GET /eth/v1/node/peers/{peer_id}
 #[get("/node/peers/{peer_id}")]
@@ -31,25 +49,47 @@ pub async fn get_peer(
         .cloned()
         .ok_or_else(|| ApiError::NotFound(format!("Peer not found: {peer_id}")))?;
 
-    Ok(HttpResponse::Ok().json(DataResponse::new(&Peer {
-        peer_id: cached_peer.peer_id,
-        last_seen_p2p_address: cached_peer.last_seen_p2p_address,
-        state: cached_peer.state,
-        direction: cached_peer.direction,
-        enr: cached_peer.enr,
-    })))
+    Ok(HttpResponse::Ok().json(DataResponse::new(&Peer::from(cached_peer))))
 }
 
 #[get("/node/peer_count")]
 pub async fn get_peer_count(
     network_state: Data<Arc<NetworkState>>,
 ) -> Result<impl Responder, ApiError> {
+    Ok(HttpResponse::Ok().json(DataResponse::new(&count_peers(
+        network_state.peer_table.read().values(),
+    ))))
+}
+
+/// Returns the peers matching the `state` and `direction` filters of `query`, an absent filter
+/// matches every peer.
+fn filter_peers(peer_table: &HashMap<PeerId, CachedPeer>, query: &PeersQuery) -> Vec<Peer> {
+    peer_table
+        .values()
+        .filter(|peer| {
+            query
+                .state
+                .as_ref()
+                .is_none_or(|states| states.contains(&peer.state))
+        })
+        .filter(|peer| {
+            query
+                .direction
+                .as_ref()
+                .is_none_or(|directions| directions.contains(&peer.direction))
+        })
+        .cloned()
+        .map(Peer::from)
+        .collect()
+}
+
+fn count_peers<'a>(peers: impl Iterator<Item = &'a CachedPeer>) -> PeerCount {
     let mut connected = 0;
     let mut connecting = 0;
     let mut disconnected = 0;
     let mut disconnecting = 0;
 
-    for peer in network_state.peer_table.read().values() {
+    for peer in peers {
         match peer.state {
             ConnectionState::Connected => connected += 1,
             ConnectionState::Connecting => connecting += 1,
@@ -58,15 +98,33 @@ pub async fn get_peer_count(
         }
     }
 
-    Ok(HttpResponse::Ok().json(DataResponse::new(&PeerCount {
+    PeerCount {
         connected,
         connecting,
         disconnected,
         disconnecting,
-    })))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PeersQuery {
+    pub state: Option<Vec<ConnectionState>>,
+    pub direction: Option<Vec<Direction>>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Serialize)]
+pub struct PeersResponse {
+    pub data: Vec<Peer>,
+    pub meta: PeersMeta,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PeersMeta {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct PeerCount {
     #[serde(with = "serde_utils::quoted_u64")]
     disconnected: u64,
@@ -97,3 +155,89 @@ pub struct Peer {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enr: Option<Enr>,
 }
+
+impl From<CachedPeer> for Peer {
+    fn from(cached_peer: CachedPeer) -> Self {
+        Self {
+            peer_id: cached_peer.peer_id,
+            last_seen_p2p_address: cached_peer.last_seen_p2p_address,
+            state: cached_peer.state,
+            direction: cached_peer.direction,
+            enr: cached_peer.enr,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer_table() -> HashMap<PeerId, CachedPeer> {
+        [
+            (ConnectionState::Connected, Direction::Inbound),
+            (ConnectionState::Connected, Direction::Outbound),
+            (ConnectionState::Disconnected, Direction::Outbound),
+            (ConnectionState::Connecting, Direction::Inbound),
+        ]
+        .into_iter()
+        .map(|(state, direction)| {
+            let peer_id = PeerId::random();
+            (
+                peer_id,
+                CachedPeer::new(peer_id, None, state, direction, None),
+            )
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_filter_peers() {
+        let peer_table = peer_table();
+
+        assert_eq!(filter_peers(&peer_table, &PeersQuery::default()).len(), 4);
+
+        let connected = filter_peers(
+            &peer_table,
+            &PeersQuery {
+                state: Some(vec![ConnectionState::Connected]),
+                direction: None,
+            },
+        );
+        assert_eq!(connected.len(), 2);
+        assert!(
+            connected
+                .iter()
+                .all(|peer| peer.state == ConnectionState::Connected)
+        );
+
+        let outbound = filter_peers(
+            &peer_table,
+            &PeersQuery {
+                state: Some(vec![
+                    ConnectionState::Connected,
+                    ConnectionState::Disconnected,
+                ]),
+                direction: Some(vec![Direction::Outbound]),
+            },
+        );
+        assert_eq!(outbound.len(), 2);
+        assert!(
+            outbound
+                .iter()
+                .all(|peer| peer.direction == Direction::Outbound)
+        );
+    }
+
+    #[test]
+    fn test_count_peers() {
+        assert_eq!(
+            count_peers(peer_table().values()),
+            PeerCount {
+                disconnected: 1,
+                connecting: 1,
+                connected: 2,
+                disconnecting: 0,
+            }
+        );
+    }
+}
@@ -2,14 +2,166 @@ use std::sync::Arc;
 
 use actix_web::{
     HttpResponse, Responder, get, post,
-    web::{Data, Json},
+    web::{Data, Query},
+};
+use anyhow::{anyhow, ensure};
+use ream_beacon_api_types::{
+    error::ApiError,
+    id::ID,
+    query::AttestationPoolQuery,
+    responses::{ApiResponse, DataResponse, ETH_CONSENSUS_VERSION_HEADER, VERSION},
+};
+use ream_consensus::{
+    attestation::Attestation,
+    attester_slashing::AttesterSlashing,
+    bls_to_execution_change::SignedBLSToExecutionChange,
+    electra::beacon_state::BeaconState,
+    misc::{compute_epoch_at_slot, get_committee_indices},
+    single_attestation::SingleAttestation,
+    voluntary_exit::SignedVoluntaryExit,
 };
-use ream_beacon_api_types::{error::ApiError, id::ID, responses::DataResponse};
-use ream_consensus::voluntary_exit::SignedVoluntaryExit;
 use ream_operation_pool::OperationPool;
 use ream_storage::db::ReamDB;
+use ssz_types::{BitList, BitVector};
+
+use crate::{handlers::state::get_state_from_id, request_body::SszOrJson};
+
+/// GET /eth/v2/beacon/pool/attestations
+#[get("/beacon/pool/attestations")]
+pub async fn get_pool_attestations(
+    operation_pool: Data<Arc<OperationPool>>,
+    query: Query<AttestationPoolQuery>,
+) -> Result<impl Responder, ApiError> {
+    let attestations = filter_pool_attestations(&operation_pool, &query);
+    Ok(HttpResponse::Ok()
+        .insert_header((ETH_CONSENSUS_VERSION_HEADER, VERSION))
+        .json(ApiResponse::data(attestations).with_version(VERSION)))
+}
+
+/// Returns the pooled attestations matching `query`. A slot is looked up through the pool's slot
+/// index rather than by filtering every pooled attestation.
+pub fn filter_pool_attestations(
+    operation_pool: &OperationPool,
+    query: &AttestationPoolQuery,
+) -> Vec<Attestation> {
+    let attestations = match query.slot {
+        Some(slot) => operation_pool.get_attestations_at_slot(slot),
+        None => operation_pool.get_attestations(),
+    };
+
+    match query.committee_index {
+        Some(committee_index) => attestations
+            .into_iter()
+            .filter(|attestation| {
+                get_committee_indices(&attestation.committee_bits).contains(&committee_index)
+            })
+            .collect(),
+        None => attestations,
+    }
+}
+
+/// POST /eth/v2/beacon/pool/attestations
+///
+/// Every valid attestation is pooled, even if others in the same request are rejected. Rejected
+/// attestations are reported by index with a 400.
+#[post("/beacon/pool/attestations")]
+pub async fn post_pool_attestations(
+    db: Data<ReamDB>,
+    operation_pool: Data<Arc<OperationPool>>,
+    single_attestations: SszOrJson<Vec<SingleAttestation>>,
+) -> Result<impl Responder, ApiError> {
+    let beacon_state = get_highest_state(&db).await?;
+    insert_single_attestations(
+        &beacon_state,
+        &operation_pool,
+        single_attestations.into_inner(),
+    )?;
+    // TODO: publish attestations to peers (gossipsub)
+
+    Ok(HttpResponse::Ok())
+}
 
-use crate::handlers::state::get_state_from_id;
+/// Validates each of `single_attestations` against `beacon_state` and pools the valid ones as
+/// attestations of their committee.
+pub fn insert_single_attestations(
+    beacon_state: &BeaconState,
+    operation_pool: &OperationPool,
+    single_attestations: Vec<SingleAttestation>,
+) -> Result<(), ApiError> {
+    let failures = single_attestations
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, single_attestation)| {
+            match to_committee_attestation(beacon_state, single_attestation) {
+                Ok(attestation) => {
+                    operation_pool.insert_attestation(attestation);
+                    None
+                }
+                Err(err) => Some(format!("{index}: {err}")),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if !failures.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid attestations, they will never pass validation so they're rejected: {}",
+            failures.join(", ")
+        )));
+    }
+
+    Ok(())
+}
+
+/// The attestation of `single_attestation`'s committee with only its attester's bit set, once its
+/// signature is verified.
+fn to_committee_attestation(
+    beacon_state: &BeaconState,
+    single_attestation: SingleAttestation,
+) -> anyhow::Result<Attestation> {
+    let SingleAttestation {
+        committee_index,
+        attester_index,
+        data,
+        signature,
+    } = single_attestation;
+    let committee_count =
+        beacon_state.get_committee_count_per_slot(compute_epoch_at_slot(data.slot));
+    ensure!(
+        committee_index < committee_count,
+        "committee index {committee_index} is out of range, there are {committee_count} committees"
+    );
+
+    let committee = beacon_state.get_beacon_committee(data.slot, committee_index)?;
+    let position = committee
+        .iter()
+        .position(|&index| index == attester_index)
+        .ok_or_else(|| {
+            anyhow!("validator {attester_index} is not in committee {committee_index}")
+        })?;
+    let mut aggregation_bits = BitList::with_capacity(committee.len())
+        .map_err(|err| anyhow!("Failed to create aggregation bits: {err:?}"))?;
+    aggregation_bits
+        .set(position, true)
+        .map_err(|err| anyhow!("Failed to set aggregation bit: {err:?}"))?;
+    let mut committee_bits = BitVector::new();
+    committee_bits
+        .set(committee_index as usize, true)
+        .map_err(|err| anyhow!("Failed to set committee bit: {err:?}"))?;
+
+    let attestation = Attestation {
+        aggregation_bits,
+        data,
+        signature,
+        committee_bits,
+    };
+    ensure!(
+        beacon_state
+            .is_valid_indexed_attestation(&beacon_state.get_indexed_attestation(&attestation)?)?,
+        "invalid signature"
+    );
+
+    Ok(attestation)
+}
 
 /// GET /eth/v1/beacon/pool/voluntary_exits
 #[get("/beacon/pool/voluntary_exits")]
@@ -25,31 +177,106 @@ pub async fn get_voluntary_exits(
 pub async fn post_voluntary_exits(
     db: Data<ReamDB>,
     operation_pool: Data<Arc<OperationPool>>,
-    signed_voluntary_exit: Json<SignedVoluntaryExit>,
+    signed_voluntary_exit: SszOrJson<SignedVoluntaryExit>,
 ) -> Result<impl Responder, ApiError> {
-    let highest_slot = db
-        .slot_index_provider()
-        .get_highest_slot()
-        .map_err(|err| {
-            ApiError::InternalError(format!("Failed to get_highest_slot, error: {err:?}"))
-        })?
-        .ok_or(ApiError::NotFound(
-            "Failed to find highest slot".to_string(),
-        ))?;
-    let beacon_state = get_state_from_id(ID::Slot(highest_slot), &db).await?;
+    let beacon_state = get_highest_state(&db).await?;
 
     let signed_voluntary_exit = signed_voluntary_exit.into_inner();
 
-    beacon_state
-        .validate_voluntary_exit(&signed_voluntary_exit)
+    operation_pool
+        .validate_and_insert_signed_voluntary_exit(&beacon_state, signed_voluntary_exit)
         .map_err(|err| {
             ApiError::BadRequest(format!(
                 "Invalid voluntary exit, it will never pass validation so it's rejected: {err:?}"
             ))
         })?;
-
-    operation_pool.insert_signed_voluntary_exit(signed_voluntary_exit);
     // TODO: publish voluntary exit to peers (gossipsub) - https://github.com/ReamLabs/ream/issues/556
 
     Ok(HttpResponse::Ok())
 }
+
+/// POST /eth/v1/beacon/pool/attester_slashings
+#[post("/beacon/pool/attester_slashings")]
+pub async fn post_attester_slashings(
+    db: Data<ReamDB>,
+    operation_pool: Data<Arc<OperationPool>>,
+    attester_slashing: SszOrJson<AttesterSlashing>,
+) -> Result<impl Responder, ApiError> {
+    let beacon_state = get_highest_state(&db).await?;
+
+    operation_pool
+        .validate_and_insert_attester_slashing(&beacon_state, attester_slashing.into_inner())
+        .map_err(|err| ApiError::BadRequest(format!("Invalid attester slashing: {err:?}")))?;
+    // TODO: publish attester slashing to peers (gossipsub)
+
+    Ok(HttpResponse::Ok())
+}
+
+/// GET /eth/v1/beacon/pool/bls_to_execution_changes
+#[get("/beacon/pool/bls_to_execution_changes")]
+pub async fn get_bls_to_execution_changes(
+    operation_pool: Data<Arc<OperationPool>>,
+) -> Result<impl Responder, ApiError> {
+    let bls_to_execution_changes = operation_pool.get_bls_to_execution_changes();
+    Ok(HttpResponse::Ok().json(DataResponse::new(bls_to_execution_changes)))
+}
+
+/// POST /eth/v1/beacon/pool/bls_to_execution_changes
+///
+/// Every valid change is pooled, even if others in the same request are rejected. Rejected
+/// changes are reported by index with a 400.
+#[post("/beacon/pool/bls_to_execution_changes")]
+pub async fn post_bls_to_execution_changes(
+    db: Data<ReamDB>,
+    operation_pool: Data<Arc<OperationPool>>,
+    bls_to_execution_changes: SszOrJson<Vec<SignedBLSToExecutionChange>>,
+) -> Result<impl Responder, ApiError> {
+    let beacon_state = get_highest_state(&db).await?;
+    insert_bls_to_execution_changes(
+        &beacon_state,
+        &operation_pool,
+        bls_to_execution_changes.into_inner(),
+    )?;
+    // TODO: publish bls to execution changes to peers (gossipsub)
+
+    Ok(HttpResponse::Ok())
+}
+
+/// Validates each of `bls_to_execution_changes` against `beacon_state` and pools the valid ones.
+pub fn insert_bls_to_execution_changes(
+    beacon_state: &BeaconState,
+    operation_pool: &OperationPool,
+    bls_to_execution_changes: Vec<SignedBLSToExecutionChange>,
+) -> Result<(), ApiError> {
+    let failures = bls_to_execution_changes
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, bls_to_execution_change)| {
+            operation_pool
+                .validate_and_insert_bls_to_execution_change(beacon_state, bls_to_execution_change)
+                .err()
+                .map(|err| format!("{index}: {err}"))
+        })
+        .collect::<Vec<_>>();
+
+    if !failures.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid bls to execution changes, they will never pass validation so they're rejected: {}",
+            failures.join(", ")
+        )));
+    }
+
+    Ok(())
+}
+
+async fn get_highest_state(db: &ReamDB) -> Result<BeaconState, ApiError> {
+    let highest_slot = db
+        .slot_index_provider()
+        .get_highest_slot()
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to get_highest_slot, error: {err:?}"))
+        })?
+        .ok_or(ApiError::NotSynced("No block is stored yet".to_string()))?;
+
+    get_state_from_id(ID::Slot(highest_slot), db).await
+}
@@ -0,0 +1,312 @@
+use std::collections::{BTreeSet, HashMap, HashSet, hash_map::Entry};
+
+use actix_web::{
+    HttpResponse, Responder, post,
+    web::{Data, Json, Path},
+};
+use ream_beacon_api_types::{
+    error::ApiError,
+    id::{ID, ValidatorID},
+    responses::BeaconResponse,
+};
+use ream_consensus::{
+    constants::{
+        PARTICIPATION_FLAG_WEIGHTS, TIMELY_HEAD_FLAG_INDEX, TIMELY_SOURCE_FLAG_INDEX,
+        TIMELY_TARGET_FLAG_INDEX,
+    },
+    electra::beacon_state::BeaconState,
+    misc::compute_start_slot_at_epoch,
+};
+use ream_storage::{db::ReamDB, tables::Field};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    block::{
+        get_beacon_block_from_id, get_block_pre_state, get_block_root_from_id,
+        get_synced_checkpoint, is_finalized_block,
+    },
+    state::get_state_with_root_from_id,
+    validator::resolve_validator_id,
+};
+
+/// Sync committee reward of a single validator for a block, in Gwei. Absent members are
+/// penalized, so `reward` can be negative.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SyncCommitteeReward {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    #[serde(with = "ream_beacon_api_types::quoted_i64")]
+    pub reward: i64,
+}
+
+/// Attestation rewards an ideally performing validator with `effective_balance` would earn, in
+/// Gwei.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IdealAttestationRewards {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub effective_balance: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub head: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub target: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub source: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub inactivity: u64,
+}
+
+/// Attestation rewards a validator earned, in Gwei. Penalties are negative.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TotalAttestationRewards {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    #[serde(with = "ream_beacon_api_types::quoted_i64")]
+    pub head: i64,
+    #[serde(with = "ream_beacon_api_types::quoted_i64")]
+    pub target: i64,
+    #[serde(with = "ream_beacon_api_types::quoted_i64")]
+    pub source: i64,
+    #[serde(with = "ream_beacon_api_types::quoted_i64")]
+    pub inactivity: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AttestationRewards {
+    pub ideal_rewards: Vec<IdealAttestationRewards>,
+    pub total_rewards: Vec<TotalAttestationRewards>,
+}
+
+/// Splits the previous epoch attestation deltas of `state` into their head, target, source and
+/// inactivity components for every eligible validator in `validator_indices`, or all eligible
+/// validators if no filter is given. Ideal rewards are reported for each effective balance among
+/// those validators.
+fn compute_attestation_rewards(
+    state: &BeaconState,
+    validator_indices: Option<&HashSet<u64>>,
+) -> Result<AttestationRewards, ApiError> {
+    let deltas = state.get_attestation_deltas().map_err(|err| {
+        ApiError::InternalError(format!("Failed to get attestation deltas, error: {err:?}"))
+    })?;
+    let get_delta = |component: usize, index: u64| {
+        let (rewards, penalties) = &deltas[component];
+        rewards[index as usize] as i64 - penalties[index as usize] as i64
+    };
+
+    let eligible_indices = state
+        .get_eligible_validator_indices()
+        .map_err(|err| {
+            ApiError::InternalError(format!(
+                "Failed to get eligible validator indices, error: {err:?}"
+            ))
+        })?
+        .into_iter()
+        .filter(|index| validator_indices.is_none_or(|indices| indices.contains(index)))
+        .collect::<Vec<_>>();
+
+    let total_rewards = eligible_indices
+        .iter()
+        .map(|&index| TotalAttestationRewards {
+            validator_index: index,
+            head: get_delta(TIMELY_HEAD_FLAG_INDEX as usize, index),
+            target: get_delta(TIMELY_TARGET_FLAG_INDEX as usize, index),
+            source: get_delta(TIMELY_SOURCE_FLAG_INDEX as usize, index),
+            inactivity: get_delta(PARTICIPATION_FLAG_WEIGHTS.len(), index),
+        })
+        .collect();
+
+    let effective_balances = eligible_indices
+        .iter()
+        .map(|&index| state.validators[index as usize].effective_balance)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let ideal_rewards = state
+        .get_ideal_flag_index_rewards(&effective_balances)
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to get ideal rewards, error: {err:?}"))
+        })?
+        .into_iter()
+        .zip(effective_balances)
+        .map(|(rewards, effective_balance)| IdealAttestationRewards {
+            effective_balance,
+            head: rewards[TIMELY_HEAD_FLAG_INDEX as usize],
+            target: rewards[TIMELY_TARGET_FLAG_INDEX as usize],
+            source: rewards[TIMELY_SOURCE_FLAG_INDEX as usize],
+            inactivity: 0,
+        })
+        .collect();
+
+    Ok(AttestationRewards {
+        ideal_rewards,
+        total_rewards,
+    })
+}
+
+/// Applies `participant_reward` to every sync committee member that participated and the equal
+/// penalty to every member that did not. Validators that hold several committee seats get the sum
+/// over their seats, in order of their first seat.
+fn compute_sync_committee_rewards(
+    committee_indices: &[u64],
+    participation: impl IntoIterator<Item = bool>,
+    participant_reward: u64,
+) -> Vec<SyncCommitteeReward> {
+    let participant_reward = participant_reward as i64;
+    let mut rewards: Vec<SyncCommitteeReward> = vec![];
+    let mut positions = HashMap::new();
+
+    for (&validator_index, participated) in committee_indices.iter().zip(participation) {
+        let reward = if participated {
+            participant_reward
+        } else {
+            -participant_reward
+        };
+        match positions.entry(validator_index) {
+            Entry::Occupied(entry) => rewards[*entry.get()].reward += reward,
+            Entry::Vacant(entry) => {
+                entry.insert(rewards.len());
+                rewards.push(SyncCommitteeReward {
+                    validator_index,
+                    reward,
+                });
+            }
+        }
+    }
+
+    rewards
+}
+
+/// Called by `/beacon/rewards/sync_committee/{block_id}` to get the sync committee rewards of a
+/// block, optionally filtered to the validators in the request body.
+#[post("/beacon/rewards/sync_committee/{block_id}")]
+pub async fn post_sync_committee_rewards(
+    db: Data<ReamDB>,
+    validator_index_cache: Data<ValidatorIndexCache>,
+    block_id: Path<ID>,
+    validator_ids: Option<Json<Vec<ValidatorID>>>,
+) -> Result<impl Responder, ApiError> {
+    let block_root = get_block_root_from_id(block_id.into_inner(), db.get_ref()).await?;
+    let beacon_block = get_beacon_block_from_id(ID::Root(block_root), db.get_ref()).await?;
+    let beacon_state = get_block_pre_state(&beacon_block, db.get_ref()).await?;
+
+    let committee_indices = beacon_state
+        .get_current_sync_committee_indices()
+        .map_err(|err| {
+            ApiError::InternalError(format!(
+                "Failed to get sync committee indices, error: {err:?}"
+            ))
+        })?;
+    let (participant_reward, _) = beacon_state.get_sync_aggregate_rewards();
+    let mut rewards = compute_sync_committee_rewards(
+        &committee_indices,
+        beacon_block
+            .message
+            .body
+            .sync_aggregate
+            .sync_committee_bits
+            .iter(),
+        participant_reward,
+    );
+
+    if let Some(validator_ids) = validator_ids {
+        // Advancing slots fills in the header with the parent state root, and the registry only
+        // changes on blocks, so the pre-state shares the cached indices of the parent state
+        let parent_state_root = beacon_state.latest_block_header.state_root;
+        let validator_indices = validator_ids
+            .iter()
+            .map(|validator_id| {
+                resolve_validator_id(
+                    &validator_index_cache,
+                    parent_state_root,
+                    &beacon_state,
+                    validator_id,
+                )
+            })
+            .collect::<Result<HashSet<_>, _>>()?;
+        rewards.retain(|reward| validator_indices.contains(&reward.validator_index));
+    }
+
+    let finalized = is_finalized_block(block_root, db.get_ref())?;
+
+    Ok(HttpResponse::Ok().json(BeaconResponse::new(rewards).with_finalized(finalized)))
+}
+
+/// Called by `/beacon/rewards/attestations/{epoch}` to get the attestation rewards of a finalized
+/// epoch, optionally filtered to the validators in the request body.
+#[post("/beacon/rewards/attestations/{epoch}")]
+pub async fn post_attestation_rewards(
+    db: Data<ReamDB>,
+    validator_index_cache: Data<ValidatorIndexCache>,
+    epoch: Path<u64>,
+    validator_ids: Option<Json<Vec<ValidatorID>>>,
+) -> Result<impl Responder, ApiError> {
+    let epoch = epoch.into_inner();
+    let finalized_checkpoint =
+        get_synced_checkpoint("finalized", db.finalized_checkpoint_provider().get())?;
+    if epoch > finalized_checkpoint.epoch {
+        return Err(ApiError::BadRequest(format!(
+            "Epoch {epoch} is not finalized, finalized epoch is {}",
+            finalized_checkpoint.epoch
+        )));
+    }
+
+    // Attestations of `epoch` are rewarded in the transition at the end of the next epoch
+    let (state_root, state) =
+        get_state_with_root_from_id(ID::Slot(compute_start_slot_at_epoch(epoch + 2) - 1), &db)
+            .await?;
+
+    let validator_indices = match validator_ids {
+        Some(validator_ids) => Some(
+            validator_ids
+                .iter()
+                .map(|validator_id| {
+                    resolve_validator_id(&validator_index_cache, state_root, &state, validator_id)
+                })
+                .collect::<Result<HashSet<_>, _>>()?,
+        ),
+        None => None,
+    };
+    let rewards = compute_attestation_rewards(&state, validator_indices.as_ref())?;
+
+    Ok(HttpResponse::Ok().json(BeaconResponse::new(rewards)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_sync_committee_rewards() {
+        let rewards = compute_sync_committee_rewards(&[1, 2], [true, false], 10);
+        assert_eq!(
+            rewards,
+            vec![
+                SyncCommitteeReward {
+                    validator_index: 1,
+                    reward: 10,
+                },
+                SyncCommitteeReward {
+                    validator_index: 2,
+                    reward: -10,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_sync_committee_rewards_repeated_member() {
+        let rewards = compute_sync_committee_rewards(&[3, 5, 3, 3], [true, true, true, false], 10);
+        assert_eq!(
+            rewards,
+            vec![
+                SyncCommitteeReward {
+                    validator_index: 3,
+                    reward: 10,
+                },
+                SyncCommitteeReward {
+                    validator_index: 5,
+                    reward: 10,
+                },
+            ]
+        );
+    }
+}
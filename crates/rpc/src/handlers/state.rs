@@ -1,8 +1,10 @@
+use std::collections::HashMap;
+
 use actix_web::{
     HttpResponse, Responder, get,
     web::{Data, Path, Query},
 };
-use alloy_primitives::B256;
+use alloy_primitives::{B32, B256};
 use ream_beacon_api_types::{
     error::ApiError,
     id::ID,
@@ -10,16 +12,22 @@ use ream_beacon_api_types::{
     responses::{BeaconResponse, BeaconVersionedResponse},
 };
 use ream_consensus::{
-    checkpoint::Checkpoint, constants::SYNC_COMMITTEE_SIZE, electra::beacon_state::BeaconState,
+    checkpoint::Checkpoint,
+    constants::{EPOCHS_PER_HISTORICAL_VECTOR, SYNC_COMMITTEE_SIZE, TIMELY_TARGET_FLAG_INDEX},
+    electra::beacon_state::BeaconState,
     misc::compute_sync_committee_period,
 };
+use ream_network_spec::networks::network_spec;
 use ream_storage::{
     db::ReamDB,
+    errors::StoreError,
     tables::{Field, Table},
 };
 use serde::{Deserialize, Serialize};
 use tree_hash::TreeHash;
 
+use super::block::get_beacon_block_from_id;
+
 pub const SYNC_COMMITTEE_SUBNET_COUNT: u64 = 4;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -54,64 +62,166 @@ impl RandaoResponse {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(transparent)]
-struct QuotedU64Vec(#[serde(with = "serde_utils::quoted_u64_vec")] Vec<u64>);
+pub struct QuotedU64Vec(#[serde(with = "serde_utils::quoted_u64_vec")] pub Vec<u64>);
 
-#[derive(Serialize, Deserialize)]
-struct SyncCommitteeResponse {
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncCommitteeResponse {
     #[serde(with = "serde_utils::quoted_u64_vec")]
     pub validators: Vec<u64>,
     pub validator_aggregates: Vec<QuotedU64Vec>,
+    /// Sum of the effective balances of `validators`, counting a validator once per seat.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub total_effective_balance: u64,
+    /// Number of seats held by validators that attested timely to the target in the state's
+    /// previous epoch.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub participation: u64,
 }
 
-pub async fn get_state_from_id(state_id: ID, db: &ReamDB) -> Result<BeaconState, ApiError> {
-    let block_root = match state_id {
-        ID::Finalized => {
-            let finalized_checkpoint = db.finalized_checkpoint_provider().get().map_err(|err| {
-                ApiError::InternalError(format!(
-                    "Failed to get finalized_checkpoint, error: {err:?}"
-                ))
-            })?;
-
-            Ok(Some(finalized_checkpoint.root))
+/// Resolves `state_id` to a state root. `ID::Root` is a state root and has to be known to the
+/// state root index, every other ID resolves to the post-state root of the matching block. The
+/// state at a skipped slot is the previous block's post-state advanced to that slot.
+pub async fn get_state_root_from_id(state_id: ID, db: &ReamDB) -> Result<B256, ApiError> {
+    if let ID::Slot(slot) = state_id {
+        if is_skipped_slot(slot, db)? {
+            return Ok(reconstruct_state(slot, db).await?.tree_hash_root());
         }
-        ID::Justified => {
-            let justified_checkpoint = db.justified_checkpoint_provider().get().map_err(|err| {
-                ApiError::InternalError(format!(
-                    "Failed to get justified_checkpoint, error: {err:?}"
-                ))
-            })?;
+    }
 
-            Ok(Some(justified_checkpoint.root))
+    let block_id = match state_id {
+        ID::Root(state_root) => {
+            return match db
+                .state_root_index_provider()
+                .get(state_root)
+                .map_err(|err| {
+                    ApiError::InternalError(format!(
+                        "Failed to get state root index, error: {err:?}"
+                    ))
+                })? {
+                Some(_) => Ok(state_root),
+                None => Err(ApiError::NotFound(format!(
+                    "Failed to find `state_root` from {state_id:?}"
+                ))),
+            };
         }
-        ID::Head | ID::Genesis => {
-            return Err(ApiError::NotFound(format!(
-                "This ID type is currently not supported: {state_id:?}"
-            )));
+        ID::Finalized | ID::Justified | ID::Head | ID::Genesis | ID::Slot(_) => state_id,
+    };
+
+    let block = get_beacon_block_from_id(block_id, db).await?;
+
+    Ok(block.message.state_root)
+}
+
+/// Resolves `state_id` to a state. Unlike blocks, states exist at skipped slots, where the state
+/// is the previous block's post-state advanced to the slot.
+pub async fn get_state_from_id(state_id: ID, db: &ReamDB) -> Result<BeaconState, ApiError> {
+    if let ID::Slot(slot) = state_id {
+        // Skipped slots have no block, so the state is rebuilt from an earlier one
+        if is_skipped_slot(slot, db)? {
+            return reconstruct_state(slot, db).await;
         }
-        ID::Slot(slot) => db.slot_index_provider().get(slot),
-        ID::Root(root) => db.state_root_index_provider().get(root),
     }
-    .map_err(|err| ApiError::InternalError(format!("Failed to get headers, error: {err:?}")))?
-    .ok_or_else(|| ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}")))?;
 
-    db.beacon_state_provider()
+    let state_root = get_state_root_from_id(state_id, db).await?;
+    let block_root = db
+        .state_root_index_provider()
+        .get(state_root)
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to get state root index, error: {err:?}"))
+        })?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}"))
+        })?;
+
+    if let Some(state) = db.beacon_state_provider().get(block_root).map_err(|err| {
+        ApiError::InternalError(format!("Failed to get block by block_root, error: {err:?}"))
+    })? {
+        return Ok(state);
+    }
+
+    // The state may have been pruned, in which case it is rebuilt by replaying its block
+    let block = db
+        .beacon_block_provider()
         .get(block_root)
         .map_err(|err| {
             ApiError::InternalError(format!("Failed to get block by block_root, error: {err:?}"))
         })?
-        .ok_or_else(|| ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}")))
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("Failed to find `block_root` from {state_id:?}"))
+        })?;
+
+    // Only canonical states can be rebuilt, as replay follows the slot index
+    let canonical_root = db
+        .slot_index_provider()
+        .get(block.message.slot)
+        .map_err(|err| {
+            ApiError::InternalError(format!("Failed to get slot index, error: {err:?}"))
+        })?;
+    if canonical_root != Some(block_root) {
+        return Err(ApiError::NotFound(format!(
+            "Failed to find state from {state_id:?}"
+        )));
+    }
+
+    reconstruct_state(block.message.slot, db).await
 }
 
-#[get("/beacon/states/{state_id}")]
-pub async fn get_beacon_state(
-    db: Data<ReamDB>,
-    state_id: Path<ID>,
-) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
+/// Resolves `state_id` to a state and its root. Only a state rebuilt at a skipped slot is hashed,
+/// every other root comes from the state root index or a block.
+pub async fn get_state_with_root_from_id(
+    state_id: ID,
+    db: &ReamDB,
+) -> Result<(B256, BeaconState), ApiError> {
+    if let ID::Slot(slot) = state_id {
+        if is_skipped_slot(slot, db)? {
+            let state = reconstruct_state(slot, db).await?;
+            return Ok((state.tree_hash_root(), state));
+        }
+    }
+
+    let state_root = get_state_root_from_id(state_id, db).await?;
+    let state = get_state_from_id(ID::Root(state_root), db).await?;
+
+    Ok((state_root, state))
+}
+
+/// Returns whether no block was stored for `slot`. Slots past the highest stored block count as
+/// skipped as well, `reconstruct_state` rejects those.
+fn is_skipped_slot(slot: u64, db: &ReamDB) -> Result<bool, ApiError> {
+    let block_root = db.slot_index_provider().get(slot).map_err(|err| {
+        ApiError::InternalError(format!("Failed to get slot index, error: {err:?}"))
+    })?;
+
+    Ok(block_root.is_none())
+}
+
+/// Rebuilds the state at `slot` by replaying stored blocks on top of the nearest stored state.
+/// Slots past the head are not reconstructed.
+async fn reconstruct_state(slot: u64, db: &ReamDB) -> Result<BeaconState, ApiError> {
+    let highest_slot = db.slot_index_provider().get_highest_slot().map_err(|err| {
+        ApiError::InternalError(format!("Failed to get highest slot, error: {err:?}"))
+    })?;
+    let Some(highest_slot) = highest_slot else {
+        return Err(ApiError::NotSynced("No block is stored yet".to_string()));
+    };
+    if slot > highest_slot {
+        return Err(ApiError::NotFound(format!(
+            "Failed to find state for slot {slot}"
+        )));
+    }
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(state)))
+    db.reconstruct_state_at(slot)
+        .await
+        .map_err(|err| match err {
+            StoreError::NotFound(_) => {
+                ApiError::NotFound(format!("Failed to find state for slot {slot}"))
+            }
+            err => ApiError::InternalError(format!(
+                "Failed to reconstruct state for slot {slot}, error: {err:?}"
+            )),
+        })
 }
 
 #[get("/beacon/states/{state_id}/root")]
@@ -165,14 +275,38 @@ pub async fn get_state_randao(
 ) -> Result<impl Responder, ApiError> {
     let state = get_state_from_id(state_id.into_inner(), &db).await?;
 
-    let randao_mix = match query.epoch {
-        Some(epoch) => state.get_randao_mix(epoch),
-        None => state.get_randao_mix(state.get_current_epoch()),
-    };
+    let epoch = get_randao_epoch(state.get_current_epoch(), query.epoch)?;
+    let randao_mix = state.get_randao_mix(epoch);
 
     Ok(HttpResponse::Ok().json(BeaconResponse::new(RandaoResponse::new(randao_mix))))
 }
 
+/// Returns the epoch to read the RANDAO mix for, defaulting to `current_epoch`. `randao_mixes`
+/// only holds the last `EPOCHS_PER_HISTORICAL_VECTOR` epochs, so anything older or in the future
+/// is rejected.
+fn get_randao_epoch(current_epoch: u64, epoch: Option<u64>) -> Result<u64, ApiError> {
+    let epoch = epoch.unwrap_or(current_epoch);
+    if epoch > current_epoch || epoch + EPOCHS_PER_HISTORICAL_VECTOR <= current_epoch {
+        return Err(ApiError::BadRequest(format!(
+            "Epoch {epoch} is out of range for the RANDAO mixes of a state at epoch {current_epoch}"
+        )));
+    }
+
+    Ok(epoch)
+}
+
+/// The pending deposit, withdrawal and consolidation queues were introduced in Electra, so states
+/// of earlier forks have none.
+fn ensure_electra_state(fork_version: B32, electra_fork_version: B32) -> Result<(), ApiError> {
+    if fork_version != electra_fork_version {
+        return Err(ApiError::BadRequest(format!(
+            "State fork version {fork_version} predates Electra"
+        )));
+    }
+
+    Ok(())
+}
+
 /// Called by `/eth/v1/beacon/states/{state_id}/pending_consolidations` to get pending
 /// consolidations for state with given stateId
 #[get("/beacon/states/{state_id}/pending_consolidations")]
@@ -181,6 +315,10 @@ pub async fn get_pending_consolidations(
     state_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
     let state = get_state_from_id(state_id.into_inner(), &db).await?;
+    ensure_electra_state(
+        state.fork.current_version,
+        network_spec().electra_fork_version,
+    )?;
 
     Ok(
         HttpResponse::Ok().json(BeaconVersionedResponse::new(Vec::from(
@@ -197,6 +335,10 @@ pub async fn get_pending_deposits(
     state_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
     let state = get_state_from_id(state_id.into_inner(), &db).await?;
+    ensure_electra_state(
+        state.fork.current_version,
+        network_spec().electra_fork_version,
+    )?;
 
     Ok(
         HttpResponse::Ok().json(BeaconVersionedResponse::new(Vec::from(
@@ -213,6 +355,10 @@ pub async fn get_pending_partial_withdrawals(
     state_id: Path<ID>,
 ) -> Result<impl Responder, ApiError> {
     let state = get_state_from_id(state_id.into_inner(), &db).await?;
+    ensure_electra_state(
+        state.fork.current_version,
+        network_spec().electra_fork_version,
+    )?;
 
     Ok(
         HttpResponse::Ok().json(BeaconVersionedResponse::new(Vec::from(
@@ -231,8 +377,24 @@ pub async fn get_sync_committees(
     epoch: Query<EpochQuery>,
 ) -> Result<impl Responder, ApiError> {
     let state = get_state_from_id(state_id.into_inner(), &db).await?;
+
+    Ok(
+        HttpResponse::Ok().json(BeaconVersionedResponse::new(compute_sync_committees(
+            &state,
+            epoch.epoch,
+        )?)),
+    )
+}
+
+/// Maps the sync committee of `state` for `epoch`, the current epoch if `None`, back to validator
+/// indices and splits them into `SYNC_COMMITTEE_SUBNET_COUNT` subcommittees. Only the current and
+/// the next sync committee period are known to a state.
+pub fn compute_sync_committees(
+    state: &BeaconState,
+    epoch: Option<u64>,
+) -> Result<SyncCommitteeResponse, ApiError> {
     let current_epoch = state.get_current_epoch();
-    let epoch = epoch.epoch.unwrap_or(current_epoch);
+    let epoch = epoch.unwrap_or(current_epoch);
     let sync_committee_period = compute_sync_committee_period(epoch);
     let current_sync_committee_period = compute_sync_committee_period(current_epoch);
 
@@ -246,27 +408,83 @@ pub async fn get_sync_committees(
         )));
     };
 
+    let validator_indices = state
+        .validators
+        .iter()
+        .enumerate()
+        .map(|(index, validator)| (&validator.public_key, index as u64))
+        .collect::<HashMap<_, _>>();
     let validators = sync_committee
         .public_keys
         .iter()
-        .filter_map(|public_key| {
-            state
-                .validators
-                .iter()
-                .position(|validator| validator.public_key == *public_key)
-                .map(|position| position as u64)
+        .map(|public_key| {
+            validator_indices.get(public_key).copied().ok_or_else(|| {
+                ApiError::InternalError(format!(
+                    "Sync committee member {public_key:?} is not in the validator registry"
+                ))
+            })
         })
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, _>>()?;
 
     let validator_aggregates = validators
         .chunks_exact((SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT) as usize)
         .map(|chunk| QuotedU64Vec(chunk.to_vec()))
         .collect::<Vec<QuotedU64Vec>>();
 
-    Ok(
-        HttpResponse::Ok().json(BeaconVersionedResponse::new(SyncCommitteeResponse {
-            validators,
-            validator_aggregates,
-        })),
-    )
+    let total_effective_balance = validators
+        .iter()
+        .map(|&index| state.validators[index as usize].effective_balance)
+        .sum();
+    let participation = validators
+        .iter()
+        .filter(|&&index| {
+            state
+                .previous_epoch_participation
+                .get(index as usize)
+                .is_some_and(|&flags| BeaconState::has_flag(flags, TIMELY_TARGET_FLAG_INDEX))
+        })
+        .count() as u64;
+
+    Ok(SyncCommitteeResponse {
+        validators,
+        validator_aggregates,
+        total_effective_balance,
+        participation,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_randao_epoch_defaults_to_current_epoch() {
+        assert_eq!(get_randao_epoch(10, None).unwrap(), 10);
+        assert_eq!(get_randao_epoch(10, Some(3)).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_randao_epoch_out_of_range() {
+        assert!(matches!(
+            get_randao_epoch(10, Some(11)),
+            Err(ApiError::BadRequest(_))
+        ));
+
+        let current_epoch = EPOCHS_PER_HISTORICAL_VECTOR + 5;
+        assert_eq!(get_randao_epoch(current_epoch, Some(6)).unwrap(), 6);
+        assert!(matches!(
+            get_randao_epoch(current_epoch, Some(5)),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_pending_queues_require_electra() {
+        let electra_fork_version = B32::from([5, 0, 0, 0]);
+        assert!(ensure_electra_state(electra_fork_version, electra_fork_version).is_ok());
+        assert!(matches!(
+            ensure_electra_state(B32::from([4, 0, 0, 0]), electra_fork_version),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
 }
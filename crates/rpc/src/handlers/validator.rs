@@ -1,26 +1,76 @@
-use std::collections::HashSet;
+use std::{
+    collections::{BTreeSet, HashSet},
+    future::ready,
+};
 
 use actix_web::{
     HttpResponse, Responder, get, post,
-    web::{Data, Json, Path, Query},
+    web::{Bytes, Data, Json, Path, Query},
 };
+use alloy_primitives::B256;
+use futures::{Stream, StreamExt, TryStreamExt, stream};
 use ream_beacon_api_types::{
     error::ApiError,
     id::{ID, ValidatorID},
-    query::{IdQuery, StatusQuery},
+    query::{CountQuery, IdQuery, PaginationQuery, StatusQuery},
     request::ValidatorsPostRequest,
-    responses::BeaconResponse,
+    responses::{BeaconResponse, JSON_CONTENT_TYPE, ValidatorCountResponse},
     validator::{ValidatorBalance, ValidatorData, ValidatorStatus},
 };
 use ream_bls::PublicKey;
-use ream_consensus::validator::Validator;
+use ream_consensus::{electra::beacon_state::BeaconState, validator::Validator};
 use ream_storage::db::ReamDB;
 use serde::Serialize;
 
-use super::state::get_state_from_id;
+use super::state::{get_state_from_id, get_state_with_root_from_id};
+use crate::validator_index_cache::ValidatorIndexCache;
 
 const MAX_VALIDATOR_COUNT: usize = 100;
 
+/// Number of validators in a page when only `page` is given.
+const DEFAULT_PAGE_SIZE: u64 = 1000;
+
+/// Largest accepted `page_size`, larger requests are capped to it.
+const MAX_PAGE_SIZE: u64 = 10_000;
+
+/// Resolves a validator index or public key to an index in the validator registry of `state`,
+/// whose root is `state_root`. Public keys are looked up in the indices of the state in
+/// `validator_index_cache`, which are built on the first lookup in the state.
+pub fn resolve_validator_id(
+    validator_index_cache: &ValidatorIndexCache,
+    state_root: B256,
+    state: &BeaconState,
+    validator_id: &ValidatorID,
+) -> Result<u64, ApiError> {
+    let public_key = match validator_id {
+        ValidatorID::Index(index) => {
+            return match state.validators.get(*index as usize) {
+                Some(_) => Ok(*index),
+                None => Err(ApiError::NotFound(format!(
+                    "Validator not found for index: {index}"
+                ))),
+            };
+        }
+        ValidatorID::Address(public_key) => public_key,
+    };
+
+    let validator_indices = validator_index_cache.get_or_build(state_root, state.slot, || {
+        state
+            .validators
+            .iter()
+            .enumerate()
+            .map(|(index, validator)| (validator.public_key.clone(), index as u64))
+            .collect()
+    });
+
+    validator_indices
+        .get(public_key)
+        .copied()
+        .ok_or(ApiError::NotFound(format!(
+            "Validator not found for public_key: {public_key:?}"
+        )))
+}
+
 fn build_validator_balances(
     validators: &[(Validator, u64)],
     filter_ids: Option<&Vec<ValidatorID>>,
@@ -45,160 +95,216 @@ fn build_validator_balances(
         .collect()
 }
 
-#[get("/beacon/states/{state_id}/validator/{validator_id}")]
+/// Builds the response entry for the validator at `index` in `state`. Shared by the single
+/// and bulk validator endpoints so they always report the same status.
+fn get_validator_data(state: &BeaconState, index: usize) -> Result<ValidatorData, ApiError> {
+    let validator = state
+        .validators
+        .get(index)
+        .ok_or(ApiError::NotFound(format!(
+            "Validator not found for index: {index}"
+        )))?;
+    let balance = state.balances.get(index).ok_or(ApiError::NotFound(format!(
+        "Validator not found for index: {index}"
+    )))?;
+    let status = ValidatorStatus::new(validator, *balance, state.get_current_epoch());
+
+    Ok(ValidatorData::new(
+        index as u64,
+        *balance,
+        status,
+        validator.clone(),
+    ))
+}
+
+/// Called using `/eth/v1/beacon/states/{state_id}/validators/{validator_id}`
+#[get("/beacon/states/{state_id}/validators/{validator_id}")]
 pub async fn get_validator_from_state(
     db: Data<ReamDB>,
+    validator_index_cache: Data<ValidatorIndexCache>,
     param: Path<(ID, ValidatorID)>,
 ) -> Result<impl Responder, ApiError> {
     let (state_id, validator_id) = param.into_inner();
-    let state = get_state_from_id(state_id, &db).await?;
-
-    let (index, validator) = {
-        match &validator_id {
-            ValidatorID::Index(i) => match state.validators.get(*i as usize) {
-                Some(validator) => (*i as usize, validator.to_owned()),
-                None => {
-                    return Err(ApiError::NotFound(format!(
-                        "Validator not found for index: {i}"
-                    )));
-                }
-            },
-            ValidatorID::Address(public_key) => {
-                match state
-                    .validators
-                    .iter()
-                    .enumerate()
-                    .find(|(_, validator)| validator.public_key == *public_key)
-                {
-                    Some((i, validator)) => (i, validator.to_owned()),
-                    None => {
-                        return Err(ApiError::NotFound(format!(
-                            "Validator not found for public_key: {public_key:?}"
-                        )))?;
-                    }
-                }
-            }
-        }
-    };
-
-    let balance = state.balances.get(index).ok_or(ApiError::NotFound(format!(
-        "Validator not found for index: {index}"
-    )))?;
-
-    let status = validator_status(&validator, &db).await?;
+    let (state_root, state) = get_state_with_root_from_id(state_id, &db).await?;
+    let index = resolve_validator_id(&validator_index_cache, state_root, &state, &validator_id)?;
 
     Ok(
-        HttpResponse::Ok().json(BeaconResponse::new(ValidatorData::new(
-            index as u64,
-            *balance,
-            status,
-            validator,
-        ))),
+        HttpResponse::Ok().json(BeaconResponse::new(get_validator_data(
+            &state,
+            index as usize,
+        )?)),
     )
 }
 
-pub async fn validator_status(
-    validator: &Validator,
-    db: &ReamDB,
-) -> Result<ValidatorStatus, ApiError> {
-    let highest_slot = db
-        .slot_index_provider()
-        .get_highest_slot()
-        .map_err(|err| {
-            ApiError::InternalError(format!("Failed to get_highest_slot, error: {err:?}"))
-        })?
-        .ok_or(ApiError::NotFound(
-            "Failed to find highest slot".to_string(),
-        ))?;
-    let state = get_state_from_id(ID::Slot(highest_slot), db).await?;
-
-    if validator.exit_epoch < state.get_current_epoch() {
-        Ok(ValidatorStatus::Offline)
-    } else {
-        Ok(ValidatorStatus::ActiveOngoing)
+/// Returns how many validators to skip and how many to return for `pagination_query`, or `None`
+/// if neither `page` nor `page_size` is set.
+fn get_page_bounds(pagination_query: &PaginationQuery) -> Option<(usize, usize)> {
+    if pagination_query.page.is_none() && pagination_query.page_size.is_none() {
+        return None;
+    }
+
+    let page_size = pagination_query
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let skip = pagination_query.page.unwrap_or(0).saturating_mul(page_size);
+
+    Some((
+        usize::try_from(skip).unwrap_or(usize::MAX),
+        page_size as usize,
+    ))
+}
+
+/// Limits `items` to the page given by `page_bounds`. A page past the end is empty.
+fn paginate<S: Stream>(
+    items: S,
+    page_bounds: Option<(usize, usize)>,
+) -> impl Stream<Item = S::Item> {
+    let (skip, take) = page_bounds.unwrap_or((0, usize::MAX));
+    items.skip(skip).take(take)
+}
+
+/// Streams the validators at `indices` as a `BeaconResponse` JSON array, applying
+/// `status_query` to each validator before it is written so large registries are never
+/// buffered in full. Only the page given by `page_bounds` of the matching validators is written.
+/// Umbrella statuses such as `active` in `status_query` select all of their fine-grained members.
+fn stream_validators_response(
+    state: BeaconState,
+    indices: Vec<usize>,
+    status_query: StatusQuery,
+    page_bounds: Option<(usize, usize)>,
+) -> HttpResponse {
+    let validators_data = stream::iter(indices)
+        .map(move |index| get_validator_data(&state, index))
+        .try_filter(move |validator_data| {
+            ready(
+                !status_query.has_status() || status_query.contains_status(&validator_data.status),
+            )
+        });
+
+    HttpResponse::Ok()
+        .content_type(JSON_CONTENT_TYPE)
+        .streaming(stream_beacon_response(paginate(
+            validators_data,
+            page_bounds,
+        )))
+}
+
+/// Counts the validators at `indices` that match `status_query` in a single pass, without
+/// building their response entries. The count is what `stream_validators_response` would write
+/// without pagination.
+fn count_validators(
+    state: &BeaconState,
+    indices: &[usize],
+    status_query: &StatusQuery,
+) -> Result<u64, ApiError> {
+    if !status_query.has_status() {
+        return Ok(indices.len() as u64);
+    }
+
+    let current_epoch = state.get_current_epoch();
+    let mut count = 0;
+    for &index in indices {
+        let (Some(validator), Some(balance)) =
+            (state.validators.get(index), state.balances.get(index))
+        else {
+            return Err(ApiError::NotFound(format!(
+                "Validator not found for index: {index}"
+            )));
+        };
+        if status_query.contains_status(&ValidatorStatus::new(validator, *balance, current_epoch)) {
+            count += 1;
+        }
     }
+
+    Ok(count)
 }
 
+/// Serializes `items` into the JSON body of `BeaconResponse::new(items)` one element at a
+/// time. The body is a valid JSON array even when `items` yields nothing.
+fn stream_beacon_response<T: Serialize>(
+    items: impl Stream<Item = Result<T, ApiError>>,
+) -> impl Stream<Item = Result<Bytes, ApiError>> {
+    // Split an empty response around its `[]` so the envelope always matches `BeaconResponse`
+    let empty_response = serde_json::to_string(&BeaconResponse::new(Vec::<T>::new()))
+        .expect("an empty response always serializes");
+    let (head, tail) = empty_response.split_at(empty_response.rfind(']').unwrap_or_default());
+    let (head, tail) = (Bytes::from(head.to_string()), Bytes::from(tail.to_string()));
+
+    let elements = items.enumerate().map(|(position, item)| {
+        let mut bytes = if position == 0 { vec![] } else { vec![b','] };
+        serde_json::to_writer(&mut bytes, &item?).map_err(|err| {
+            ApiError::InternalError(format!("Failed to serialize response, error: {err:?}"))
+        })?;
+        Ok(Bytes::from(bytes))
+    });
+
+    stream::once(ready(Ok(head)))
+        .chain(elements)
+        .chain(stream::once(ready(Ok(tail))))
+}
+
+/// Called using `/eth/v1/beacon/states/{state_id}/validators`. With `count_only=true` only the
+/// number of validators matching `id` and `status` is returned, ignoring pagination, so a client
+/// that only wants a count does not have to download the registry.
 #[get("/beacon/states/{state_id}/validators")]
 pub async fn get_validators_from_state(
     db: Data<ReamDB>,
+    validator_index_cache: Data<ValidatorIndexCache>,
     state_id: Path<ID>,
     id_query: Query<IdQuery>,
     status_query: Query<StatusQuery>,
+    pagination_query: Query<PaginationQuery>,
+    count_query: Query<CountQuery>,
 ) -> Result<impl Responder, ApiError> {
+    let status_query = status_query.into_inner();
+    let page_bounds = get_page_bounds(&pagination_query);
     if let Some(validator_ids) = &id_query.id {
         if validator_ids.len() >= MAX_VALIDATOR_COUNT {
             return Err(ApiError::TooManyValidatorsIds);
         }
     }
 
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
-    let mut validators_data = Vec::new();
+    let (state_root, state) = get_state_with_root_from_id(state_id.into_inner(), &db).await?;
     let mut validator_indices_to_process = Vec::new();
 
     // First, collect all the validator indices we need to process
     if let Some(validator_ids) = &id_query.id {
         for validator_id in validator_ids {
-            let (index, _) = {
-                match validator_id {
-                    ValidatorID::Index(i) => match state.validators.get(*i as usize) {
-                        Some(validator) => (*i as usize, validator.to_owned()),
-                        None => {
-                            return Err(ApiError::NotFound(format!(
-                                "Validator not found for index: {i}"
-                            )))?;
-                        }
-                    },
-                    ValidatorID::Address(public_key) => {
-                        match state
-                            .validators
-                            .iter()
-                            .enumerate()
-                            .find(|(_, validator)| validator.public_key == *public_key)
-                        {
-                            Some((i, validator)) => (i, validator.to_owned()),
-                            None => {
-                                return Err(ApiError::NotFound(format!(
-                                    "Validator not found for public_key: {public_key:?}"
-                                )))?;
-                            }
-                        }
-                    }
-                }
-            };
-            validator_indices_to_process.push(index);
+            validator_indices_to_process.push(resolve_validator_id(
+                &validator_index_cache,
+                state_root,
+                &state,
+                validator_id,
+            )? as usize);
         }
     } else {
         validator_indices_to_process = (0..state.validators.len()).collect();
     }
 
-    for index in validator_indices_to_process {
-        let validator = &state.validators[index];
-
-        let status = validator_status(validator, &db).await?;
-
-        if status_query.has_status() && !status_query.contains_status(&status) {
-            continue;
-        }
-
-        let balance = state.balances.get(index).ok_or(ApiError::NotFound(format!(
-            "Validator not found for index: {index}"
-        )))?;
+    if count_query.count_only.unwrap_or(false) {
+        let count = count_validators(&state, &validator_indices_to_process, &status_query)?;
+        return Ok(HttpResponse::Ok().json(BeaconResponse::new(ValidatorCountResponse { count })));
+    }
 
-        validators_data.push(ValidatorData::new(
-            index as u64,
-            *balance,
-            status,
-            validator.clone(),
-        ));
+    // Pages are only stable over a fixed ordering, so requested IDs are ordered by index
+    if page_bounds.is_some() {
+        validator_indices_to_process.sort_unstable();
+        validator_indices_to_process.dedup();
     }
 
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(validators_data)))
+    Ok(stream_validators_response(
+        state,
+        validator_indices_to_process,
+        status_query,
+        page_bounds,
+    ))
 }
 
 #[post("/beacon/states/{state_id}/validators")]
 pub async fn post_validators_from_state(
     db: Data<ReamDB>,
+    validator_index_cache: Data<ValidatorIndexCache>,
     state_id: Path<ID>,
     request: Json<ValidatorsPostRequest>,
     _status_query: Json<StatusQuery>,
@@ -206,107 +312,71 @@ pub async fn post_validators_from_state(
     let ValidatorsPostRequest { ids, statuses, .. } = request.into_inner();
     let status_query = StatusQuery { status: statuses };
 
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
-    let mut validators_data = Vec::new();
+    let (state_root, state) = get_state_with_root_from_id(state_id.into_inner(), &db).await?;
     let mut validator_indices_to_process = Vec::new();
 
     // First, collect all the validator indices we need to process
     if let Some(validator_ids) = &ids {
         for validator_id in validator_ids {
-            let (index, _) = {
-                match validator_id {
-                    ValidatorID::Index(i) => match state.validators.get(*i as usize) {
-                        Some(validator) => (*i as usize, validator.to_owned()),
-                        None => {
-                            return Err(ApiError::NotFound(format!(
-                                "Validator not found for index: {i}"
-                            )))?;
-                        }
-                    },
-                    ValidatorID::Address(public_key) => {
-                        match state
-                            .validators
-                            .iter()
-                            .enumerate()
-                            .find(|(_, validator)| validator.public_key == *public_key)
-                        {
-                            Some((i, validator)) => (i, validator.to_owned()),
-                            None => {
-                                return Err(ApiError::NotFound(format!(
-                                    "Validator not found for public_key: {public_key:?}"
-                                )))?;
-                            }
-                        }
-                    }
-                }
-            };
-            validator_indices_to_process.push(index);
+            validator_indices_to_process.push(resolve_validator_id(
+                &validator_index_cache,
+                state_root,
+                &state,
+                validator_id,
+            )? as usize);
         }
     } else {
         validator_indices_to_process = (0..state.validators.len()).collect();
     }
 
-    for index in validator_indices_to_process {
-        let validator = &state.validators[index];
-
-        let status = validator_status(validator, &db).await?;
-
-        if status_query.has_status() && !status_query.contains_status(&status) {
-            continue;
-        }
-
-        let balance = state.balances.get(index).ok_or(ApiError::NotFound(format!(
-            "Validator not found for index: {index}"
-        )))?;
-
-        validators_data.push(ValidatorData::new(
-            index as u64,
-            *balance,
-            status,
-            validator.clone(),
-        ));
-    }
-
-    Ok(HttpResponse::Ok().json(BeaconResponse::new(validators_data)))
+    Ok(stream_validators_response(
+        state,
+        validator_indices_to_process,
+        status_query,
+        None,
+    ))
 }
 
 #[derive(Debug, Serialize)]
 struct ValidatorIdentity {
     #[serde(with = "serde_utils::quoted_u64")]
     index: u64,
+    #[serde(rename = "pubkey")]
     public_key: PublicKey,
     #[serde(with = "serde_utils::quoted_u64")]
     activation_epoch: u64,
 }
 
+/// Called using `/eth/v1/beacon/states/{state_id}/validator_identities` with the indices or
+/// public keys of the validators to look up. IDs of validators that are not in the state, such as
+/// out of range indices, are left out of the response rather than failing the request.
 #[post("/beacon/states/{state_id}/validator_identities")]
 pub async fn post_validator_identities_from_state(
     db: Data<ReamDB>,
+    validator_index_cache: Data<ValidatorIndexCache>,
     state_id: Path<ID>,
     validator_ids: Json<Vec<ValidatorID>>,
 ) -> Result<impl Responder, ApiError> {
-    let state = get_state_from_id(state_id.into_inner(), &db).await?;
-
-    let validator_ids_set: HashSet<ValidatorID> = validator_ids.into_inner().into_iter().collect();
+    let (state_root, state) = get_state_with_root_from_id(state_id.into_inner(), &db).await?;
 
-    let validator_identities: Vec<ValidatorIdentity> = state
-        .validators
+    // Resolve only the requested IDs, ordered by index like the validator registry
+    let validator_indices = validator_ids
         .iter()
-        .enumerate()
-        .filter_map(|(index, validator)| {
-            if validator_ids_set.contains(&ValidatorID::Index(index as u64))
-                || validator_ids_set.contains(&ValidatorID::Address(validator.public_key.clone()))
-            {
-                Some(ValidatorIdentity {
-                    index: index as u64,
-                    public_key: validator.public_key.clone(),
-                    activation_epoch: validator.activation_epoch,
-                })
-            } else {
-                None
+        .filter_map(|validator_id| {
+            resolve_validator_id(&validator_index_cache, state_root, &state, validator_id).ok()
+        })
+        .collect::<BTreeSet<_>>();
+    let validator_identities = validator_indices
+        .into_iter()
+        .map(|index| {
+            let validator = &state.validators[index as usize];
+            ValidatorIdentity {
+                index,
+                public_key: validator.public_key.clone(),
+                activation_epoch: validator.activation_epoch,
             }
         })
-        .collect();
+        .collect::<Vec<_>>();
 
     Ok(HttpResponse::Ok().json(BeaconResponse::new(validator_identities)))
 }
@@ -348,3 +418,89 @@ pub async fn post_validator_balances_from_state(
         ))),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    fn collect_body(body: impl Stream<Item = Result<Bytes, ApiError>>) -> Value {
+        let chunks = block_on(body.try_collect::<Vec<_>>()).unwrap();
+        serde_json::from_slice(&chunks.concat()).unwrap()
+    }
+
+    #[test]
+    fn test_stream_beacon_response_filtered_to_nothing() {
+        let items = stream::iter([Ok(1u64), Ok(2)]).try_filter(|_| ready(false));
+
+        let body = collect_body(stream_beacon_response(items));
+        assert_eq!(body["data"], json!([]));
+        assert_eq!(
+            body,
+            serde_json::to_value(BeaconResponse::new(Vec::<u64>::new())).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_stream_beacon_response_filtered() {
+        let items = stream::iter([Ok(1u64), Ok(2), Ok(3)]).try_filter(|item| ready(item % 2 == 1));
+
+        let body = collect_body(stream_beacon_response(items));
+        assert_eq!(
+            body,
+            serde_json::to_value(BeaconResponse::new(vec![1u64, 3])).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_page_bounds() {
+        assert_eq!(get_page_bounds(&PaginationQuery::default()), None);
+        assert_eq!(
+            get_page_bounds(&PaginationQuery {
+                page: Some(2),
+                page_size: Some(10),
+            }),
+            Some((20, 10))
+        );
+        assert_eq!(
+            get_page_bounds(&PaginationQuery {
+                page: Some(1),
+                page_size: None,
+            }),
+            Some((DEFAULT_PAGE_SIZE as usize, DEFAULT_PAGE_SIZE as usize))
+        );
+    }
+
+    #[test]
+    fn test_page_size_is_capped() {
+        assert_eq!(
+            get_page_bounds(&PaginationQuery {
+                page: None,
+                page_size: Some(MAX_PAGE_SIZE + 1),
+            }),
+            Some((0, MAX_PAGE_SIZE as usize))
+        );
+    }
+
+    #[test]
+    fn test_paginate_boundaries() {
+        let page = |page, page_size| {
+            let page_bounds = get_page_bounds(&PaginationQuery {
+                page: Some(page),
+                page_size: Some(page_size),
+            });
+            collect_body(stream_beacon_response(paginate(
+                stream::iter((0..10u64).map(Ok)),
+                page_bounds,
+            )))
+        };
+
+        assert_eq!(page(0, 4)["data"], json!([0, 1, 2, 3]));
+        assert_eq!(page(1, 4)["data"], json!([4, 5, 6, 7]));
+        // The last page is partial and pages past the end are empty
+        assert_eq!(page(2, 4)["data"], json!([8, 9]));
+        assert_eq!(page(3, 4)["data"], json!([]));
+    }
+}
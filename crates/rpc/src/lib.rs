@@ -1,66 +1,133 @@
-use std::sync::Arc;
+use std::{io, pin::pin, sync::Arc};
 
-use actix_web::{App, HttpServer, dev::ServerHandle, middleware, web::Data};
+use actix_web::{
+    App, HttpServer,
+    middleware::{self, from_fn},
+    web::Data,
+};
 use config::RpcServerConfig;
+use ream_beacon_api_types::events::BeaconEvent;
 use ream_execution_engine::ExecutionEngine;
 use ream_operation_pool::OperationPool;
 use ream_p2p::network_state::NetworkState;
 use ream_storage::db::ReamDB;
-use tracing::info;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
 
-use crate::routes::register_routers;
+use crate::{
+    metrics::{METRICS, record_metrics},
+    proposer_cache::ProposerCache,
+    rate_limit::{RateLimiter, rate_limit},
+    request_id::assign_request_id,
+    routes::register_routers,
+    shutdown::{ShutdownState, reject_during_shutdown},
+    state_cache::StateCache,
+    store::BeaconStore,
+    validator_index_cache::ValidatorIndexCache,
+};
 
 pub mod config;
 pub mod handlers;
+pub mod metrics;
+pub mod proposer_cache;
+pub mod rate_limit;
+pub mod request_body;
+pub mod request_id;
+pub mod retry;
 pub mod routes;
+pub mod shutdown;
+pub mod state_cache;
+pub mod store;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+pub mod validator_index_cache;
 
-/// Start the Beacon API server.
-pub async fn start_server(
+/// Run the Beacon API server until `shutdown` resolves, then shut it down gracefully: no new
+/// connections are accepted and requests on open ones are rejected with 503, in-flight requests
+/// get up to `shutdown_timeout` to complete and pending database writes are flushed. Resolves once
+/// the shutdown has completed.
+pub async fn run_server(
     server_config: RpcServerConfig,
     db: ReamDB,
     network_state: Arc<NetworkState>,
     operation_pool: Arc<OperationPool>,
     execution_engine: Option<ExecutionEngine>,
-) -> std::io::Result<()> {
+    event_sender: broadcast::Sender<BeaconEvent>,
+    shutdown: impl Future<Output = ()>,
+) -> io::Result<()> {
     info!(
         "starting HTTP server on {:?}",
         server_config.http_socket_address
     );
-    // create the stop handle container
-    let stop_handle = Data::new(StopHandle::default());
+    let shutdown_state = Data::new(ShutdownState::default());
+    // Shared by all workers, so a client's quota does not depend on the worker it reaches
+    let rate_limiter = Data::new(RateLimiter::new(server_config.rate_limit.clone()));
+    let beacon_store: Arc<dyn BeaconStore> = Arc::new(db.clone());
+    let state_cache = Data::new(StateCache::default());
+    let proposer_cache = Data::new(ProposerCache::default());
+    let validator_index_cache = Data::new(ValidatorIndexCache::default());
+    METRICS.set_operation_pool(operation_pool.clone());
+    tokio::spawn({
+        let validator_index_cache = validator_index_cache.clone();
+        let finalized = db.finality_notifier.subscribe();
+        async move { validator_index_cache.evict_on_finality(finalized).await }
+    });
 
-    let server = HttpServer::new(move || {
-        let stop_handle = stop_handle.clone();
-        App::new()
-            .wrap(middleware::Logger::default())
-            .app_data(stop_handle)
-            .app_data(Data::new(db.clone()))
-            .app_data(Data::new(network_state.clone()))
-            .app_data(Data::new(operation_pool.clone()))
-            .app_data(Data::new(execution_engine.clone()))
-            .configure(register_routers)
+    let server = HttpServer::new({
+        let shutdown_state = shutdown_state.clone();
+        let db = db.clone();
+        move || {
+            App::new()
+                .wrap(from_fn(reject_during_shutdown))
+                .wrap(from_fn(rate_limit))
+                .wrap(from_fn(record_metrics))
+                .wrap(middleware::Logger::default())
+                .wrap(from_fn(assign_request_id))
+                .app_data(shutdown_state.clone())
+                .app_data(rate_limiter.clone())
+                .app_data(Data::new(db.clone()))
+                .app_data(Data::new(beacon_store.clone()))
+                .app_data(state_cache.clone())
+                .app_data(proposer_cache.clone())
+                .app_data(validator_index_cache.clone())
+                .app_data(Data::new(network_state.clone()))
+                .app_data(Data::new(operation_pool.clone()))
+                .app_data(Data::new(execution_engine.clone()))
+                .app_data(Data::new(event_sender.clone()))
+                .configure(register_routers)
+        }
     })
+    .shutdown_timeout(server_config.shutdown_timeout.as_secs())
+    // The node decides when to shut down, through `shutdown`
+    .disable_signals()
     .bind(server_config.http_socket_address)?
     .run();
+    let server_handle = server.handle();
+    let mut server = pin!(server);
 
-    server.await
-}
-
-#[derive(Default)]
-struct StopHandle {
-    inner: parking_lot::Mutex<Option<ServerHandle>>,
-}
-
-#[allow(dead_code)]
-impl StopHandle {
-    /// Sets the server handle to stop.
-    pub(crate) fn register(&self, handle: ServerHandle) {
-        *self.inner.lock() = Some(handle);
+    tokio::select! {
+        result = &mut server => return result,
+        () = shutdown => {}
     }
 
-    /// Sends stop signal through contained server handle.
-    pub(crate) fn stop(&self, graceful: bool) {
-        #[allow(clippy::let_underscore_future)]
-        let _ = self.inner.lock().as_ref().unwrap().stop(graceful);
+    info!("shutting down HTTP server");
+    shutdown_state.begin();
+    let (drained, (), result) = tokio::join!(
+        shutdown_state.wait_for_drain(server_config.shutdown_timeout),
+        server_handle.stop(true),
+        server,
+    );
+    if !drained {
+        warn!(
+            "{} requests were still in flight after {:?}",
+            shutdown_state.in_flight(),
+            server_config.shutdown_timeout
+        );
     }
+    result?;
+
+    db.flush().map_err(io::Error::other)?;
+    info!("HTTP server shut down");
+
+    Ok(())
 }
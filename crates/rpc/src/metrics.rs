@@ -0,0 +1,260 @@
+use std::{
+    sync::{Arc, LazyLock},
+    time::Instant,
+};
+
+use actix_web::{
+    Error, HttpResponse, Responder,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    get,
+    http::header::CONTENT_TYPE,
+    middleware::Next,
+};
+use parking_lot::RwLock;
+use prometheus_client::{
+    collector::Collector,
+    encoding::{DescriptorEncoder, EncodeLabelSet, EncodeMetric, text::encode},
+    metrics::{
+        MetricType,
+        counter::{ConstCounter, Counter},
+        family::Family,
+        histogram::{Histogram, exponential_buckets},
+    },
+    registry::Registry,
+};
+use ream_beacon_api_types::error::ApiError;
+use ream_operation_pool::OperationPool;
+
+pub const METRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Label of requests that matched no route, so unknown paths do not each get their own series.
+const UNMATCHED_ENDPOINT: &str = "unmatched";
+
+pub static METRICS: LazyLock<RpcMetrics> = LazyLock::new(RpcMetrics::new);
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct RequestLabels {
+    /// Route pattern, e.g. `/eth/v2/beacon/blocks/{block_id}`.
+    pub endpoint: String,
+    pub method: String,
+    pub status: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct DbReadLabels {
+    pub provider: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct RejectionLabels {
+    pub reason: String,
+}
+
+type HistogramFamily<L> = Family<L, Histogram, fn() -> Histogram>;
+
+/// Exports the rejection counts of the operation pool set with
+/// [`RpcMetrics::set_operation_pool`], read from the pool on every scrape.
+#[derive(Clone, Debug, Default)]
+struct OperationPoolCollector {
+    operation_pool: Arc<RwLock<Option<Arc<OperationPool>>>>,
+}
+
+impl Collector for OperationPoolCollector {
+    fn encode(&self, mut encoder: DescriptorEncoder) -> Result<(), std::fmt::Error> {
+        let Some(operation_pool) = self.operation_pool.read().clone() else {
+            return Ok(());
+        };
+
+        let rejection_stats = operation_pool.rejection_stats();
+        let mut metric_encoder = encoder.encode_descriptor(
+            "operation_pool_rejections",
+            "Number of operations rejected before entering the operation pool",
+            None,
+            MetricType::Counter,
+        )?;
+        for (reason, count) in [
+            ("invalid_signature", rejection_stats.invalid_signature),
+            ("stale_voluntary_exit", rejection_stats.stale_voluntary_exit),
+            ("invalid_operation", rejection_stats.invalid_operation),
+        ] {
+            let labels = RejectionLabels {
+                reason: reason.to_string(),
+            };
+            ConstCounter::new(count).encode(metric_encoder.encode_family(&labels)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// 16 buckets from 100µs up to 3.3s, doubling each time.
+fn duration_histogram() -> Histogram {
+    Histogram::new(exponential_buckets(0.0001, 2.0, 16))
+}
+
+/// Beacon API request and storage read metrics, exposed at `/metrics`.
+#[derive(Debug)]
+pub struct RpcMetrics {
+    registry: Registry,
+    requests: Family<RequestLabels, Counter>,
+    request_duration: HistogramFamily<RequestLabels>,
+    db_read_duration: HistogramFamily<DbReadLabels>,
+    operation_pool: OperationPoolCollector,
+}
+
+impl RpcMetrics {
+    fn new() -> Self {
+        let requests = Family::<RequestLabels, Counter>::default();
+        let request_duration: HistogramFamily<RequestLabels> =
+            Family::new_with_constructor(duration_histogram);
+        let db_read_duration: HistogramFamily<DbReadLabels> =
+            Family::new_with_constructor(duration_histogram);
+
+        let mut registry = Registry::default();
+        registry.register(
+            "rpc_requests",
+            "Number of Beacon API requests",
+            requests.clone(),
+        );
+        registry.register(
+            "rpc_request_duration_seconds",
+            "Time taken to answer Beacon API requests",
+            request_duration.clone(),
+        );
+        registry.register(
+            "rpc_db_read_duration_seconds",
+            "Time taken by storage reads of the Beacon API handlers",
+            db_read_duration.clone(),
+        );
+        let operation_pool = OperationPoolCollector::default();
+        registry.register_collector(Box::new(operation_pool.clone()));
+
+        Self {
+            registry,
+            requests,
+            request_duration,
+            db_read_duration,
+            operation_pool,
+        }
+    }
+
+    /// Exports the rejection counts of `operation_pool`, replacing any pool set before.
+    pub fn set_operation_pool(&self, operation_pool: Arc<OperationPool>) {
+        *self.operation_pool.operation_pool.write() = Some(operation_pool);
+    }
+
+    pub fn observe_request(&self, labels: &RequestLabels, seconds: f64) {
+        self.requests.get_or_create(labels).inc();
+        self.request_duration.get_or_create(labels).observe(seconds);
+    }
+
+    pub fn observe_db_read(&self, provider: &str, seconds: f64) {
+        self.db_read_duration
+            .get_or_create(&DbReadLabels {
+                provider: provider.to_string(),
+            })
+            .observe(seconds);
+    }
+
+    /// The registered metrics in the OpenMetrics text format.
+    pub fn encode(&self) -> Result<String, std::fmt::Error> {
+        let mut buffer = String::new();
+        encode(&mut buffer, &self.registry)?;
+        Ok(buffer)
+    }
+}
+
+/// Runs `read` and records how long it took under `provider`.
+pub fn time_db_read<T>(provider: &str, read: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = read();
+    METRICS.observe_db_read(provider, start.elapsed().as_secs_f64());
+    result
+}
+
+/// Counts every request and records how long it took, labelled with the matched route pattern so
+/// new endpoints are instrumented without changes to their handlers.
+pub async fn record_metrics(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let start = Instant::now();
+    let endpoint = request
+        .match_pattern()
+        .unwrap_or_else(|| UNMATCHED_ENDPOINT.to_string());
+    let method = request.method().to_string();
+
+    let response = next.call(request).await?;
+    METRICS.observe_request(
+        &RequestLabels {
+            endpoint,
+            method,
+            status: response.status().as_u16().to_string(),
+        },
+        start.elapsed().as_secs_f64(),
+    );
+
+    Ok(response)
+}
+
+/// Called by `/metrics` to scrape the Beacon API metrics.
+#[get("/metrics")]
+pub async fn get_metrics() -> Result<impl Responder, ApiError> {
+    let metrics = METRICS
+        .encode()
+        .map_err(|err| ApiError::InternalError(format!("Failed to encode metrics: {err}")))?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((CONTENT_TYPE, METRICS_CONTENT_TYPE))
+        .body(metrics))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, http::StatusCode, middleware::from_fn, test, web};
+
+    use super::*;
+
+    async fn ok() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[actix_web::test]
+    async fn test_requests_are_counted() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(record_metrics))
+                .route("/counted/{id}", web::get().to(ok))
+                .service(get_metrics),
+        )
+        .await;
+
+        for id in 0..3 {
+            let request = test::TestRequest::get()
+                .uri(&format!("/counted/{id}"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        time_db_read("counted_provider", || ());
+
+        let request = test::TestRequest::get().uri("/metrics").to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            METRICS_CONTENT_TYPE
+        );
+
+        let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+        assert!(body.contains(
+            r#"rpc_requests_total{endpoint="/counted/{id}",method="GET",status="200"} 3"#
+        ));
+        assert!(body.contains(
+            r#"rpc_request_duration_seconds_count{endpoint="/counted/{id}",method="GET",status="200"} 3"#
+        ));
+        assert!(
+            body.contains(r#"rpc_db_read_duration_seconds_count{provider="counted_provider"} 1"#)
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+
+use alloy_primitives::B256;
+use parking_lot::Mutex;
+use ream_consensus::constants::SLOTS_PER_EPOCH;
+
+/// Number of epochs kept by a default [`ProposerCache`]. Validators poll the current and the next
+/// epoch, so a few entries are enough.
+pub const PROPOSER_CACHE_CAPACITY: usize = 4;
+
+/// The proposer index of every slot of an epoch, first slot first.
+pub type EpochProposers = [u64; SLOTS_PER_EPOCH as usize];
+
+/// Proposers of recent epochs keyed by epoch and dependent root, so polling the proposer duties
+/// does not recompute the seed and shuffling for every slot on each request. The proposers of an
+/// epoch only change when its dependent root does, in which case the entry is replaced.
+#[derive(Debug)]
+pub struct ProposerCache {
+    capacity: usize,
+    /// Entries oldest first, the oldest is evicted once `capacity` is reached.
+    entries: Mutex<VecDeque<(u64, B256, EpochProposers)>>,
+}
+
+impl Default for ProposerCache {
+    fn default() -> Self {
+        Self::new(PROPOSER_CACHE_CAPACITY)
+    }
+}
+
+impl ProposerCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Returns the proposers of `epoch` on the chain with `dependent_root`, calling `compute` if
+    /// they are not cached. An entry for `epoch` with another dependent root, left behind by a
+    /// reorg across the epoch boundary, is replaced. A failed computation is not cached.
+    pub fn get_or_compute<E>(
+        &self,
+        epoch: u64,
+        dependent_root: B256,
+        compute: impl FnOnce() -> Result<EpochProposers, E>,
+    ) -> Result<EpochProposers, E> {
+        if let Some((_, _, proposers)) = self
+            .entries
+            .lock()
+            .iter()
+            .find(|(cached_epoch, root, _)| *cached_epoch == epoch && *root == dependent_root)
+        {
+            return Ok(*proposers);
+        }
+
+        // Computed without holding the lock, requests for cached epochs are not held up
+        let proposers = compute()?;
+
+        let mut entries = self.entries.lock();
+        entries.retain(|(cached_epoch, _, _)| *cached_epoch != epoch);
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back((epoch, dependent_root, proposers));
+
+        Ok(proposers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, convert::Infallible};
+
+    use super::*;
+
+    fn proposers(index: u64) -> EpochProposers {
+        [index; SLOTS_PER_EPOCH as usize]
+    }
+
+    #[test]
+    fn test_same_dependent_root_hits_cache() {
+        let cache = ProposerCache::default();
+        let computations = Cell::new(0);
+        let compute = |index| {
+            computations.set(computations.get() + 1);
+            Ok::<_, Infallible>(proposers(index))
+        };
+
+        let root = B256::repeat_byte(1);
+        assert_eq!(
+            cache.get_or_compute(5, root, || compute(1)),
+            Ok(proposers(1))
+        );
+        assert_eq!(
+            cache.get_or_compute(5, root, || compute(2)),
+            Ok(proposers(1))
+        );
+        assert_eq!(computations.get(), 1);
+
+        // Another epoch with the same dependent root has its own proposers
+        assert_eq!(
+            cache.get_or_compute(6, root, || compute(3)),
+            Ok(proposers(3))
+        );
+        assert_eq!(computations.get(), 2);
+    }
+
+    #[test]
+    fn test_changed_dependent_root_busts_cache() {
+        let cache = ProposerCache::default();
+        let computations = Cell::new(0);
+        let compute = |index| {
+            computations.set(computations.get() + 1);
+            Ok::<_, Infallible>(proposers(index))
+        };
+
+        let old_root = B256::repeat_byte(1);
+        let new_root = B256::repeat_byte(2);
+        assert_eq!(
+            cache.get_or_compute(5, old_root, || compute(1)),
+            Ok(proposers(1))
+        );
+        assert_eq!(
+            cache.get_or_compute(5, new_root, || compute(2)),
+            Ok(proposers(2))
+        );
+        assert_eq!(
+            cache.get_or_compute(5, new_root, || compute(3)),
+            Ok(proposers(2))
+        );
+        assert_eq!(computations.get(), 2);
+
+        // The entry of the old root was replaced, not kept next to the new one
+        assert_eq!(
+            cache.get_or_compute(5, old_root, || compute(4)),
+            Ok(proposers(4))
+        );
+        assert_eq!(computations.get(), 3);
+    }
+
+    #[test]
+    fn test_failed_computation_is_not_cached() {
+        let cache = ProposerCache::default();
+        let root = B256::repeat_byte(1);
+
+        assert_eq!(
+            cache.get_or_compute(5, root, || Err("failed")),
+            Err("failed")
+        );
+        assert_eq!(
+            cache.get_or_compute(5, root, || Ok::<_, &str>(proposers(1))),
+            Ok(proposers(1))
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, Ipv4Addr},
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    Error, HttpResponse,
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::RETRY_AFTER,
+    middleware::Next,
+    web::Data,
+};
+use parking_lot::Mutex;
+
+/// Number of client buckets tracked. Past it full buckets, which are indistinguishable from new
+/// ones, are dropped, and if none is full the bucket updated longest ago is evicted.
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+/// Token bucket parameters of a rate limited endpoint. A client may make `burst` requests at once,
+/// after which it regains one request every `refill_period`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    pub burst: u32,
+    pub refill_period: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Quotas keyed by route pattern, e.g. `/eth/v1/beacon/blocks/{block_id}/rewards`. Routes
+    /// without a quota are not rate limited.
+    pub quotas: HashMap<String, Quota>,
+    /// Clients that are never rate limited.
+    pub allowlist: HashSet<IpAddr>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        let quota = Quota {
+            burst: 10,
+            refill_period: Duration::from_millis(500),
+        };
+        let quotas = [
+            "/eth/v1/beacon/blocks/{block_id}/rewards",
+            "/eth/v1/beacon/rewards/attestations/{epoch}",
+            "/eth/v1/beacon/rewards/sync_committee/{block_id}",
+            "/eth/v1/beacon/states/{state_id}/validators",
+            "/eth/v1/debug/beacon/heads",
+            "/eth/v2/debug/beacon/heads",
+            "/eth/v2/debug/beacon/states/{state_id}",
+        ]
+        .into_iter()
+        .map(|pattern| (pattern.to_string(), quota))
+        .collect();
+
+        Self {
+            quotas,
+            allowlist: HashSet::from([IpAddr::V4(Ipv4Addr::LOCALHOST)]),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Per client and endpoint token buckets enforcing a [`RateLimitConfig`].
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<(String, IpAddr), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::default(),
+        }
+    }
+
+    /// Takes a token from the bucket of `client` for `pattern` at `now`. Returns how long the
+    /// client has to wait for its next token if the bucket is empty.
+    pub fn check(&self, pattern: &str, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let Some(quota) = self.config.quotas.get(pattern) else {
+            return Ok(());
+        };
+        if self.config.allowlist.contains(&client) {
+            return Ok(());
+        }
+
+        let key = (pattern.to_string(), client);
+        let mut buckets = self.buckets.lock();
+        if buckets.len() >= MAX_TRACKED_BUCKETS && !buckets.contains_key(&key) {
+            buckets.retain(|(pattern, _), bucket| {
+                self.config
+                    .quotas
+                    .get(pattern)
+                    .is_some_and(|quota| tokens_at(bucket, quota, now) < f64::from(quota.burst))
+            });
+            if buckets.len() >= MAX_TRACKED_BUCKETS {
+                let oldest = buckets
+                    .iter()
+                    .min_by_key(|(_, bucket)| bucket.updated_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    buckets.remove(&oldest);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(key).or_insert_with(|| Bucket {
+            tokens: f64::from(quota.burst),
+            updated_at: now,
+        });
+        refill(bucket, quota, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(quota.refill_period.mul_f64(1.0 - bucket.tokens))
+        }
+    }
+}
+
+/// Returns the tokens `bucket` holds at `now`, without updating it.
+fn tokens_at(bucket: &Bucket, quota: &Quota, now: Instant) -> f64 {
+    let elapsed = now.saturating_duration_since(bucket.updated_at);
+    let refilled = elapsed.as_secs_f64() / quota.refill_period.as_secs_f64();
+    (bucket.tokens + refilled).min(f64::from(quota.burst))
+}
+
+fn refill(bucket: &mut Bucket, quota: &Quota, now: Instant) {
+    bucket.tokens = tokens_at(bucket, quota, now);
+    bucket.updated_at = now;
+}
+
+/// Rejects requests with 429 and a `Retry-After` header once the client has used up its quota for
+/// the matched route. Requires a `Data<RateLimiter>` in the app data, without one nothing is
+/// limited.
+pub async fn rate_limit(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let limited = match (
+        request.app_data::<Data<RateLimiter>>(),
+        request.match_pattern(),
+    ) {
+        (Some(rate_limiter), Some(pattern)) => {
+            let client = request
+                .peer_addr()
+                .map(|address| address.ip())
+                .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+            rate_limiter.check(&pattern, client, Instant::now()).err()
+        }
+        _ => None,
+    };
+
+    match limited {
+        Some(retry_after) => {
+            let response = HttpResponse::TooManyRequests()
+                .insert_header((
+                    RETRY_AFTER,
+                    retry_after.as_secs_f64().ceil().max(1.0) as u64,
+                ))
+                .finish();
+            Ok(request.into_response(response).map_into_right_body())
+        }
+        None => Ok(next.call(request).await?.map_into_left_body()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use actix_web::{App, http::StatusCode, middleware::from_fn, test, web};
+
+    use super::*;
+
+    const PATTERN: &str = "/limited";
+
+    async fn ok() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    fn rate_limiter(burst: u32) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            quotas: HashMap::from([(
+                PATTERN.to_string(),
+                Quota {
+                    burst,
+                    refill_period: Duration::from_secs(1),
+                },
+            )]),
+            allowlist: HashSet::from([IpAddr::V4(Ipv4Addr::LOCALHOST)]),
+        })
+    }
+
+    #[actix_web::test]
+    async fn test_burst_beyond_quota_is_rejected() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(rate_limit))
+                .app_data(Data::new(rate_limiter(2)))
+                .route(PATTERN, web::get().to(ok))
+                .route("/unlimited", web::get().to(ok)),
+        )
+        .await;
+        let request = |path: &str, client: [u8; 4]| {
+            test::TestRequest::get()
+                .uri(path)
+                .peer_addr(SocketAddr::from((client, 9000)))
+                .to_request()
+        };
+
+        for _ in 0..2 {
+            let response = test::call_service(&app, request(PATTERN, [10, 0, 0, 1])).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = test::call_service(&app, request(PATTERN, [10, 0, 0, 1])).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "1");
+
+        // Other clients, allowlisted clients and other routes have their own limits
+        let response = test::call_service(&app, request(PATTERN, [10, 0, 0, 2])).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        for _ in 0..3 {
+            let response = test::call_service(&app, request(PATTERN, [127, 0, 0, 1])).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let response = test::call_service(&app, request("/unlimited", [10, 0, 0, 1])).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let rate_limiter = rate_limiter(2);
+        let client = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let start = Instant::now();
+
+        assert!(rate_limiter.check(PATTERN, client, start).is_ok());
+        assert!(rate_limiter.check(PATTERN, client, start).is_ok());
+        assert_eq!(
+            rate_limiter.check(PATTERN, client, start),
+            Err(Duration::from_secs(1))
+        );
+
+        // Half a token is not enough
+        let half_refilled = start + Duration::from_millis(500);
+        assert_eq!(
+            rate_limiter.check(PATTERN, client, half_refilled),
+            Err(Duration::from_millis(500))
+        );
+
+        let refilled = start + Duration::from_secs(1);
+        assert!(rate_limiter.check(PATTERN, client, refilled).is_ok());
+        assert!(rate_limiter.check(PATTERN, client, refilled).is_err());
+
+        // The bucket never holds more than its burst
+        let much_later = start + Duration::from_secs(60);
+        assert!(rate_limiter.check(PATTERN, client, much_later).is_ok());
+        assert!(rate_limiter.check(PATTERN, client, much_later).is_ok());
+        assert!(rate_limiter.check(PATTERN, client, much_later).is_err());
+    }
+
+    #[test]
+    fn test_tracked_buckets_are_capped() {
+        let rate_limiter = rate_limiter(2);
+        let client = |index: usize| IpAddr::V4(Ipv4Addr::from(0x0a00_0000 + index as u32));
+        let start = Instant::now();
+
+        // Every bucket is mid-burst, so none can be dropped as full
+        assert!(rate_limiter.check(PATTERN, client(0), start).is_ok());
+        let later = start + Duration::from_millis(1);
+        for index in 1..MAX_TRACKED_BUCKETS {
+            assert!(rate_limiter.check(PATTERN, client(index), later).is_ok());
+        }
+        assert_eq!(rate_limiter.buckets.lock().len(), MAX_TRACKED_BUCKETS);
+
+        // A new client evicts the bucket updated longest ago
+        let new_client = client(MAX_TRACKED_BUCKETS);
+        assert!(rate_limiter.check(PATTERN, new_client, later).is_ok());
+        let buckets = rate_limiter.buckets.lock();
+        assert_eq!(buckets.len(), MAX_TRACKED_BUCKETS);
+        assert!(!buckets.contains_key(&(PATTERN.to_string(), client(0))));
+        assert!(buckets.contains_key(&(PATTERN.to_string(), client(1))));
+        assert!(buckets.contains_key(&(PATTERN.to_string(), new_client)));
+    }
+}
@@ -0,0 +1,319 @@
+use std::io::Read;
+
+use actix_web::{
+    FromRequest, HttpMessage, HttpRequest,
+    dev::Payload,
+    http::header::CONTENT_ENCODING,
+    web::{Bytes, BytesMut},
+};
+use futures::{StreamExt, future::LocalBoxFuture};
+use ream_beacon_api_types::{
+    error::ApiError,
+    responses::{JSON_CONTENT_TYPE, SSZ_CONTENT_TYPE},
+};
+use ream_p2p::constants::MAX_PAYLOAD_SIZE;
+use serde::de::DeserializeOwned;
+use snap::read::FrameDecoder;
+use ssz::Decode;
+
+/// `Content-Encoding` of a snappy framed request body.
+pub const SNAPPY_CONTENT_ENCODING: &str = "snappy";
+
+/// Limits of the bodies read by [`SszOrJson`], found in the app data. Without one the default
+/// limits apply.
+#[derive(Debug, Clone)]
+pub struct RequestBodyConfig {
+    limit: usize,
+}
+
+impl Default for RequestBodyConfig {
+    fn default() -> Self {
+        Self {
+            limit: MAX_PAYLOAD_SIZE as usize,
+        }
+    }
+}
+
+impl RequestBodyConfig {
+    /// Maximum size of a body in bytes, after it is decompressed. Defaults to the largest payload
+    /// accepted over gossip.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+}
+
+/// Extracts a `T` from the request body, SSZ encoded if the request is sent as
+/// `application/octet-stream` and JSON otherwise. Either can be snappy framed, which is indicated
+/// by `Content-Encoding: snappy`. Bodies larger than the `RequestBodyConfig` limit are rejected
+/// with 413, a compressed body is never decompressed past the limit.
+#[derive(Debug)]
+pub struct SszOrJson<T>(pub T);
+
+impl<T> SszOrJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Decode + DeserializeOwned + 'static> FromRequest for SszOrJson<T> {
+    type Error = ApiError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(request: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let limit = request
+            .app_data::<RequestBodyConfig>()
+            .cloned()
+            .unwrap_or_default()
+            .limit;
+        let content_type = request.content_type().to_string();
+        let content_encoding = request
+            .headers()
+            .get(CONTENT_ENCODING)
+            .map(|value| value.to_str().unwrap_or_default().to_string());
+        let mut payload = payload.take();
+
+        Box::pin(async move {
+            let is_ssz = match content_type.as_str() {
+                SSZ_CONTENT_TYPE => true,
+                // A body without a content type is taken to be JSON
+                JSON_CONTENT_TYPE | "" => false,
+                content_type => {
+                    return Err(ApiError::BadRequest(format!(
+                        "Unsupported content type: {content_type}"
+                    )));
+                }
+            };
+            let is_snappy = match content_encoding.as_deref() {
+                Some(SNAPPY_CONTENT_ENCODING) => true,
+                None | Some("identity") => false,
+                Some(content_encoding) => {
+                    return Err(ApiError::BadRequest(format!(
+                        "Unsupported content encoding: {content_encoding}"
+                    )));
+                }
+            };
+
+            let mut body = BytesMut::new();
+            while let Some(chunk) = payload.next().await {
+                let chunk = chunk.map_err(|err| {
+                    ApiError::BadRequest(format!("Failed to read request body: {err}"))
+                })?;
+                if body.len() + chunk.len() > limit {
+                    return Err(body_too_large(limit));
+                }
+                body.extend_from_slice(&chunk);
+            }
+            let body = match is_snappy {
+                true => decompress(&body, limit)?,
+                false => body.freeze(),
+            };
+
+            let value = match is_ssz {
+                true => T::from_ssz_bytes(&body).map_err(|err| {
+                    ApiError::BadRequest(format!("Failed to decode SSZ body: {err:?}"))
+                })?,
+                false => serde_json::from_slice(&body).map_err(|err| {
+                    ApiError::BadRequest(format!("Failed to decode JSON body: {err}"))
+                })?,
+            };
+
+            Ok(SszOrJson(value))
+        })
+    }
+}
+
+/// Decode the snappy frames of `body`, failing as soon as more than `limit` bytes come out.
+fn decompress(body: &[u8], limit: usize) -> Result<Bytes, ApiError> {
+    let mut decompressed = Vec::new();
+    FrameDecoder::new(body)
+        .take(limit as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|err| ApiError::BadRequest(format!("Invalid snappy framed body: {err}")))?;
+    if decompressed.len() > limit {
+        return Err(body_too_large(limit));
+    }
+
+    Ok(decompressed.into())
+}
+
+fn body_too_large(limit: usize) -> ApiError {
+    ApiError::PayloadTooLarge(format!("Request body exceeds {limit} bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, sync::Arc};
+
+    use actix_web::{
+        App, HttpResponse,
+        http::StatusCode,
+        test,
+        web::{self, Data},
+    };
+    use alloy_primitives::B256;
+    use ream_beacon_api_types::error::ErrorResponse;
+    use ream_bls::BLSSignature;
+    use ream_consensus::{
+        attestation::Attestation, attestation_data::AttestationData, checkpoint::Checkpoint,
+    };
+    use ream_operation_pool::OperationPool;
+    use snap::write::FrameEncoder;
+    use ssz::Encode;
+    use ssz_types::{BitList, BitVector};
+
+    use super::*;
+
+    async fn pool_attestations(
+        operation_pool: Data<Arc<OperationPool>>,
+        attestations: SszOrJson<Vec<Attestation>>,
+    ) -> HttpResponse {
+        for attestation in attestations.into_inner() {
+            operation_pool.insert_attestation(attestation);
+        }
+        HttpResponse::Ok().finish()
+    }
+
+    fn attestation() -> Attestation {
+        let mut aggregation_bits = BitList::with_capacity(8).unwrap();
+        aggregation_bits.set(3, true).unwrap();
+        let mut committee_bits = BitVector::new();
+        committee_bits.set(1, true).unwrap();
+        Attestation {
+            aggregation_bits,
+            data: AttestationData {
+                slot: 7,
+                index: 0,
+                beacon_block_root: B256::repeat_byte(1),
+                source: Checkpoint::default(),
+                target: Checkpoint {
+                    epoch: 0,
+                    root: B256::repeat_byte(2),
+                },
+            },
+            signature: BLSSignature::infinity(),
+            committee_bits,
+        }
+    }
+
+    fn snappy_frame(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = FrameEncoder::new(Vec::new());
+        encoder.write_all(bytes).unwrap();
+        encoder.into_inner().unwrap()
+    }
+
+    /// Submits `request` to a fresh pool and returns the pool's attestations.
+    async fn submit(config: RequestBodyConfig, request: test::TestRequest) -> Vec<Attestation> {
+        let operation_pool = Arc::new(OperationPool::default());
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(operation_pool.clone()))
+                .app_data(config)
+                .route("/attestations", web::post().to(pool_attestations)),
+        )
+        .await;
+        let response = test::call_service(&app, request.uri("/attestations").to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        operation_pool.get_attestations()
+    }
+
+    async fn submit_error(config: RequestBodyConfig, request: test::TestRequest) -> ErrorResponse {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(Arc::new(OperationPool::default())))
+                .app_data(config)
+                .route("/attestations", web::post().to(pool_attestations)),
+        )
+        .await;
+        let response = test::call_service(&app, request.uri("/attestations").to_request()).await;
+        test::read_body_json(response).await
+    }
+
+    #[actix_web::test]
+    async fn test_json_ssz_and_snappy_bodies_give_the_same_pool() {
+        let attestations = vec![attestation()];
+        let ssz_body = attestations.as_ssz_bytes();
+
+        let from_json = submit(
+            RequestBodyConfig::default(),
+            test::TestRequest::post().set_json(&attestations),
+        )
+        .await;
+        let from_ssz = submit(
+            RequestBodyConfig::default(),
+            test::TestRequest::post()
+                .insert_header(("content-type", SSZ_CONTENT_TYPE))
+                .set_payload(ssz_body.clone()),
+        )
+        .await;
+        let from_snappy_ssz = submit(
+            RequestBodyConfig::default(),
+            test::TestRequest::post()
+                .insert_header(("content-type", SSZ_CONTENT_TYPE))
+                .insert_header((CONTENT_ENCODING, SNAPPY_CONTENT_ENCODING))
+                .set_payload(snappy_frame(&ssz_body)),
+        )
+        .await;
+
+        assert_eq!(from_json, attestations);
+        assert_eq!(from_ssz, attestations);
+        assert_eq!(from_snappy_ssz, attestations);
+    }
+
+    #[actix_web::test]
+    async fn test_body_over_limit_is_rejected() {
+        let ssz_body = vec![attestation()].as_ssz_bytes();
+        let config = RequestBodyConfig::default().limit(ssz_body.len() - 1);
+
+        let error = submit_error(
+            config,
+            test::TestRequest::post()
+                .insert_header(("content-type", SSZ_CONTENT_TYPE))
+                .set_payload(ssz_body),
+        )
+        .await;
+        assert_eq!(error.code, 413);
+    }
+
+    #[actix_web::test]
+    async fn test_decompression_stops_at_limit() {
+        // Zeros compress well, the compressed body is well below the limit
+        let bomb = snappy_frame(&vec![0; 1024 * 1024]);
+        let config = RequestBodyConfig::default().limit(64 * 1024);
+        assert!(bomb.len() < 64 * 1024);
+
+        let error = submit_error(
+            config,
+            test::TestRequest::post()
+                .insert_header(("content-type", SSZ_CONTENT_TYPE))
+                .insert_header((CONTENT_ENCODING, SNAPPY_CONTENT_ENCODING))
+                .set_payload(bomb),
+        )
+        .await;
+        assert_eq!(error.code, 413);
+        assert_eq!(
+            error.message,
+            format!(
+                "Payload Too Large: Request body exceeds {} bytes",
+                64 * 1024
+            )
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_unsupported_content_type_is_rejected() {
+        let error = submit_error(
+            RequestBodyConfig::default(),
+            test::TestRequest::post()
+                .insert_header(("content-type", "text/plain"))
+                .set_payload("[]"),
+        )
+        .await;
+        assert_eq!(error.code, 400);
+        assert_eq!(
+            error.message,
+            "Bad Request: Unsupported content type: text/plain"
+        );
+    }
+}
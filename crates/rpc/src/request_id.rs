@@ -0,0 +1,172 @@
+use std::{fmt, time::Instant};
+
+use actix_web::{
+    Error, HttpMessage,
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{HeaderName, HeaderValue},
+    middleware::Next,
+};
+use ream_beacon_api_types::error::ApiError;
+use tracing::{Instrument, info, info_span, warn};
+use uuid::Uuid;
+
+/// Response header carrying the id of the request.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Id assigned to every Beacon API request, available to handlers as a request extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestId(pub Uuid);
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Assigns a `RequestId` to every request and handles it in a `rpc_request` span carrying the
+/// id, method and path, logging the status and latency once it is answered. Failed requests get
+/// the id in their `ApiError` body as well, so a client report can be matched with the logs.
+pub async fn assign_request_id(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let start = Instant::now();
+    let request_id = RequestId(Uuid::new_v4());
+    request.extensions_mut().insert(request_id);
+    let span = info_span!(
+        "rpc_request",
+        %request_id,
+        method = %request.method(),
+        path = %request.path(),
+    );
+    let http_request = request.request().clone();
+
+    let response = match next.call(request).instrument(span.clone()).await {
+        Ok(response) => response.map_into_left_body(),
+        Err(err) => ServiceResponse::from_err(err, http_request).map_into_right_body(),
+    };
+
+    let status = response.status().as_u16();
+    let latency_ms = start.elapsed().as_millis() as u64;
+    let error_response = match response
+        .response()
+        .error()
+        .and_then(|err| err.as_error::<ApiError>())
+    {
+        Some(err) => {
+            warn!(parent: &span, status, latency_ms, error = %err, "Request failed");
+            Some(err.error_response_with_request_id(request_id.to_string()))
+        }
+        None => {
+            info!(parent: &span, status, latency_ms, "Request completed");
+            None
+        }
+    };
+
+    let mut response = match error_response {
+        Some(error_response) => response.into_response(error_response).map_into_right_body(),
+        None => response,
+    };
+    response.headers_mut().insert(
+        HeaderName::from_static(REQUEST_ID_HEADER),
+        HeaderValue::from_str(&request_id.to_string()).expect("a UUID is a valid header value"),
+    );
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, sync::Arc};
+
+    use actix_web::{App, HttpResponse, http::StatusCode, middleware::from_fn, test, web};
+    use parking_lot::Mutex;
+    use ream_beacon_api_types::error::ErrorResponse;
+    use tracing_subscriber::fmt::MakeWriter;
+
+    use super::*;
+
+    /// Collects everything the test subscriber logs.
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CapturedLogs {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    async fn fail() -> Result<HttpResponse, ApiError> {
+        Err(ApiError::InternalError("Failed to read state".to_string()))
+    }
+
+    async fn ok() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[actix_web::test]
+    async fn test_failed_request_id_matches_logs() {
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(logs.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(assign_request_id))
+                .route("/fail", web::get().to(fail)),
+        )
+        .await;
+        let request = test::TestRequest::get().uri("/fail").to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let header = response.headers().get(REQUEST_ID_HEADER).unwrap().clone();
+
+        let body: ErrorResponse = test::read_body_json(response).await;
+        let request_id = body.request_id.unwrap();
+        assert_eq!(header, request_id.as_str());
+        assert_eq!(body.code, 500);
+        assert_eq!(body.message, "Internal Server Error: Failed to read state");
+
+        let logs = String::from_utf8(logs.0.lock().clone()).unwrap();
+        let failure = logs
+            .lines()
+            .find(|line| line.contains("Request failed"))
+            .unwrap();
+        assert!(failure.contains(&format!("request_id={request_id}")));
+        assert!(failure.contains("path=/fail"));
+        assert!(failure.contains("status=500"));
+    }
+
+    #[actix_web::test]
+    async fn test_successful_request_has_id_header() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(assign_request_id))
+                .route("/ok", web::get().to(ok)),
+        )
+        .await;
+        let request = test::TestRequest::get().uri("/ok").to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let header = response.headers().get(REQUEST_ID_HEADER).unwrap();
+        assert!(Uuid::parse_str(header.to_str().unwrap()).is_ok());
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use actix_web::rt::time::sleep;
+use ream_storage::errors::StoreError;
+
+/// Number of times a storage read is attempted before its error is returned.
+pub const STORAGE_READ_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry of a storage read, doubled after every further attempt.
+pub const STORAGE_READ_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Runs `read` up to `attempts` times, sleeping `backoff` before the first retry and doubling it
+/// before each following one. Only transient errors are retried, see
+/// [`StoreError::is_transient`], any other error is returned immediately.
+pub async fn with_retry<T>(
+    attempts: u32,
+    backoff: Duration,
+    mut read: impl FnMut() -> Result<T, StoreError>,
+) -> Result<T, StoreError> {
+    let mut backoff = backoff;
+    let mut attempt = 1;
+    loop {
+        match read() {
+            Err(err) if err.is_transient() && attempt < attempts => {
+                sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// [`with_retry`] with the default storage read attempts and backoff.
+pub async fn read_with_retry<T>(
+    read: impl FnMut() -> Result<T, StoreError>,
+) -> Result<T, StoreError> {
+    with_retry(STORAGE_READ_ATTEMPTS, STORAGE_READ_BACKOFF, read).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    use actix_web::{App, HttpResponse, http::StatusCode, test, web};
+    use ream_beacon_api_types::error::ApiError;
+
+    use super::*;
+
+    fn transient_error() -> StoreError {
+        StoreError::Io(io::Error::from(io::ErrorKind::WouldBlock))
+    }
+
+    /// Fails its first `failures` reads with a transient error.
+    fn flaky_read(calls: &AtomicU32, failures: u32) -> Result<u64, StoreError> {
+        match calls.fetch_add(1, Ordering::SeqCst) {
+            call if call < failures => Err(transient_error()),
+            _ => Ok(7),
+        }
+    }
+
+    async fn flaky_handler(calls: web::Data<AtomicU32>) -> Result<HttpResponse, ApiError> {
+        let value = read_with_retry(|| flaky_read(&calls, 2))
+            .await
+            .map_err(|err| ApiError::InternalError(err.to_string()))?;
+
+        Ok(HttpResponse::Ok().json(value))
+    }
+
+    #[actix_web::test]
+    async fn test_handler_succeeds_after_transient_failures() {
+        let calls = web::Data::new(AtomicU32::new(0));
+        let app = test::init_service(
+            App::new()
+                .app_data(calls.clone())
+                .route("/", web::get().to(flaky_handler)),
+        )
+        .await;
+
+        let response = test::call_service(&app, test::TestRequest::get().to_request()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[actix_web::test]
+    async fn test_gives_up_after_attempts() {
+        let calls = AtomicU32::new(0);
+        let result = with_retry(2, Duration::ZERO, || flaky_read(&calls, 2)).await;
+
+        assert!(matches!(result, Err(StoreError::Io(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[actix_web::test]
+    async fn test_non_transient_errors_are_not_retried() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = with_retry(3, Duration::ZERO, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(StoreError::NotFound("beacon state".to_string()))
+        })
+        .await;
+
+        assert!(matches!(result, Err(StoreError::NotFound(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -10,7 +10,12 @@ use crate::handlers::{
     light_client::{
         get_light_client_bootstrap, get_light_client_finality_update, get_light_client_updates,
     },
-    pool::{get_voluntary_exits, post_voluntary_exits},
+    pool::{
+        get_bls_to_execution_changes, get_pool_attestations, get_voluntary_exits,
+        post_attester_slashings, post_bls_to_execution_changes, post_pool_attestations,
+        post_voluntary_exits,
+    },
+    rewards::{post_attestation_rewards, post_sync_committee_rewards},
     state::{
         get_pending_consolidations, get_pending_deposits, get_pending_partial_withdrawals,
         get_state_finality_checkpoint, get_state_fork, get_state_randao, get_state_root,
@@ -27,6 +32,8 @@ use crate::handlers::{
 pub fn register_beacon_routes(cfg: &mut ServiceConfig) {
     cfg.service(get_blob_sidecars)
         .service(get_block_rewards)
+        .service(post_attestation_rewards)
+        .service(post_sync_committee_rewards)
         .service(get_block_root)
         .service(get_committees)
         .service(get_genesis)
@@ -48,6 +55,9 @@ pub fn register_beacon_routes(cfg: &mut ServiceConfig) {
         .service(post_validator_balances_from_state)
         .service(get_voluntary_exits)
         .service(post_voluntary_exits)
+        .service(post_attester_slashings)
+        .service(get_bls_to_execution_changes)
+        .service(post_bls_to_execution_changes)
         .service(get_light_client_bootstrap)
         .service(get_light_client_updates)
         .service(get_light_client_finality_update);
@@ -55,5 +65,7 @@ pub fn register_beacon_routes(cfg: &mut ServiceConfig) {
 
 pub fn register_beacon_routes_v2(cfg: &mut ServiceConfig) {
     cfg.service(get_block_attestations)
-        .service(get_block_from_id);
+        .service(get_block_from_id)
+        .service(get_pool_attestations)
+        .service(post_pool_attestations);
 }
@@ -1,11 +1,22 @@
 use actix_web::web::{ServiceConfig, scope};
 
-use crate::handlers::{block::get_beacon_heads, state::get_beacon_state};
+use crate::handlers::{
+    block::get_beacon_heads,
+    debug::{get_debug_heads, get_debug_state, get_validator_stats},
+};
 
 pub fn register_debug_routes(cfg: &mut ServiceConfig) {
-    cfg.service(scope("/debug").service(get_beacon_heads));
+    cfg.service(
+        scope("/debug")
+            .service(get_debug_heads)
+            .service(get_validator_stats),
+    );
 }
 
 pub fn register_debug_routes_v2(cfg: &mut ServiceConfig) {
-    cfg.service(scope("/debug").service(get_beacon_state));
+    cfg.service(
+        scope("/debug")
+            .service(get_beacon_heads)
+            .service(get_debug_state),
+    );
 }
@@ -1,4 +1,7 @@
-use actix_web::web::{ServiceConfig, scope};
+use actix_web::web::{PathConfig, ServiceConfig, scope};
+use ream_beacon_api_types::error::ApiError;
+
+use crate::{handlers::events::get_events_ws, metrics::get_metrics};
 
 pub mod beacon;
 pub mod config;
@@ -25,6 +28,19 @@ pub fn get_v2_routes(config: &mut ServiceConfig) {
     );
 }
 
+pub fn get_ws_routes(config: &mut ServiceConfig) {
+    config.service(scope("/ws").service(get_events_ws));
+}
+
 pub fn register_routers(config: &mut ServiceConfig) {
-    config.configure(get_v1_routes).configure(get_v2_routes);
+    config
+        // Malformed path parameters are a 400, actix answers 404 by default
+        .app_data(
+            PathConfig::default()
+                .error_handler(|err, _| ApiError::BadRequest(err.to_string()).into()),
+        )
+        .configure(get_v1_routes)
+        .configure(get_v2_routes)
+        .configure(get_ws_routes)
+        .service(get_metrics);
 }
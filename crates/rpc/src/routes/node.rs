@@ -1,14 +1,17 @@
 use actix_web::web::ServiceConfig;
 
 use crate::handlers::{
-    peers::{get_peer, get_peer_count},
+    identity::get_node_identity,
+    peers::{get_peer, get_peer_count, get_peers},
     syncing::get_syncing_status,
     version::get_version,
 };
 
 pub fn register_node_routes(cfg: &mut ServiceConfig) {
     cfg.service(get_version)
+        .service(get_node_identity)
         .service(get_peer)
         .service(get_peer_count)
+        .service(get_peers)
         .service(get_syncing_status);
 }
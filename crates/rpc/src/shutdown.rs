@@ -0,0 +1,172 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use actix_web::{
+    Error,
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web::Data,
+};
+use ream_beacon_api_types::error::ApiError;
+use tokio::sync::Notify;
+
+/// How long in-flight requests get to complete once the server is shutting down.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tracks the requests being handled so a shutdown can wait for them, and whether the server is
+/// shutting down so new requests can be turned away.
+#[derive(Debug, Default)]
+pub struct ShutdownState {
+    shutting_down: AtomicBool,
+    in_flight: AtomicUsize,
+    drained: Notify,
+}
+
+impl ShutdownState {
+    /// Rejects every request from now on, requests already being handled are not interrupted.
+    pub fn begin(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// Number of requests whose handler has not returned yet.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Waits until no request is in flight, for at most `timeout`. Returns whether the requests
+    /// drained in time.
+    pub async fn wait_for_drain(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, async {
+            loop {
+                // Registered before the check so a request finishing in between is not missed
+                let drained = self.drained.notified();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                drained.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+
+    fn track(shutdown_state: &Data<ShutdownState>) -> InFlight {
+        shutdown_state.in_flight.fetch_add(1, Ordering::SeqCst);
+        InFlight(shutdown_state.clone())
+    }
+}
+
+/// Counts a request as in flight until dropped.
+struct InFlight(Data<ShutdownState>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.drained.notify_waiters();
+        }
+    }
+}
+
+/// Rejects requests with 503 once the server is shutting down and counts the others as in flight
+/// until their handler returns. A response body streamed after that, like the event stream, does
+/// not hold up the shutdown. Requires a `Data<ShutdownState>` in the app data, without one every
+/// request is let through.
+pub async fn reject_during_shutdown(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let Some(shutdown_state) = request.app_data::<Data<ShutdownState>>().cloned() else {
+        return Ok(next.call(request).await?.map_into_left_body());
+    };
+
+    // Counted before the check, so a shutdown either sees this request or turns it away
+    let _in_flight = ShutdownState::track(&shutdown_state);
+    if shutdown_state.is_shutting_down() {
+        return Ok(request
+            .error_response(ApiError::ShuttingDown)
+            .map_into_right_body());
+    }
+
+    Ok(next.call(request).await?.map_into_left_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::pin;
+
+    use actix_web::{App, HttpResponse, http::StatusCode, middleware::from_fn, test, web};
+    use ream_beacon_api_types::error::ErrorResponse;
+
+    use super::*;
+
+    async fn slow() -> HttpResponse {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        HttpResponse::Ok().finish()
+    }
+
+    async fn ok() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[actix_web::test]
+    async fn test_in_flight_request_completes_during_shutdown() {
+        let shutdown_state = Data::new(ShutdownState::default());
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(reject_during_shutdown))
+                .app_data(shutdown_state.clone())
+                .route("/slow", web::get().to(slow))
+                .route("/ok", web::get().to(ok)),
+        )
+        .await;
+
+        let mut slow_request = pin!(test::call_service(
+            &app,
+            test::TestRequest::get().uri("/slow").to_request()
+        ));
+        assert!(futures::poll!(&mut slow_request).is_pending());
+        assert_eq!(shutdown_state.in_flight(), 1);
+
+        shutdown_state.begin();
+        let response =
+            test::call_service(&app, test::TestRequest::get().uri("/ok").to_request()).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body: ErrorResponse = test::read_body_json(response).await;
+        assert_eq!(body.code, 503);
+        assert_eq!(body.message, "Server is shutting down");
+        assert_eq!(shutdown_state.in_flight(), 1);
+
+        let (drained, response) = tokio::join!(
+            shutdown_state.wait_for_drain(Duration::from_secs(5)),
+            slow_request
+        );
+        assert!(drained);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(shutdown_state.in_flight(), 0);
+    }
+
+    #[actix_web::test]
+    async fn test_drain_times_out() {
+        let shutdown_state = Data::new(ShutdownState::default());
+        assert!(
+            shutdown_state
+                .wait_for_drain(Duration::from_millis(10))
+                .await
+        );
+
+        let _in_flight = ShutdownState::track(&shutdown_state);
+        shutdown_state.begin();
+        assert!(
+            !shutdown_state
+                .wait_for_drain(Duration::from_millis(10))
+                .await
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use alloy_primitives::B256;
+use parking_lot::Mutex;
+use ream_consensus::electra::beacon_state::BeaconState;
+use tokio::sync::OnceCell;
+
+/// Number of states kept by a default [`StateCache`]. Head queries mostly hit the latest one or two
+/// heads, so a few entries are enough.
+pub const STATE_CACHE_CAPACITY: usize = 8;
+
+type StateCell = Arc<OnceCell<Arc<BeaconState>>>;
+
+/// Recently loaded states keyed by state root. Concurrent requests for the same root share a
+/// single load, so a new head is only read and deserialized once however many clients ask for it
+/// at the same time.
+#[derive(Debug)]
+pub struct StateCache {
+    capacity: usize,
+    /// Entries oldest first, the oldest is evicted once `capacity` is reached.
+    entries: Mutex<VecDeque<(B256, StateCell)>>,
+}
+
+impl Default for StateCache {
+    fn default() -> Self {
+        Self::new(STATE_CACHE_CAPACITY)
+    }
+}
+
+impl StateCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Returns the state at `state_root`, calling `load` if it is neither cached nor being loaded
+    /// by another request. A failed load is not cached, the next request tries again.
+    pub async fn get_or_load<E, F>(
+        &self,
+        state_root: B256,
+        load: impl FnOnce() -> F,
+    ) -> Result<Arc<BeaconState>, E>
+    where
+        F: Future<Output = Result<BeaconState, E>>,
+    {
+        let cell = {
+            let mut entries = self.entries.lock();
+            match entries.iter().find(|(root, _)| *root == state_root) {
+                Some((_, cell)) => cell.clone(),
+                None => {
+                    if entries.len() >= self.capacity {
+                        entries.pop_front();
+                    }
+                    let cell = StateCell::default();
+                    entries.push_back((state_root, cell.clone()));
+                    cell
+                }
+            }
+        };
+
+        cell.get_or_try_init(|| async { load().await.map(Arc::new) })
+            .await
+            .cloned()
+    }
+}
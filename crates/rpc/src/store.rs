@@ -0,0 +1,96 @@
+use alloy_primitives::B256;
+use hashbrown::HashMap;
+use ream_consensus::{
+    checkpoint::Checkpoint,
+    electra::{
+        beacon_block::{BeaconBlock, SignedBeaconBlock},
+        beacon_state::BeaconState,
+    },
+};
+use ream_fork_choice::store::Store;
+use ream_storage::{
+    db::ReamDB,
+    errors::StoreError,
+    tables::{Field, Table},
+};
+
+/// The reads the block handlers make, so they can be served from a backend other than `ReamDB`,
+/// such as the in-memory store of the `test-utils` feature.
+///
+/// Handlers taking a `Data<Arc<dyn BeaconStore>>` are served from whichever backend the app was
+/// built with.
+pub trait BeaconStore: Send + Sync {
+    fn beacon_block(&self, block_root: B256) -> Result<Option<SignedBeaconBlock>, StoreError>;
+
+    /// The post-state of the block at `block_root`.
+    fn beacon_state(&self, block_root: B256) -> Result<Option<BeaconState>, StoreError>;
+
+    /// The root of the canonical block at `slot`, `None` for skipped slots.
+    fn block_root_at_slot(&self, slot: u64) -> Result<Option<B256>, StoreError>;
+
+    fn justified_checkpoint(&self) -> Result<Checkpoint, StoreError>;
+
+    fn finalized_checkpoint(&self) -> Result<Checkpoint, StoreError>;
+
+    /// Whether the payload of the block at `block_root`, or of one of its ancestors, has not been
+    /// verified by the execution layer.
+    fn is_optimistic(&self, block_root: B256) -> Result<bool, StoreError>;
+
+    /// The root of the current head block.
+    fn head_root(&self) -> anyhow::Result<B256>;
+
+    /// The fork choice block tree rooted at the justified checkpoint, always containing the
+    /// justified block itself.
+    fn justified_block_tree(&self) -> anyhow::Result<HashMap<B256, BeaconBlock>>;
+}
+
+impl BeaconStore for ReamDB {
+    fn beacon_block(&self, block_root: B256) -> Result<Option<SignedBeaconBlock>, StoreError> {
+        self.beacon_block_provider().get(block_root)
+    }
+
+    fn beacon_state(&self, block_root: B256) -> Result<Option<BeaconState>, StoreError> {
+        self.beacon_state_provider().get(block_root)
+    }
+
+    fn block_root_at_slot(&self, slot: u64) -> Result<Option<B256>, StoreError> {
+        self.slot_index_provider().get(slot)
+    }
+
+    fn justified_checkpoint(&self) -> Result<Checkpoint, StoreError> {
+        self.justified_checkpoint_provider().get()
+    }
+
+    fn finalized_checkpoint(&self) -> Result<Checkpoint, StoreError> {
+        self.finalized_checkpoint_provider().get()
+    }
+
+    fn is_optimistic(&self, block_root: B256) -> Result<bool, StoreError> {
+        ReamDB::is_optimistic(self, block_root)
+    }
+
+    fn head_root(&self) -> anyhow::Result<B256> {
+        Store::read_only(self.clone()).get_head()
+    }
+
+    fn justified_block_tree(&self) -> anyhow::Result<HashMap<B256, BeaconBlock>> {
+        let justified_checkpoint = self.justified_checkpoint_provider().get()?;
+
+        let mut blocks = HashMap::new();
+        Store::read_only(self.clone()).filter_block_tree(justified_checkpoint.root, &mut blocks)?;
+
+        // The justified block is the head when none of its descendants are viable, so it stays in
+        // the tree even when the filter drops it
+        if !blocks.contains_key(&justified_checkpoint.root) {
+            let block = self
+                .beacon_block_provider()
+                .get(justified_checkpoint.root)?
+                .ok_or_else(|| {
+                    StoreError::NotFound(format!("justified block {}", justified_checkpoint.root))
+                })?;
+            blocks.insert(justified_checkpoint.root, block.message);
+        }
+
+        Ok(blocks)
+    }
+}
@@ -0,0 +1,211 @@
+//! Helpers for driving the Beacon API through the full actix stack in tests. Enabled by the
+//! `test-utils` feature, and always available to the tests of this crate.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Once},
+};
+
+use actix_web::{App, dev::AppEntry, web::Data};
+use alloy_primitives::B256;
+use parking_lot::RwLock;
+use ream_beacon_api_types::events::BeaconEvent;
+use ream_consensus::{
+    checkpoint::Checkpoint,
+    electra::{
+        beacon_block::{BeaconBlock, SignedBeaconBlock},
+        beacon_state::BeaconState,
+    },
+};
+use ream_execution_engine::ExecutionEngine;
+use ream_network_spec::networks::{MAINNET, set_network_spec};
+use ream_operation_pool::OperationPool;
+use ream_storage::{db::ReamDB, errors::StoreError};
+use tempfile::TempDir;
+use tokio::sync::broadcast;
+use tree_hash::TreeHash;
+
+use crate::{
+    handlers::events::EVENT_CHANNEL_CAPACITY, proposer_cache::ProposerCache,
+    routes::register_routers, state_cache::StateCache, store::BeaconStore,
+    validator_index_cache::ValidatorIndexCache,
+};
+
+static NETWORK_SPEC: Once = Once::new();
+
+/// Sets the network spec to mainnet unless a test already did, for handlers that read it.
+pub fn init_network_spec() {
+    NETWORK_SPEC.call_once(|| set_network_spec(MAINNET.clone()));
+}
+
+/// A `ReamDB` in a temporary directory that is removed when the `TestDB` is dropped.
+pub struct TestDB {
+    pub db: ReamDB,
+    _data_dir: TempDir,
+}
+
+impl TestDB {
+    pub fn new() -> Result<Self, StoreError> {
+        let data_dir = TempDir::new()?;
+        let db = ReamDB::new(data_dir.path().to_path_buf())?;
+        Ok(Self {
+            db,
+            _data_dir: data_dir,
+        })
+    }
+
+    /// Stores `block` along with its slot, state root and parent root indices, returning its root.
+    pub fn insert_block(&self, block: SignedBeaconBlock) -> Result<B256, StoreError> {
+        let block_root = block.message.tree_hash_root();
+        self.db.write_batch(|batch| {
+            batch.insert_slot_index(block.message.slot, block_root)?;
+            batch.insert_block(block_root, block)
+        })?;
+        Ok(block_root)
+    }
+
+    /// Stores `state` as the post-state of the block at `block_root`.
+    pub fn insert_state(&self, block_root: B256, state: BeaconState) -> Result<(), StoreError> {
+        self.db
+            .write_batch(|batch| batch.insert_state(block_root, state))
+    }
+
+    /// Sets both the justified and the finalized checkpoint to `checkpoint`.
+    pub fn set_checkpoints(&self, checkpoint: Checkpoint) -> Result<(), StoreError> {
+        self.db.write_batch(|batch| {
+            batch.insert_justified_checkpoint(checkpoint)?;
+            batch.insert_finalized_checkpoint(checkpoint)
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct InMemoryStoreData {
+    blocks: HashMap<B256, SignedBeaconBlock>,
+    states: HashMap<B256, BeaconState>,
+    slot_index: HashMap<u64, B256>,
+    justified_checkpoint: Option<Checkpoint>,
+    finalized_checkpoint: Option<Checkpoint>,
+}
+
+/// A `BeaconStore` kept in memory. Every block is canonical and has a verified payload, every
+/// descendant of the justified block is viable, and the head is the block with the highest slot.
+#[derive(Debug, Default)]
+pub struct InMemoryStore {
+    data: RwLock<InMemoryStoreData>,
+}
+
+impl InMemoryStore {
+    /// Stores `block` and indexes it by its slot, returning its root.
+    pub fn insert_block(&self, block: SignedBeaconBlock) -> B256 {
+        let block_root = block.message.tree_hash_root();
+        let mut data = self.data.write();
+        data.slot_index.insert(block.message.slot, block_root);
+        data.blocks.insert(block_root, block);
+        block_root
+    }
+
+    /// Stores `state` as the post-state of the block at `block_root`.
+    pub fn insert_state(&self, block_root: B256, state: BeaconState) {
+        self.data.write().states.insert(block_root, state);
+    }
+
+    /// Sets both the justified and the finalized checkpoint to `checkpoint`.
+    pub fn set_checkpoints(&self, checkpoint: Checkpoint) {
+        let mut data = self.data.write();
+        data.justified_checkpoint = Some(checkpoint);
+        data.finalized_checkpoint = Some(checkpoint);
+    }
+}
+
+impl BeaconStore for InMemoryStore {
+    fn beacon_block(&self, block_root: B256) -> Result<Option<SignedBeaconBlock>, StoreError> {
+        Ok(self.data.read().blocks.get(&block_root).cloned())
+    }
+
+    fn beacon_state(&self, block_root: B256) -> Result<Option<BeaconState>, StoreError> {
+        Ok(self.data.read().states.get(&block_root).cloned())
+    }
+
+    fn block_root_at_slot(&self, slot: u64) -> Result<Option<B256>, StoreError> {
+        Ok(self.data.read().slot_index.get(&slot).copied())
+    }
+
+    fn justified_checkpoint(&self) -> Result<Checkpoint, StoreError> {
+        self.data
+            .read()
+            .justified_checkpoint
+            .ok_or(StoreError::FieldNotInitilized)
+    }
+
+    fn finalized_checkpoint(&self) -> Result<Checkpoint, StoreError> {
+        self.data
+            .read()
+            .finalized_checkpoint
+            .ok_or(StoreError::FieldNotInitilized)
+    }
+
+    fn is_optimistic(&self, _block_root: B256) -> Result<bool, StoreError> {
+        Ok(false)
+    }
+
+    fn head_root(&self) -> anyhow::Result<B256> {
+        Ok(self
+            .data
+            .read()
+            .blocks
+            .iter()
+            .max_by_key(|(block_root, block)| (block.message.slot, **block_root))
+            .map(|(block_root, _)| *block_root)
+            .ok_or_else(|| StoreError::NotFound("No blocks stored".to_string()))?)
+    }
+
+    fn justified_block_tree(&self) -> anyhow::Result<hashbrown::HashMap<B256, BeaconBlock>> {
+        let justified_checkpoint = self.justified_checkpoint()?;
+
+        let data = self.data.read();
+        let mut blocks = hashbrown::HashMap::new();
+        let mut pending_roots = vec![justified_checkpoint.root];
+        while let Some(block_root) = pending_roots.pop() {
+            let block = data
+                .blocks
+                .get(&block_root)
+                .ok_or_else(|| StoreError::NotFound(format!("beacon block {block_root}")))?;
+            blocks.insert(block_root, block.message.clone());
+            pending_roots.extend(
+                data.blocks
+                    .iter()
+                    .filter(|(_, child)| child.message.parent_root == block_root)
+                    .map(|(child_root, _)| *child_root),
+            );
+        }
+
+        Ok(blocks)
+    }
+}
+
+/// Builds an `App` serving every Beacon API route from `db`, with an empty operation pool and no
+/// execution engine, on the mainnet network spec. Node endpoints additionally need a
+/// `Data<Arc<NetworkState>>`, which can be added to the returned `App`. Pass it to
+/// `actix_web::test::init_service` to call handlers.
+pub fn test_app(db: ReamDB) -> App<AppEntry> {
+    let beacon_store: Arc<dyn BeaconStore> = Arc::new(db.clone());
+    test_app_with_store(beacon_store).app_data(Data::new(db))
+}
+
+/// Like [`test_app`], but without a `ReamDB`, so only the handlers reading a `BeaconStore` are
+/// served from `beacon_store`, the rest fail with 500.
+pub fn test_app_with_store(beacon_store: Arc<dyn BeaconStore>) -> App<AppEntry> {
+    init_network_spec();
+    let (event_sender, _) = broadcast::channel::<BeaconEvent>(EVENT_CHANNEL_CAPACITY);
+
+    App::new()
+        .app_data(Data::new(beacon_store))
+        .app_data(Data::new(StateCache::default()))
+        .app_data(Data::new(ProposerCache::default()))
+        .app_data(Data::new(ValidatorIndexCache::default()))
+        .app_data(Data::new(Arc::new(OperationPool::default())))
+        .app_data(Data::new(None::<ExecutionEngine>))
+        .app_data(Data::new(event_sender))
+        .configure(register_routers)
+}
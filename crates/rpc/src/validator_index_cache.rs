@@ -0,0 +1,190 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+use alloy_primitives::B256;
+use parking_lot::Mutex;
+use ream_bls::PublicKey;
+use ream_consensus::{checkpoint::Checkpoint, misc::compute_start_slot_at_epoch};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Number of registries kept by a default [`ValidatorIndexCache`]. Lookups mostly target the head
+/// state, so a few entries are enough.
+pub const VALIDATOR_INDEX_CACHE_CAPACITY: usize = 4;
+
+/// Public key to index map of the validator registry of a state.
+pub type ValidatorIndices = Arc<HashMap<PublicKey, u64>>;
+
+/// Validator indices of recently queried states keyed by state root, so resolving public keys
+/// does not scan the registry on every request. A map is only used for the state it was built
+/// from, and entries of states older than the finalized checkpoint are evicted once it advances,
+/// see [`ValidatorIndexCache::evict_on_finality`].
+#[derive(Debug)]
+pub struct ValidatorIndexCache {
+    capacity: usize,
+    /// Entries oldest first as `(state_root, slot, indices)`, the oldest is evicted once
+    /// `capacity` is reached.
+    entries: Mutex<VecDeque<(B256, u64, ValidatorIndices)>>,
+}
+
+impl Default for ValidatorIndexCache {
+    fn default() -> Self {
+        Self::new(VALIDATOR_INDEX_CACHE_CAPACITY)
+    }
+}
+
+impl ValidatorIndexCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Returns the validator indices of the state at `slot` with `state_root`, calling `build` if
+    /// they are not cached.
+    pub fn get_or_build(
+        &self,
+        state_root: B256,
+        slot: u64,
+        build: impl FnOnce() -> HashMap<PublicKey, u64>,
+    ) -> ValidatorIndices {
+        if let Some((_, _, validator_indices)) = self
+            .entries
+            .lock()
+            .iter()
+            .find(|(root, _, _)| *root == state_root)
+        {
+            return validator_indices.clone();
+        }
+
+        // Built without holding the lock, lookups in cached states are not held up
+        let validator_indices = Arc::new(build());
+
+        let mut entries = self.entries.lock();
+        entries.retain(|(root, _, _)| *root != state_root);
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back((state_root, slot, validator_indices.clone()));
+
+        validator_indices
+    }
+
+    /// Drops the entries of states before the first slot of the `finalized_checkpoint` epoch.
+    pub fn evict_finalized(&self, finalized_checkpoint: Checkpoint) {
+        let finalized_slot = compute_start_slot_at_epoch(finalized_checkpoint.epoch);
+        self.entries
+            .lock()
+            .retain(|(_, slot, _)| *slot >= finalized_slot);
+    }
+
+    /// Evicts finalized entries for every checkpoint received on `finalized`, such as a
+    /// subscription to the `FinalityNotifier` of the database, until its sender is dropped.
+    pub async fn evict_on_finality(&self, mut finalized: broadcast::Receiver<Checkpoint>) {
+        loop {
+            match finalized.recv().await {
+                Ok(finalized_checkpoint) => self.evict_finalized(finalized_checkpoint),
+                // Each checkpoint supersedes the earlier ones, so missed ones need no handling
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use ream_consensus::constants::SLOTS_PER_EPOCH;
+
+    use super::*;
+
+    fn public_key(byte: u8) -> PublicKey {
+        PublicKey {
+            inner: vec![byte; 48].into(),
+        }
+    }
+
+    fn registry(count: u8) -> HashMap<PublicKey, u64> {
+        (0..count)
+            .map(|index| (public_key(index), index as u64))
+            .collect()
+    }
+
+    #[test]
+    fn test_indices_are_cached_per_state_root() {
+        let cache = ValidatorIndexCache::default();
+        let builds = Cell::new(0);
+        let build = |count| {
+            builds.set(builds.get() + 1);
+            registry(count)
+        };
+
+        let root = B256::repeat_byte(1);
+        assert_eq!(cache.get_or_build(root, 10, || build(2)).len(), 2);
+        assert_eq!(cache.get_or_build(root, 10, || build(3)).len(), 2);
+        assert_eq!(builds.get(), 1);
+
+        // Another state has its own registry, even at the same slot
+        let other_root = B256::repeat_byte(2);
+        assert_eq!(cache.get_or_build(other_root, 10, || build(3)).len(), 3);
+        assert_eq!(builds.get(), 2);
+    }
+
+    #[test]
+    fn test_oldest_state_is_evicted_at_capacity() {
+        let cache = ValidatorIndexCache::new(2);
+        let builds = Cell::new(0);
+        let build = || {
+            builds.set(builds.get() + 1);
+            registry(1)
+        };
+
+        for byte in 1..=3 {
+            cache.get_or_build(B256::repeat_byte(byte), byte as u64, build);
+        }
+        assert_eq!(builds.get(), 3);
+
+        cache.get_or_build(B256::repeat_byte(3), 3, build);
+        assert_eq!(builds.get(), 3);
+        cache.get_or_build(B256::repeat_byte(1), 1, build);
+        assert_eq!(builds.get(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_finalized_states_are_evicted_on_finality() {
+        let cache = Arc::new(ValidatorIndexCache::default());
+        let builds = Cell::new(0);
+        let build = || {
+            builds.set(builds.get() + 1);
+            registry(1)
+        };
+
+        let old_root = B256::repeat_byte(1);
+        let head_root = B256::repeat_byte(2);
+        cache.get_or_build(old_root, SLOTS_PER_EPOCH - 1, build);
+        cache.get_or_build(head_root, 2 * SLOTS_PER_EPOCH, build);
+
+        let (sender, receiver) = broadcast::channel(4);
+        let eviction = tokio::spawn({
+            let cache = cache.clone();
+            async move { cache.evict_on_finality(receiver).await }
+        });
+        sender
+            .send(Checkpoint {
+                epoch: 1,
+                root: B256::repeat_byte(3),
+            })
+            .unwrap();
+        drop(sender);
+        eviction.await.unwrap();
+
+        cache.get_or_build(head_root, 2 * SLOTS_PER_EPOCH, build);
+        assert_eq!(builds.get(), 2);
+        cache.get_or_build(old_root, SLOTS_PER_EPOCH - 1, build);
+        assert_eq!(builds.get(), 3);
+    }
+}
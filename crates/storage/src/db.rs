@@ -1,24 +1,50 @@
-use std::{fs, io, path::PathBuf, sync::Arc};
+use std::{
+    cell::Cell,
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
+use alloy_primitives::B256;
 use anyhow::Result;
-use redb::{Builder, Database};
+use ream_consensus::{
+    checkpoint::Checkpoint,
+    constants::GENESIS_SLOT,
+    electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
+    misc::{compute_epoch_at_slot, compute_start_slot_at_epoch},
+};
+use ream_execution_engine::ExecutionEngine;
+use redb::{Builder, Database, Durability, ReadableTable, WriteTransaction};
+use ssz::{Decode, Encode};
 use tracing::info;
+use tree_hash::TreeHash;
 
 use crate::{
     errors::StoreError,
+    finality::FinalityNotifier,
     tables::{
+        Field, Table,
         beacon_block::{BEACON_BLOCK_TABLE, BeaconBlockTable},
         beacon_state::{BEACON_STATE_TABLE, BeaconStateTable},
+        blob_sidecar::BlobSidecarTable,
         blobs_and_proofs::{BLOB_FOLDER_NAME, BlobsAndProofsTable},
         block_timeliness::{BLOCK_TIMELINESS_TABLE, BlockTimelinessTable},
         checkpoint_states::{CHECKPOINT_STATES_TABLE, CheckpointStatesTable},
         equivocating_indices::{EQUIVOCATING_INDICES_FIELD, EquivocatingIndicesField},
-        finalized_checkpoint::{FINALIZED_CHECKPOINT_FIELD, FinalizedCheckpointField},
+        execution_status::{EXECUTION_STATUS_TABLE, ExecutionStatus, ExecutionStatusTable},
+        finalized_checkpoint::{
+            FINALIZED_CHECKPOINT_FIELD, FINALIZED_CHECKPOINT_FIELD_KEY, FinalizedCheckpointField,
+        },
         genesis_time::{GENESIS_TIME_FIELD, GenesisTimeField},
-        justified_checkpoint::{JUSTIFIED_CHECKPOINT_FIELD, JustifiedCheckpointField},
+        justified_checkpoint::{
+            JUSTIFIED_CHECKPOINT_FIELD, JUSTIFIED_CHECKPOINT_KEY, JustifiedCheckpointField,
+        },
         latest_messages::{LATEST_MESSAGES_TABLE, LatestMessagesTable},
         parent_root_index::{PARENT_ROOT_INDEX_MULTIMAP_TABLE, ParentRootIndexMultimapTable},
-        proposer_boost_root::{PROPOSER_BOOST_ROOT_FIELD, ProposerBoostRootField},
+        proposer_boost_root::{
+            PROPOSER_BOOST_ROOT_FIELD, PROPOSER_BOOST_ROOT_KEY, ProposerBoostRootField,
+        },
         slot_index::{SLOT_INDEX_TABLE, SlotIndexTable},
         state_root_index::{STATE_ROOT_INDEX_TABLE, StateRootIndexTable},
         time::{TIME_FIELD, TimeField},
@@ -41,10 +67,134 @@ pub const REDB_FILE: &str = "ream.redb";
 /// 1 GiB
 pub const REDB_CACHE_SIZE: usize = 1_024 * 1_024 * 1_024;
 
+/// Number of entries removed by [`ReamDB::prune_finalized`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruneReport {
+    pub pruned_states: u64,
+    pub pruned_blocks: u64,
+}
+
+/// A group of writes that is committed atomically by [`ReamDB::write_batch`].
+pub struct WriteBatch {
+    write_txn: WriteTransaction,
+    /// The finalized checkpoint written by the batch, announced once the batch is committed
+    finalized_checkpoint: Cell<Option<Checkpoint>>,
+}
+
+impl WriteBatch {
+    /// Insert ``block`` at ``block_root`` along with its state root and parent root index entries.
+    ///
+    /// The slot index only holds canonical blocks, so it is left to
+    /// [`WriteBatch::insert_slot_index`] for blocks known to be canonical and to
+    /// [`ReamDB::update_slot_index`] once fork choice picks a head.
+    pub fn insert_block(
+        &self,
+        block_root: B256,
+        block: SignedBeaconBlock,
+    ) -> Result<(), StoreError> {
+        self.write_txn
+            .open_table(STATE_ROOT_INDEX_TABLE)?
+            .insert(block.message.state_root, block_root)?;
+        self.write_txn
+            .open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?
+            .insert(block.message.parent_root, block_root)?;
+        self.write_txn
+            .open_table(BEACON_BLOCK_TABLE)?
+            .insert(block_root, block)?;
+        Ok(())
+    }
+
+    /// Point the slot index at the canonical block ``block_root`` for ``slot``.
+    pub fn insert_slot_index(&self, slot: u64, block_root: B256) -> Result<(), StoreError> {
+        self.write_txn
+            .open_table(SLOT_INDEX_TABLE)?
+            .insert(slot, block_root)?;
+        Ok(())
+    }
+
+    pub fn insert_state(&self, block_root: B256, state: BeaconState) -> Result<(), StoreError> {
+        self.write_txn
+            .open_table(BEACON_STATE_TABLE)?
+            .insert(block_root, state)?;
+        Ok(())
+    }
+
+    /// Insert ``block`` and its post-state ``state`` at ``block_root``, after checking that the
+    /// state root ``block`` commits to is ``state_root``, the root of ``state``. Hashing a state is
+    /// expensive, so ``state_root`` is taken from the caller: the root a validating state
+    /// transition already checked, or ``state.tree_hash_root()`` on paths that skip validation.
+    ///
+    /// Fails with ``StoreError::StateRootMismatch`` without writing anything otherwise, so a
+    /// corrupt block never ends up in the state root index.
+    pub fn insert_block_with_state(
+        &self,
+        block_root: B256,
+        block: SignedBeaconBlock,
+        state: BeaconState,
+        state_root: B256,
+    ) -> Result<(), StoreError> {
+        if block.message.state_root != state_root {
+            return Err(StoreError::StateRootMismatch(format!(
+                "block {block_root} has state root {} != post-state root {state_root}",
+                block.message.state_root
+            )));
+        }
+
+        self.insert_block(block_root, block)?;
+        self.insert_state(block_root, state)
+    }
+
+    pub fn insert_block_timeliness(
+        &self,
+        block_root: B256,
+        is_timely: bool,
+    ) -> Result<(), StoreError> {
+        self.write_txn
+            .open_table(BLOCK_TIMELINESS_TABLE)?
+            .insert(block_root, is_timely)?;
+        Ok(())
+    }
+
+    pub fn insert_execution_status(
+        &self,
+        block_root: B256,
+        execution_status: ExecutionStatus,
+    ) -> Result<(), StoreError> {
+        self.write_txn
+            .open_table(EXECUTION_STATUS_TABLE)?
+            .insert(block_root, execution_status)?;
+        Ok(())
+    }
+
+    pub fn insert_proposer_boost_root(&self, block_root: B256) -> Result<(), StoreError> {
+        self.write_txn
+            .open_table(PROPOSER_BOOST_ROOT_FIELD)?
+            .insert(PROPOSER_BOOST_ROOT_KEY, block_root)?;
+        Ok(())
+    }
+
+    pub fn insert_justified_checkpoint(&self, checkpoint: Checkpoint) -> Result<(), StoreError> {
+        self.write_txn
+            .open_table(JUSTIFIED_CHECKPOINT_FIELD)?
+            .insert(JUSTIFIED_CHECKPOINT_KEY, checkpoint)?;
+        Ok(())
+    }
+
+    pub fn insert_finalized_checkpoint(&self, checkpoint: Checkpoint) -> Result<(), StoreError> {
+        self.write_txn
+            .open_table(FINALIZED_CHECKPOINT_FIELD)?
+            .insert(FINALIZED_CHECKPOINT_FIELD_KEY, checkpoint)?;
+        self.finalized_checkpoint.set(Some(checkpoint));
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ReamDB {
     pub db: Arc<Database>,
     pub data_dir: PathBuf,
+    /// Announces every new finalized checkpoint, shared by all clones of the database
+    pub finality_notifier: FinalityNotifier,
 }
 
 impl ReamDB {
@@ -59,6 +209,7 @@ impl ReamDB {
         write_txn.open_table(BLOCK_TIMELINESS_TABLE)?;
         write_txn.open_table(CHECKPOINT_STATES_TABLE)?;
         write_txn.open_table(EQUIVOCATING_INDICES_FIELD)?;
+        write_txn.open_table(EXECUTION_STATUS_TABLE)?;
         write_txn.open_table(FINALIZED_CHECKPOINT_FIELD)?;
         write_txn.open_table(GENESIS_TIME_FIELD)?;
         write_txn.open_table(JUSTIFIED_CHECKPOINT_FIELD)?;
@@ -78,6 +229,7 @@ impl ReamDB {
         Ok(Self {
             db: Arc::new(db),
             data_dir,
+            finality_notifier: FinalityNotifier::default(),
         })
     }
 
@@ -93,6 +245,13 @@ impl ReamDB {
         }
     }
 
+    pub fn blob_sidecar_provider(&self) -> BlobSidecarTable {
+        BlobSidecarTable {
+            db: self.db.clone(),
+            data_dir: self.data_dir.clone(),
+        }
+    }
+
     pub fn blobs_and_proofs_provider(&self) -> BlobsAndProofsTable {
         BlobsAndProofsTable {
             data_dir: self.data_dir.clone(),
@@ -111,6 +270,12 @@ impl ReamDB {
         }
     }
 
+    pub fn execution_status_provider(&self) -> ExecutionStatusTable {
+        ExecutionStatusTable {
+            db: self.db.clone(),
+        }
+    }
+
     pub fn latest_messages_provider(&self) -> LatestMessagesTable {
         LatestMessagesTable {
             db: self.db.clone(),
@@ -150,6 +315,7 @@ impl ReamDB {
     pub fn finalized_checkpoint_provider(&self) -> FinalizedCheckpointField {
         FinalizedCheckpointField {
             db: self.db.clone(),
+            finality_notifier: self.finality_notifier.clone(),
         }
     }
 
@@ -189,6 +355,448 @@ impl ReamDB {
         }
     }
 
+    /// Write the SSZ encoding of the block stored at ``block_root`` to ``path``.
+    pub fn export_block_ssz(&self, block_root: B256, path: &Path) -> Result<(), StoreError> {
+        let block = self
+            .beacon_block_provider()
+            .get(block_root)?
+            .ok_or_else(|| StoreError::NotFound(format!("beacon block {block_root:?}")))?;
+        fs::write(path, block.as_ssz_bytes())?;
+        Ok(())
+    }
+
+    /// Read an SSZ encoded block from ``path``, store it and return its block root.
+    pub fn import_block_ssz(&self, path: &Path) -> Result<B256, StoreError> {
+        let block = SignedBeaconBlock::from_ssz_bytes(&fs::read(path)?)?;
+        let block_root = block.message.tree_hash_root();
+        self.beacon_block_provider().insert(block_root, block)?;
+        Ok(block_root)
+    }
+
+    /// Write the SSZ encoding of the state stored at ``block_root`` to ``path``.
+    pub fn export_state_ssz(&self, block_root: B256, path: &Path) -> Result<(), StoreError> {
+        let state = self
+            .beacon_state_provider()
+            .get(block_root)?
+            .ok_or_else(|| StoreError::NotFound(format!("beacon state {block_root:?}")))?;
+        fs::write(path, state.as_ssz_bytes())?;
+        Ok(())
+    }
+
+    /// Read an SSZ encoded state from ``path`` and store it at ``block_root``.
+    pub fn import_state_ssz(&self, block_root: B256, path: &Path) -> Result<(), StoreError> {
+        let state = BeaconState::from_ssz_bytes(&fs::read(path)?)?;
+        self.beacon_state_provider().insert(block_root, state)
+    }
+
+    /// Prune history older than ``retain_epochs`` before the finalized checkpoint.
+    ///
+    /// Below the cutoff, the first stored state of every epoch is kept so that historical states
+    /// can still be reconstructed by replaying blocks, and all other states are deleted. Blocks
+    /// that are not ancestors of the finalized checkpoint are deleted along with their states and
+    /// index entries, while canonical blocks are always kept. Nothing at or after the finalized
+    /// checkpoint is pruned, and the finalized and justified checkpoint states are always kept.
+    pub fn prune_finalized(&self, retain_epochs: u64) -> Result<PruneReport, StoreError> {
+        let finalized_checkpoint = self.finalized_checkpoint_provider().get()?;
+        let justified_checkpoint = self.justified_checkpoint_provider().get()?;
+        let cutoff_slot =
+            compute_start_slot_at_epoch(finalized_checkpoint.epoch.saturating_sub(retain_epochs));
+        let protected_roots = [finalized_checkpoint.root, justified_checkpoint.root];
+
+        let mut report = PruneReport::default();
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        {
+            let mut block_table = write_txn.open_table(BEACON_BLOCK_TABLE)?;
+            let mut state_table = write_txn.open_table(BEACON_STATE_TABLE)?;
+            let mut slot_index_table = write_txn.open_table(SLOT_INDEX_TABLE)?;
+            let mut state_root_index_table = write_txn.open_table(STATE_ROOT_INDEX_TABLE)?;
+            let mut parent_root_index_table =
+                write_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
+
+            let mut old_blocks = vec![];
+            for entry in block_table.iter()? {
+                let (block_root, block) = entry?;
+                let block = block.value().message;
+                if block.slot < cutoff_slot {
+                    old_blocks.push((block_root.value(), block));
+                }
+            }
+            old_blocks.sort_by_key(|(_, block)| block.slot);
+
+            // Walk the finalized chain back to the oldest candidate to find canonical blocks
+            let mut canonical_roots = HashSet::new();
+            if let Some((_, oldest_block)) = old_blocks.first() {
+                let mut block_root = finalized_checkpoint.root;
+                while let Some(block) = block_table.get(block_root)?.map(|block| block.value()) {
+                    if block.message.slot < oldest_block.slot {
+                        break;
+                    }
+                    canonical_roots.insert(block_root);
+                    if block.message.slot == GENESIS_SLOT {
+                        break;
+                    }
+                    block_root = block.message.parent_root;
+                }
+            }
+
+            let mut retained_epochs = HashSet::new();
+            for (block_root, block) in old_blocks {
+                if protected_roots.contains(&block_root) {
+                    continue;
+                }
+
+                if canonical_roots.contains(&block_root) {
+                    if state_table.get(block_root)?.is_none() {
+                        continue;
+                    }
+                    if retained_epochs.insert(compute_epoch_at_slot(block.slot)) {
+                        continue;
+                    }
+                    state_table.remove(block_root)?;
+                    report.pruned_states += 1;
+                    continue;
+                }
+
+                if state_table.remove(block_root)?.is_some() {
+                    report.pruned_states += 1;
+                }
+                block_table.remove(block_root)?;
+                report.pruned_blocks += 1;
+
+                let indexed_root = slot_index_table
+                    .get(block.slot)?
+                    .map(|block_root| block_root.value());
+                if indexed_root == Some(block_root) {
+                    slot_index_table.remove(block.slot)?;
+                }
+                let indexed_root = state_root_index_table
+                    .get(block.state_root)?
+                    .map(|block_root| block_root.value());
+                if indexed_root == Some(block_root) {
+                    state_root_index_table.remove(block.state_root)?;
+                }
+                parent_root_index_table.remove(block.parent_root, block_root)?;
+            }
+        }
+        write_txn.commit()?;
+
+        Ok(report)
+    }
+
+    /// Initialize the database from a trusted finalized ``block`` and its post-state ``state``
+    /// instead of from genesis. Both are stored, with the slot index pointing at ``block``, and
+    /// become the justified and finalized checkpoint, so ``finalized`` resolves right away.
+    ///
+    /// Fails with ``StoreError::CheckpointMismatch`` if ``state`` is not the state ``block``
+    /// commits to.
+    pub fn init_from_checkpoint(
+        &self,
+        state: BeaconState,
+        block: SignedBeaconBlock,
+    ) -> Result<(), StoreError> {
+        let state_root = state.tree_hash_root();
+        if block.message.state_root != state_root {
+            return Err(StoreError::CheckpointMismatch(format!(
+                "block state root {} != state root {state_root}",
+                block.message.state_root
+            )));
+        }
+        if block.message.slot != state.slot {
+            return Err(StoreError::CheckpointMismatch(format!(
+                "block slot {} != state slot {}",
+                block.message.slot, state.slot
+            )));
+        }
+
+        let block_root = block.message.tree_hash_root();
+        let checkpoint = Checkpoint {
+            epoch: compute_epoch_at_slot(block.message.slot),
+            root: block_root,
+        };
+        self.write_batch(|batch| {
+            batch.insert_slot_index(block.message.slot, block_root)?;
+            batch.insert_block(block_root, block)?;
+            batch.insert_state(block_root, state)?;
+            batch.insert_justified_checkpoint(checkpoint)?;
+            batch.insert_finalized_checkpoint(checkpoint)
+        })?;
+        info!("Initialized database from checkpoint {checkpoint:?}");
+
+        Ok(())
+    }
+
+    /// Run ``f`` with a [`WriteBatch`] and commit all of its writes in a single transaction. If
+    /// ``f`` returns an error the transaction is aborted and none of the writes land.
+    ///
+    /// Only one write transaction can be open at a time, so ``f`` must not write through the
+    /// table providers, reads are fine and see the state before the batch.
+    pub fn write_batch<T>(
+        &self,
+        f: impl FnOnce(&WriteBatch) -> Result<T, StoreError>,
+    ) -> Result<T, StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        let batch = WriteBatch {
+            write_txn,
+            finalized_checkpoint: Cell::new(None),
+        };
+        match f(&batch) {
+            Ok(result) => {
+                batch.write_txn.commit()?;
+                if let Some(checkpoint) = batch.finalized_checkpoint.get() {
+                    self.finality_notifier.notify(checkpoint);
+                }
+                Ok(result)
+            }
+            Err(err) => {
+                batch.write_txn.abort()?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Wait for the write transaction in progress, if any, and make every write committed so far
+    /// durable by committing an empty transaction with immediate durability. Called on shutdown so
+    /// a pending [`ReamDB::write_batch`] lands before the process exits.
+    pub fn flush(&self) -> Result<(), StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        write_txn.commit()?;
+        Ok(())
+    }
+
+    /// Point the slot index at the chain ending in the new head ``head_root``.
+    ///
+    /// Walks back from ``head_root`` to the first block the slot index already points at,
+    /// indexing every block on the way and removing the entries of slots the new chain skips or
+    /// that are past its head, so blocks of an abandoned branch are no longer resolved by slot.
+    pub fn update_slot_index(&self, head_root: B256) -> Result<(), StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        {
+            let block_table = write_txn.open_table(BEACON_BLOCK_TABLE)?;
+            let mut slot_index_table = write_txn.open_table(SLOT_INDEX_TABLE)?;
+            let mut block_root = head_root;
+            let mut block = block_table
+                .get(block_root)?
+                .ok_or_else(|| StoreError::NotFound(format!("beacon block {block_root:?}")))?
+                .value();
+            // Nothing past the head is canonical
+            slot_index_table.retain_in(block.message.slot + 1.., |_, _| false)?;
+            loop {
+                let slot = block.message.slot;
+                let indexed_root = slot_index_table
+                    .get(slot)?
+                    .map(|block_root| block_root.value());
+                if indexed_root == Some(block_root) {
+                    break;
+                }
+                slot_index_table.insert(slot, block_root)?;
+
+                if slot == GENESIS_SLOT {
+                    break;
+                }
+                let parent_root = block.message.parent_root;
+                let Some(parent) = block_table.get(parent_root)?.map(|block| block.value()) else {
+                    break;
+                };
+                // Slots between the parent and the block are skipped by the new chain
+                slot_index_table.retain_in(parent.message.slot + 1..slot, |_, _| false)?;
+                (block_root, block) = (parent_root, parent);
+            }
+        }
+        write_txn.commit()?;
+
+        Ok(())
+    }
+
+    /// Rebuild the canonical post-state at ``slot`` from the nearest stored state at or before it,
+    /// replaying the canonical blocks in between and processing any empty slots up to ``slot``.
+    /// As [`ReamDB::prune_finalized`] keeps a state in every epoch, at most ``SLOTS_PER_EPOCH``
+    /// blocks are replayed for a pruned slot. Blocks are assumed valid, so signatures, state roots
+    /// and execution payloads are not verified.
+    pub async fn reconstruct_state_at(&self, slot: u64) -> Result<BeaconState, StoreError> {
+        let slot_index = self.slot_index_provider();
+        let beacon_state_provider = self.beacon_state_provider();
+
+        let mut replay_roots = vec![];
+        let mut base_state = None;
+        for indexed_slot in (GENESIS_SLOT..=slot).rev() {
+            let Some(block_root) = slot_index.get(indexed_slot)? else {
+                continue;
+            };
+            if let Some(state) = beacon_state_provider.get(block_root)? {
+                base_state = Some(state);
+                break;
+            }
+            replay_roots.push(block_root);
+        }
+        let mut state = base_state
+            .ok_or_else(|| StoreError::NotFound(format!("beacon state before slot {slot}")))?;
+
+        let beacon_block_provider = self.beacon_block_provider();
+        for block_root in replay_roots.into_iter().rev() {
+            let block = beacon_block_provider
+                .get(block_root)?
+                .ok_or_else(|| StoreError::NotFound(format!("beacon block {block_root:?}")))?;
+            state
+                .state_transition(&block, false, &None::<ExecutionEngine>)
+                .await
+                .map_err(|err| StoreError::StateTransition(err.to_string()))?;
+        }
+
+        if state.slot < slot {
+            state
+                .process_slots(slot)
+                .map_err(|err| StoreError::StateTransition(err.to_string()))?;
+        }
+
+        Ok(state)
+    }
+
+    /// Return whether the block at ``block_root`` is optimistic, i.e. its payload or the payload
+    /// of one of its ancestors was not verified by the execution layer. A block without a
+    /// recorded status inherits the status of its parent, and blocks that are not stored are not
+    /// optimistic. The finalized block and its ancestors are taken as verified, so the walk never
+    /// goes past the finalized slot.
+    ///
+    /// Fails with ``StoreError::InvalidExecutionPayload`` if the execution layer rejected the
+    /// payload of the block or of the ancestor it inherits its status from.
+    pub fn is_optimistic(&self, block_root: B256) -> Result<bool, StoreError> {
+        let finalized_slot = self.finalized_slot()?;
+        let execution_status_provider = self.execution_status_provider();
+        let beacon_block_provider = self.beacon_block_provider();
+        let mut root = block_root;
+        loop {
+            match execution_status_provider.get(root)? {
+                // A valid payload implies every ancestor payload is valid
+                Some(ExecutionStatus::Valid) => return Ok(false),
+                Some(ExecutionStatus::Optimistic) => return Ok(true),
+                Some(ExecutionStatus::Invalid) => {
+                    return Err(StoreError::InvalidExecutionPayload(format!(
+                        "payload of block {root:?}, inherited by block {block_root:?}"
+                    )));
+                }
+                None => match beacon_block_provider.get(root)? {
+                    Some(block) if finalized_slot.is_none_or(|slot| block.message.slot > slot) => {
+                        root = block.message.parent_root
+                    }
+                    _ => return Ok(false),
+                },
+            }
+        }
+    }
+
+    /// Record that the execution layer verified the payload of the block at ``block_root``. A
+    /// valid payload implies every ancestor payload is valid, so the optimistic ancestors and
+    /// those without a recorded status are marked valid as well, back to the first valid block or
+    /// the finalized slot.
+    ///
+    /// Fails with ``StoreError::InvalidExecutionPayload`` without writing anything if one of the
+    /// blocks was rejected by the execution layer.
+    pub fn set_execution_valid(&self, block_root: B256) -> Result<(), StoreError> {
+        let finalized_slot = self.finalized_slot()?;
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        {
+            let block_table = write_txn.open_table(BEACON_BLOCK_TABLE)?;
+            let mut execution_status_table = write_txn.open_table(EXECUTION_STATUS_TABLE)?;
+            let mut root = block_root;
+            loop {
+                let Some(block) = block_table.get(root)?.map(|block| block.value()) else {
+                    if root == block_root {
+                        return Err(StoreError::NotFound(format!("beacon block {block_root:?}")));
+                    }
+                    break;
+                };
+                let execution_status = execution_status_table
+                    .get(root)?
+                    .map(|execution_status| execution_status.value());
+                match execution_status {
+                    Some(ExecutionStatus::Valid) => break,
+                    Some(ExecutionStatus::Invalid) => {
+                        return Err(StoreError::InvalidExecutionPayload(format!(
+                            "payload of block {root:?}, an ancestor of valid block {block_root:?}"
+                        )));
+                    }
+                    Some(ExecutionStatus::Optimistic) | None => {
+                        execution_status_table.insert(root, ExecutionStatus::Valid)?;
+                    }
+                }
+
+                if block.message.slot == GENESIS_SLOT
+                    || finalized_slot.is_some_and(|slot| block.message.slot <= slot)
+                {
+                    break;
+                }
+                root = block.message.parent_root;
+            }
+        }
+        write_txn.commit()?;
+
+        Ok(())
+    }
+
+    /// Return the slot of the finalized checkpoint block, if it is set and stored.
+    fn finalized_slot(&self) -> Result<Option<u64>, StoreError> {
+        let finalized_checkpoint = match self.finalized_checkpoint_provider().get() {
+            Ok(checkpoint) => checkpoint,
+            Err(StoreError::FieldNotInitilized) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        Ok(self
+            .beacon_block_provider()
+            .get(finalized_checkpoint.root)?
+            .map(|block| block.message.slot))
+    }
+
+    /// Return the ``genesis_validators_root`` of the oldest stored state.
+    pub fn genesis_validators_root(&self) -> Result<B256, StoreError> {
+        Ok(self.oldest_state()?.genesis_validators_root)
+    }
+
+    /// Check that the stored chain belongs to the configured network and return its
+    /// ``genesis_validators_root``. The root must equal ``expected_genesis_validators_root``, when
+    /// the network has a known one, and the ``genesis_time`` can't be earlier than
+    /// ``min_genesis_time``.
+    ///
+    /// The expected root has to come from outside the database, comparing the stored root with
+    /// itself accepts the state of any network.
+    pub fn validate_genesis(
+        &self,
+        expected_genesis_validators_root: Option<B256>,
+        min_genesis_time: u64,
+    ) -> Result<B256, StoreError> {
+        let state = self.oldest_state()?;
+        if let Some(expected_genesis_validators_root) = expected_genesis_validators_root {
+            if state.genesis_validators_root != expected_genesis_validators_root {
+                return Err(StoreError::GenesisMismatch(format!(
+                    "stored genesis_validators_root {} does not match configured {expected_genesis_validators_root}",
+                    state.genesis_validators_root
+                )));
+            }
+        }
+        if state.genesis_time < min_genesis_time {
+            return Err(StoreError::GenesisMismatch(format!(
+                "stored genesis_time {} is earlier than configured min_genesis_time {min_genesis_time}",
+                state.genesis_time
+            )));
+        }
+
+        Ok(state.genesis_validators_root)
+    }
+
+    fn oldest_state(&self) -> Result<BeaconState, StoreError> {
+        let oldest_root = self
+            .slot_index_provider()
+            .get_oldest_root()?
+            .ok_or_else(|| StoreError::NotFound("oldest block root".to_string()))?;
+        self.beacon_state_provider()
+            .get(oldest_root)?
+            .ok_or_else(|| StoreError::NotFound(format!("beacon state {oldest_root:?}")))
+    }
+
     pub fn is_initialized(&self) -> bool {
         match self.slot_index_provider().get_highest_slot() {
             Ok(Some(slot)) => slot > 0,
@@ -224,3 +832,58 @@ pub fn reset_db(db_path: PathBuf) -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_write_batch_is_atomic() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_write_batch_is_atomic")?;
+        let db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+        let block_root = B256::repeat_byte(1);
+        let checkpoint = Checkpoint {
+            epoch: 1,
+            root: block_root,
+        };
+
+        let result = db.write_batch(|batch| {
+            batch.insert_execution_status(block_root, ExecutionStatus::Valid)?;
+            batch.insert_block_timeliness(block_root, true)?;
+            batch.insert_proposer_boost_root(block_root)?;
+            batch.insert_finalized_checkpoint(checkpoint)?;
+            Err::<(), _>(StoreError::NotFound("simulated failure".to_string()))
+        });
+        assert!(matches!(result, Err(StoreError::NotFound(_))));
+
+        // Nothing from the failed batch landed
+        assert_eq!(db.execution_status_provider().get(block_root)?, None);
+        assert_eq!(db.block_timeliness_provider().get(block_root)?, None);
+        assert!(matches!(
+            db.proposer_boost_root_provider().get(),
+            Err(StoreError::FieldNotInitilized)
+        ));
+        assert!(matches!(
+            db.finalized_checkpoint_provider().get(),
+            Err(StoreError::FieldNotInitilized)
+        ));
+
+        db.write_batch(|batch| {
+            batch.insert_execution_status(block_root, ExecutionStatus::Valid)?;
+            batch.insert_block_timeliness(block_root, true)?;
+            batch.insert_proposer_boost_root(block_root)?;
+            batch.insert_finalized_checkpoint(checkpoint)
+        })?;
+        assert_eq!(
+            db.execution_status_provider().get(block_root)?,
+            Some(ExecutionStatus::Valid)
+        );
+        assert_eq!(db.block_timeliness_provider().get(block_root)?, Some(true));
+        assert_eq!(db.proposer_boost_root_provider().get()?, block_root);
+        assert_eq!(db.finalized_checkpoint_provider().get()?, checkpoint);
+
+        Ok(())
+    }
+}
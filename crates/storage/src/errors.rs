@@ -11,11 +11,59 @@ pub enum StoreError {
     #[error("Field not initilized")]
     FieldNotInitilized,
 
+    #[error("Value not found: {0}")]
+    NotFound(String),
+
     #[error("DecodeError not found {0}")]
     DecodeError(String),
 
     #[error("SnappyError not found {0}")]
     SnappyError(#[from] snap::Error),
+
+    #[error("Failed to build blob sidecar: {0}")]
+    BlobSidecar(String),
+
+    #[error("State transition failed: {0}")]
+    StateTransition(String),
+
+    #[error("Stored genesis does not match the network config: {0}")]
+    GenesisMismatch(String),
+
+    #[error("Checkpoint block does not match its state: {0}")]
+    CheckpointMismatch(String),
+
+    #[error("Block state root does not match its post-state: {0}")]
+    StateRootMismatch(String),
+
+    #[error("Invalid snapshot: {0}")]
+    Snapshot(String),
+
+    #[error("Execution payload was rejected by the execution layer: {0}")]
+    InvalidExecutionPayload(String),
+}
+
+impl StoreError {
+    /// Returns whether the operation may succeed if it is tried again, such as an interrupted
+    /// read. Missing values, undecodable data and a database locked by another process never are.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            StoreError::Io(err) => is_transient_io_error(err),
+            StoreError::Redb(err) => match err.as_ref() {
+                redb::Error::Io(err) => is_transient_io_error(err),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+fn is_transient_io_error(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::Interrupted
+            | std::io::ErrorKind::WouldBlock
+            | std::io::ErrorKind::TimedOut
+    )
 }
 
 impl From<redb::Error> for StoreError {
@@ -59,3 +107,23 @@ impl From<ssz::DecodeError> for StoreError {
         StoreError::DecodeError(format!("{value:?}"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    #[test]
+    fn test_is_transient() {
+        assert!(StoreError::Io(io::Error::from(io::ErrorKind::Interrupted)).is_transient());
+        assert!(
+            StoreError::from(redb::Error::Io(io::Error::from(io::ErrorKind::TimedOut)))
+                .is_transient()
+        );
+
+        // Retrying can't release a database held by another process
+        assert!(!StoreError::from(redb::Error::DatabaseAlreadyOpen).is_transient());
+        assert!(!StoreError::NotFound("beacon state".to_string()).is_transient());
+    }
+}
@@ -0,0 +1,89 @@
+use ream_consensus::checkpoint::Checkpoint;
+use tokio::sync::broadcast;
+
+/// Number of finalized checkpoints buffered for a subscriber that has not received them yet.
+pub const FINALITY_CHANNEL_CAPACITY: usize = 16;
+
+/// Broadcasts every finalized checkpoint written to the [`ReamDB`](crate::db::ReamDB) once its
+/// write is committed, so caches keyed by roots from before finalization can drop stale entries.
+/// Clones share the same subscribers.
+#[derive(Debug, Clone)]
+pub struct FinalityNotifier {
+    sender: broadcast::Sender<Checkpoint>,
+}
+
+impl Default for FinalityNotifier {
+    fn default() -> Self {
+        Self::new(FINALITY_CHANNEL_CAPACITY)
+    }
+}
+
+impl FinalityNotifier {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    /// Receive the finalized checkpoints written from now on. A subscriber that falls more than
+    /// the channel capacity behind skips the oldest checkpoints, only the latest one matters.
+    pub fn subscribe(&self) -> broadcast::Receiver<Checkpoint> {
+        self.sender.subscribe()
+    }
+
+    pub(crate) fn notify(&self, checkpoint: Checkpoint) {
+        // Without subscribers there is nobody to notify
+        let _ = self.sender.send(checkpoint);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use tempdir::TempDir;
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    use super::*;
+    use crate::{db::ReamDB, errors::StoreError, tables::Field};
+
+    #[test]
+    fn test_finalized_checkpoint_update_is_delivered() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_finalized_checkpoint_update_is_delivered")?;
+        let db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+        let mut finalized = db.finality_notifier.subscribe();
+
+        let checkpoint = Checkpoint {
+            epoch: 3,
+            root: B256::repeat_byte(1),
+        };
+        db.finalized_checkpoint_provider().insert(checkpoint)?;
+        assert_eq!(finalized.try_recv(), Ok(checkpoint));
+
+        // Writes through a batch are announced once it is committed, through any clone
+        let checkpoint = Checkpoint {
+            epoch: 4,
+            root: B256::repeat_byte(2),
+        };
+        db.clone()
+            .write_batch(|batch| batch.insert_finalized_checkpoint(checkpoint))?;
+        assert_eq!(finalized.try_recv(), Ok(checkpoint));
+        assert_eq!(finalized.try_recv(), Err(TryRecvError::Empty));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_aborted_batch_is_not_delivered() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_aborted_batch_is_not_delivered")?;
+        let db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+        let mut finalized = db.finality_notifier.subscribe();
+
+        let result = db.write_batch(|batch| {
+            batch.insert_finalized_checkpoint(Checkpoint::default())?;
+            Err::<(), _>(StoreError::NotFound("simulated failure".to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(finalized.try_recv(), Err(TryRecvError::Empty));
+
+        Ok(())
+    }
+}
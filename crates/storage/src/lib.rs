@@ -1,4 +1,6 @@
 pub mod db;
 pub mod dir;
 pub mod errors;
+pub mod finality;
+pub mod snapshot;
 pub mod tables;
@@ -0,0 +1,166 @@
+//! Export and import of the canonical chain since finalization, for backups and migrations.
+//!
+//! A snapshot is the ``SNAPSHOT_MAGIC`` bytes and the little endian ``SNAPSHOT_VERSION``,
+//! followed by a snappy frame stream of length prefixed SSZ records: the justified and finalized
+//! checkpoints, the finalized state, the number of blocks and the blocks from the finalized block
+//! up to the highest indexed block, oldest first.
+
+use std::io::{Read, Write};
+
+use alloy_primitives::B256;
+use ream_consensus::{
+    checkpoint::Checkpoint,
+    electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
+};
+use snap::{read::FrameDecoder, write::FrameEncoder};
+use ssz::{Decode, Encode};
+use tree_hash::TreeHash;
+
+use crate::{
+    db::ReamDB,
+    errors::StoreError,
+    tables::{Field, Table},
+};
+
+pub const SNAPSHOT_MAGIC: [u8; 8] = *b"REAMSNAP";
+
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+fn write_record(writer: &mut impl Write, record: &impl Encode) -> Result<(), StoreError> {
+    let bytes = record.as_ssz_bytes();
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+fn read_record<T: Decode>(reader: &mut impl Read) -> Result<T, StoreError> {
+    let mut length = [0; 8];
+    reader.read_exact(&mut length)?;
+    let mut bytes = vec![0; u64::from_le_bytes(length) as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(T::from_ssz_bytes(&bytes)?)
+}
+
+impl ReamDB {
+    /// Write a snapshot of the finalized state and the canonical blocks since finalization to
+    /// ``writer``, see the [module documentation](self) for the format.
+    pub fn export_snapshot(&self, mut writer: impl Write) -> Result<(), StoreError> {
+        let justified_checkpoint = self.justified_checkpoint_provider().get()?;
+        let finalized_checkpoint = self.finalized_checkpoint_provider().get()?;
+        let finalized_state = self
+            .beacon_state_provider()
+            .get(finalized_checkpoint.root)?
+            .ok_or_else(|| {
+                StoreError::NotFound(format!("beacon state {:?}", finalized_checkpoint.root))
+            })?;
+
+        // Walk back from the highest indexed block to the finalized block
+        let beacon_block_provider = self.beacon_block_provider();
+        let mut blocks = vec![];
+        let mut block_root = self
+            .slot_index_provider()
+            .get_highest_root()?
+            .unwrap_or(finalized_checkpoint.root);
+        loop {
+            let block = beacon_block_provider
+                .get(block_root)?
+                .ok_or_else(|| StoreError::NotFound(format!("beacon block {block_root:?}")))?;
+            let parent_root = block.message.parent_root;
+            let slot = block.message.slot;
+            blocks.push(block);
+            if block_root == finalized_checkpoint.root {
+                break;
+            }
+            if slot <= finalized_state.slot {
+                return Err(StoreError::Snapshot(format!(
+                    "highest indexed block does not descend from the finalized block {:?}",
+                    finalized_checkpoint.root
+                )));
+            }
+            block_root = parent_root;
+        }
+        blocks.reverse();
+
+        writer.write_all(&SNAPSHOT_MAGIC)?;
+        writer.write_all(&SNAPSHOT_VERSION.to_le_bytes())?;
+
+        let mut encoder = FrameEncoder::new(writer);
+        write_record(&mut encoder, &justified_checkpoint)?;
+        write_record(&mut encoder, &finalized_checkpoint)?;
+        write_record(&mut encoder, &finalized_state)?;
+        write_record(&mut encoder, &(blocks.len() as u64))?;
+        for block in &blocks {
+            write_record(&mut encoder, block)?;
+        }
+        encoder.flush()?;
+
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`ReamDB::export_snapshot`] from ``reader`` and store it. The
+    /// blocks have to form a chain starting at the finalized block, which has to commit to the
+    /// finalized state, otherwise nothing is stored.
+    pub fn import_snapshot(&self, mut reader: impl Read) -> Result<(), StoreError> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != SNAPSHOT_MAGIC {
+            return Err(StoreError::Snapshot("not a snapshot".to_string()));
+        }
+        let mut version = [0; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != SNAPSHOT_VERSION {
+            return Err(StoreError::Snapshot(format!(
+                "unsupported snapshot version {version}"
+            )));
+        }
+
+        let mut decoder = FrameDecoder::new(reader);
+        let justified_checkpoint: Checkpoint = read_record(&mut decoder)?;
+        let finalized_checkpoint: Checkpoint = read_record(&mut decoder)?;
+        let finalized_state: BeaconState = read_record(&mut decoder)?;
+        let block_count: u64 = read_record(&mut decoder)?;
+        let blocks = (0..block_count)
+            .map(|_| read_record::<SignedBeaconBlock>(&mut decoder))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let Some(finalized_block) = blocks.first() else {
+            return Err(StoreError::Snapshot("snapshot has no blocks".to_string()));
+        };
+        if finalized_block.message.tree_hash_root() != finalized_checkpoint.root {
+            return Err(StoreError::Snapshot(
+                "first block is not the finalized block".to_string(),
+            ));
+        }
+        if finalized_block.message.state_root != finalized_state.tree_hash_root() {
+            return Err(StoreError::Snapshot(
+                "finalized block does not commit to the finalized state".to_string(),
+            ));
+        }
+
+        let mut block_roots = Vec::with_capacity(blocks.len());
+        let mut parent_root: Option<B256> = None;
+        for block in &blocks {
+            if parent_root.is_some_and(|parent_root| block.message.parent_root != parent_root) {
+                return Err(StoreError::Snapshot(format!(
+                    "block at slot {} does not link to its predecessor",
+                    block.message.slot
+                )));
+            }
+            let block_root = block.message.tree_hash_root();
+            block_roots.push(block_root);
+            parent_root = Some(block_root);
+        }
+
+        self.write_batch(|batch| {
+            // The snapshot holds the canonical chain, so every block is indexed by its slot
+            for (block_root, block) in block_roots.into_iter().zip(blocks) {
+                batch.insert_slot_index(block.message.slot, block_root)?;
+                batch.insert_block(block_root, block)?;
+            }
+            batch.insert_state(finalized_checkpoint.root, finalized_state)?;
+            batch.insert_justified_checkpoint(justified_checkpoint)?;
+            batch.insert_finalized_checkpoint(finalized_checkpoint)
+        })
+    }
+}
@@ -3,7 +3,6 @@ use std::sync::Arc;
 use alloy_primitives::B256;
 use ream_consensus::electra::beacon_block::SignedBeaconBlock;
 use redb::{Database, Durability, TableDefinition};
-use tree_hash::TreeHash;
 
 use super::{
     MultimapTable, SSZEncoding, Table, parent_root_index::ParentRootIndexMultimapTable,
@@ -37,7 +36,7 @@ impl Table for BeaconBlockTable {
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
         // insert entry to slot_index table
-        let block_root = value.tree_hash_root();
+        let block_root = key;
         let slot_index_table = SlotIndexTable {
             db: self.db.clone(),
         };
@@ -0,0 +1,45 @@
+use std::{path::PathBuf, sync::Arc};
+
+use ream_consensus::blob_sidecar::{BlobIdentifier, BlobSidecar};
+use redb::Database;
+
+use super::{Table, beacon_block::BeaconBlockTable, blobs_and_proofs::BlobsAndProofsTable};
+use crate::errors::StoreError;
+
+/// Read only view assembling blob sidecars from the Beacon Block table and the stored blobs
+///
+/// Key: BlobIdentifier
+/// Value: BlobSidecar
+pub struct BlobSidecarTable {
+    pub db: Arc<Database>,
+    pub data_dir: PathBuf,
+}
+
+impl BlobSidecarTable {
+    /// Return the sidecar for ``key``, or ``None`` if its block or blob is not stored or the block
+    /// has no commitment at ``key.index``.
+    pub fn get(&self, key: BlobIdentifier) -> Result<Option<BlobSidecar>, StoreError> {
+        let beacon_block_table = BeaconBlockTable {
+            db: self.db.clone(),
+        };
+        let Some(block) = beacon_block_table.get(key.block_root)? else {
+            return Ok(None);
+        };
+        if key.index >= block.message.body.blob_kzg_commitments.len() as u64 {
+            return Ok(None);
+        }
+
+        let blobs_and_proofs_table = BlobsAndProofsTable {
+            data_dir: self.data_dir.clone(),
+        };
+        let index = key.index;
+        let Some(blob_and_proof) = blobs_and_proofs_table.get(key)? else {
+            return Ok(None);
+        };
+
+        block
+            .blob_sidecar(blob_and_proof, index)
+            .map(Some)
+            .map_err(|err| StoreError::BlobSidecar(err.to_string()))
+    }
+}
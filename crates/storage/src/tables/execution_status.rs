@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+use redb::{Database, Durability, TableDefinition};
+use ssz_derive::{Decode, Encode};
+
+use super::{SSZEncoding, Table};
+use crate::errors::StoreError;
+
+/// Table definition for the Execution Status table
+///
+/// Key: block_root
+/// Value: ExecutionStatus
+pub const EXECUTION_STATUS_TABLE: TableDefinition<SSZEncoding<B256>, SSZEncoding<ExecutionStatus>> =
+    TableDefinition::new("execution_status");
+
+/// Whether the execution payload of a block was verified by the execution layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[ssz(enum_behaviour = "tag")]
+pub enum ExecutionStatus {
+    /// The execution layer verified the payload, and so every ancestor payload.
+    Valid,
+    /// The block was imported without the execution layer verifying its payload.
+    Optimistic,
+    /// The execution layer rejected the payload.
+    Invalid,
+}
+
+pub struct ExecutionStatusTable {
+    pub db: Arc<Database>,
+}
+
+impl Table for ExecutionStatusTable {
+    type Key = B256;
+
+    type Value = ExecutionStatus;
+
+    fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+
+        let table = read_txn.open_table(EXECUTION_STATUS_TABLE)?;
+        let result = table.get(key)?;
+        Ok(result.map(|res| res.value()))
+    }
+
+    fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        let mut table = write_txn.open_table(EXECUTION_STATUS_TABLE)?;
+        table.insert(key, value)?;
+        drop(table);
+        write_txn.commit()?;
+        Ok(())
+    }
+}
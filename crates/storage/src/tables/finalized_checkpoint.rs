@@ -4,7 +4,7 @@ use ream_consensus::checkpoint::Checkpoint;
 use redb::{Database, Durability, TableDefinition};
 
 use super::{Field, SSZEncoding};
-use crate::errors::StoreError;
+use crate::{errors::StoreError, finality::FinalityNotifier};
 
 /// Table definition for the Finalized_Checkpoint table
 ///
@@ -16,6 +16,7 @@ pub const FINALIZED_CHECKPOINT_FIELD_KEY: &str = "finalized_checkpoint_key";
 
 pub struct FinalizedCheckpointField {
     pub db: Arc<Database>,
+    pub finality_notifier: FinalityNotifier,
 }
 
 impl Field for FinalizedCheckpointField {
@@ -38,6 +39,7 @@ impl Field for FinalizedCheckpointField {
         table.insert(FINALIZED_CHECKPOINT_FIELD_KEY, value)?;
         drop(table);
         write_txn.commit()?;
+        self.finality_notifier.notify(value);
         Ok(())
     }
 }
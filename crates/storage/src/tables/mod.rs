@@ -1,9 +1,11 @@
 pub mod beacon_block;
 pub mod beacon_state;
+pub mod blob_sidecar;
 pub mod blobs_and_proofs;
 pub mod block_timeliness;
 pub mod checkpoint_states;
 pub mod equivocating_indices;
+pub mod execution_status;
 pub mod finalized_checkpoint;
 pub mod genesis_time;
 pub mod justified_checkpoint;
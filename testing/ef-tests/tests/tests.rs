@@ -47,6 +47,15 @@ use ream_consensus::{
 };
 use ream_merkle::is_valid_normalized_merkle_branch;
 
+/// The pre-state of the ``one_basic_attestation`` operations test, a mainnet state with a full
+/// validator set that the API and storage tests build on.
+fn read_state() -> BeaconState {
+    utils::read_ssz_snappy(std::path::Path::new(
+        "mainnet/tests/mainnet/electra/operations/attestation/pyspec_tests/one_basic_attestation/pre.ssz_snappy",
+    ))
+    .expect("cannot find test asset (pre.ssz_snappy)")
+}
+
 // General consensus types
 test_consensus_type!(Attestation);
 test_consensus_type!(AttestationData);
@@ -229,3 +238,3873 @@ test_sanity_blocks!(test_random, "random/random");
 
 // Testing finality
 test_sanity_blocks!(test_finality, "finality/finality");
+
+// Testing signature domains
+#[cfg(test)]
+mod tests_get_domain {
+    use ream_consensus::{
+        constants::{CAPELLA_FORK_VERSION, DOMAIN_VOLUNTARY_EXIT},
+        misc::compute_domain,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_get_domain_with_fork_version_override() {
+        let base_path = "mainnet/tests/mainnet/electra/operations/voluntary_exit/pyspec_tests";
+        for entry in std::fs::read_dir(base_path).unwrap() {
+            let case_dir = entry.unwrap().path();
+            if !case_dir.is_dir() {
+                continue;
+            }
+            let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+                .expect("cannot find test asset(pre.ssz_snappy)");
+
+            let derived_domain = state.get_domain(DOMAIN_VOLUNTARY_EXIT, None, None);
+            assert_eq!(
+                derived_domain,
+                compute_domain(
+                    DOMAIN_VOLUNTARY_EXIT,
+                    Some(state.fork.current_version),
+                    Some(state.genesis_validators_root),
+                )
+            );
+
+            let pinned_domain =
+                state.get_domain(DOMAIN_VOLUNTARY_EXIT, None, Some(CAPELLA_FORK_VERSION));
+            assert_eq!(
+                pinned_domain,
+                compute_domain(
+                    DOMAIN_VOLUNTARY_EXIT,
+                    Some(CAPELLA_FORK_VERSION),
+                    Some(state.genesis_validators_root),
+                )
+            );
+            assert_eq!(pinned_domain[..4], DOMAIN_VOLUNTARY_EXIT[..]);
+            assert_ne!(derived_domain, pinned_domain);
+        }
+    }
+}
+// Testing the operation pool rejection metrics
+#[cfg(test)]
+mod tests_operation_pool_rejection_metrics {
+    use std::sync::Arc;
+
+    use actix_web::{App, test};
+    use ream_operation_pool::{OperationPool, RejectionStats};
+    use ream_rpc::metrics::{METRICS, get_metrics};
+
+    use super::*;
+
+    fn read_case<T: ssz::Decode>(operation: &str, case: &str, file: &str) -> T {
+        utils::read_ssz_snappy(
+            &std::path::Path::new(&format!(
+                "mainnet/tests/mainnet/electra/operations/{operation}/pyspec_tests/{case}"
+            ))
+            .join(file),
+        )
+        .expect("cannot find test asset")
+    }
+
+    #[actix_web::test]
+    async fn test_invalid_operations_are_counted_by_reason() {
+        let operation_pool = Arc::new(OperationPool::default());
+        METRICS.set_operation_pool(operation_pool.clone());
+
+        for case in [
+            "invalid_incorrect_signature",
+            "invalid_validator_exit_in_progress",
+        ] {
+            let state: BeaconState = read_case("voluntary_exit", case, "pre.ssz_snappy");
+            let signed_voluntary_exit: SignedVoluntaryExit =
+                read_case("voluntary_exit", case, "voluntary_exit.ssz_snappy");
+            assert!(
+                operation_pool
+                    .validate_and_insert_signed_voluntary_exit(&state, signed_voluntary_exit)
+                    .is_err(),
+                "{case}"
+            );
+        }
+
+        let state: BeaconState = read_case(
+            "attester_slashing",
+            "invalid_incorrect_sig_1",
+            "pre.ssz_snappy",
+        );
+        let attester_slashing: AttesterSlashing = read_case(
+            "attester_slashing",
+            "invalid_incorrect_sig_1",
+            "attester_slashing.ssz_snappy",
+        );
+        assert!(
+            operation_pool
+                .validate_and_insert_attester_slashing(&state, attester_slashing)
+                .is_err()
+        );
+
+        // Two copies of the same vote are not slashable
+        let state: BeaconState = read_case("attester_slashing", "basic_double", "pre.ssz_snappy");
+        let mut attester_slashing: AttesterSlashing = read_case(
+            "attester_slashing",
+            "basic_double",
+            "attester_slashing.ssz_snappy",
+        );
+        attester_slashing.attestation_2 = attester_slashing.attestation_1.clone();
+        assert!(
+            operation_pool
+                .validate_and_insert_attester_slashing(&state, attester_slashing)
+                .is_err()
+        );
+
+        assert_eq!(
+            operation_pool.rejection_stats(),
+            RejectionStats {
+                invalid_signature: 2,
+                stale_voluntary_exit: 1,
+                invalid_operation: 1,
+            }
+        );
+        assert!(operation_pool.get_attester_slashings().is_empty());
+        assert!(operation_pool.get_signed_voluntary_exits().is_empty());
+
+        let app = test::init_service(App::new().service(get_metrics)).await;
+        let request = test::TestRequest::get().uri("/metrics").to_request();
+        let body = test::call_and_read_body(&app, request).await;
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        for line in [
+            r#"operation_pool_rejections_total{reason="invalid_signature"} 2"#,
+            r#"operation_pool_rejections_total{reason="stale_voluntary_exit"} 1"#,
+            r#"operation_pool_rejections_total{reason="invalid_operation"} 1"#,
+        ] {
+            assert!(body.contains(line), "{line} in {body}");
+        }
+    }
+}
+
+// Testing indexed attestation signatures
+#[cfg(test)]
+mod tests_indexed_attestation_verify_signature {
+    use super::*;
+
+    #[test]
+    fn test_verify_signature() {
+        let base_path = "mainnet/tests/mainnet/electra/operations/attester_slashing/pyspec_tests";
+        for entry in std::fs::read_dir(base_path).unwrap() {
+            let case_dir = entry.unwrap().path();
+            // Only successful cases are guaranteed to carry validly signed attestations
+            if !case_dir.is_dir() || !case_dir.join("post.ssz_snappy").exists() {
+                continue;
+            }
+            let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+                .expect("cannot find test asset(pre.ssz_snappy)");
+            let attester_slashing: AttesterSlashing =
+                utils::read_ssz_snappy(&case_dir.join("attester_slashing.ssz_snappy"))
+                    .expect("cannot find test asset(attester_slashing.ssz_snappy)");
+
+            for attestation in [
+                attester_slashing.attestation_1,
+                attester_slashing.attestation_2,
+            ] {
+                assert!(attestation.verify_signature(&state).unwrap());
+
+                let mut tampered = attestation.clone();
+                tampered.data.slot += 1;
+                assert!(!tampered.verify_signature(&state).unwrap());
+
+                let mut empty = attestation;
+                empty.attesting_indices = Default::default();
+                assert!(!empty.verify_signature(&state).unwrap());
+            }
+        }
+    }
+}
+
+// Testing committee public key aggregation
+#[cfg(test)]
+mod tests_aggregate_committee {
+    use ream_bls::{PublicKey, errors::BLSError, traits::Verifiable};
+    use ream_consensus::{
+        constants::DOMAIN_BEACON_ATTESTER, electra::beacon_state::eth_aggregate_public_keys,
+        misc::compute_signing_root,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_aggregate_committee() {
+        assert_eq!(
+            PublicKey::aggregate_committee(&[]),
+            Err(BLSError::EmptyAggregate)
+        );
+
+        let base_path = "mainnet/tests/mainnet/electra/operations/attester_slashing/pyspec_tests";
+        for entry in std::fs::read_dir(base_path).unwrap() {
+            let case_dir = entry.unwrap().path();
+            // Only successful cases are guaranteed to carry validly signed attestations
+            if !case_dir.is_dir() || !case_dir.join("post.ssz_snappy").exists() {
+                continue;
+            }
+            let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+                .expect("cannot find test asset(pre.ssz_snappy)");
+            let attester_slashing: AttesterSlashing =
+                utils::read_ssz_snappy(&case_dir.join("attester_slashing.ssz_snappy"))
+                    .expect("cannot find test asset(attester_slashing.ssz_snappy)");
+
+            for attestation in [
+                attester_slashing.attestation_1,
+                attester_slashing.attestation_2,
+            ] {
+                let public_keys = attestation
+                    .attesting_indices
+                    .iter()
+                    .map(|&index| state.validators[index as usize].public_key.clone())
+                    .collect::<Vec<_>>();
+
+                assert_eq!(
+                    PublicKey::aggregate_committee(&public_keys[..1]).unwrap(),
+                    public_keys[0]
+                );
+
+                let aggregate_public_key = PublicKey::aggregate_committee(&public_keys).unwrap();
+                assert_eq!(
+                    aggregate_public_key,
+                    eth_aggregate_public_keys(&public_keys.iter().collect::<Vec<_>>()).unwrap()
+                );
+
+                let domain = state.get_domain(
+                    DOMAIN_BEACON_ATTESTER,
+                    Some(attestation.data.target.epoch),
+                    None,
+                );
+                let signing_root = compute_signing_root(&attestation.data, domain);
+                assert!(
+                    attestation
+                        .signature
+                        .verify(&aggregate_public_key, signing_root.as_ref())
+                        .unwrap()
+                );
+            }
+        }
+    }
+}
+
+// Testing parallel indexed attestation verification
+#[cfg(test)]
+mod tests_verify_indexed_attestations_parallel {
+    use ream_consensus::indexed_attestation::{
+        IndexedAttestation, verify_indexed_attestations_parallel,
+    };
+
+    use super::*;
+
+    fn load_case() -> (BeaconState, AttesterSlashing) {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/operations/attester_slashing/pyspec_tests/basic_surround",
+        );
+        let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+            .expect("cannot find test asset(pre.ssz_snappy)");
+        let attester_slashing: AttesterSlashing =
+            utils::read_ssz_snappy(&case_dir.join("attester_slashing.ssz_snappy"))
+                .expect("cannot find test asset(attester_slashing.ssz_snappy)");
+        (state, attester_slashing)
+    }
+
+    fn verify_serial(
+        state: &BeaconState,
+        indexed_attestations: &[IndexedAttestation],
+    ) -> anyhow::Result<bool> {
+        for indexed_attestation in indexed_attestations {
+            if !state.is_valid_indexed_attestation(indexed_attestation)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    #[test]
+    fn test_verify_128_attestations() {
+        let (state, attester_slashing) = load_case();
+        let indexed_attestations = [
+            attester_slashing.attestation_1,
+            attester_slashing.attestation_2,
+        ]
+        .into_iter()
+        .cycle()
+        .take(128)
+        .collect::<Vec<_>>();
+
+        assert!(verify_serial(&state, &indexed_attestations).unwrap());
+        assert!(verify_indexed_attestations_parallel(&state, &indexed_attestations).is_ok());
+    }
+
+    #[test]
+    fn test_one_bad_signature_fails() {
+        let (state, attester_slashing) = load_case();
+        let mut indexed_attestations = vec![attester_slashing.attestation_1.clone(); 128];
+        indexed_attestations[77].signature = attester_slashing.attestation_2.signature;
+
+        assert!(!verify_serial(&state, &indexed_attestations).unwrap());
+        assert!(verify_indexed_attestations_parallel(&state, &indexed_attestations).is_err());
+    }
+
+    #[test]
+    fn test_one_corrupted_signature_fails() {
+        let (state, attester_slashing) = load_case();
+        let mut indexed_attestations = vec![attester_slashing.attestation_1; 128];
+        let mut signature = indexed_attestations[100].signature.inner.to_vec();
+        signature[95] ^= 1;
+        indexed_attestations[100].signature.inner = signature.into();
+
+        // A corrupted signature may not even decode, so the serial check may fail with an error
+        assert!(!verify_serial(&state, &indexed_attestations).unwrap_or(false));
+        assert!(verify_indexed_attestations_parallel(&state, &indexed_attestations).is_err());
+    }
+}
+
+// Testing attestation selection for block inclusion
+#[cfg(test)]
+mod tests_best_attestations {
+    use ream_operation_pool::OperationPool;
+
+    use super::*;
+
+    #[test]
+    fn test_best_attestations_filters_by_state() {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/operations/attestation/pyspec_tests/one_basic_attestation",
+        );
+        let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+            .expect("cannot find test asset(pre.ssz_snappy)");
+        let attestation: Attestation =
+            utils::read_ssz_snappy(&case_dir.join("attestation.ssz_snappy"))
+                .expect("cannot find test asset(attestation.ssz_snappy)");
+
+        let operation_pool = OperationPool::default();
+        operation_pool.insert_attestation(attestation.clone());
+        assert_eq!(
+            operation_pool.best_attestations(&state, 8),
+            vec![attestation.clone()]
+        );
+        assert!(operation_pool.best_attestations(&state, 0).is_empty());
+
+        // Once included, the attestation adds no new participation
+        let mut post_state = state.clone();
+        post_state.process_attestation(&attestation).unwrap();
+        assert!(operation_pool.best_attestations(&post_state, 8).is_empty());
+
+        let mut wrong_source = attestation;
+        wrong_source.data.source.epoch += 1;
+        let operation_pool = OperationPool::default();
+        operation_pool.insert_attestation(wrong_source);
+        assert!(operation_pool.best_attestations(&state, 8).is_empty());
+    }
+}
+
+// Testing attestation reward components
+#[cfg(test)]
+mod tests_attestation_deltas {
+    use ream_consensus::constants::{
+        PARTICIPATION_FLAG_WEIGHTS, TIMELY_HEAD_FLAG_INDEX, TIMELY_SOURCE_FLAG_INDEX,
+        TIMELY_TARGET_FLAG_INDEX,
+    };
+    use ssz_derive::Decode;
+    use ssz_types::{VariableList, typenum::U1099511627776};
+
+    use super::*;
+
+    #[derive(Decode, Debug)]
+    pub struct Deltas {
+        pub rewards: VariableList<u64, U1099511627776>,
+        pub penalties: VariableList<u64, U1099511627776>,
+    }
+
+    #[test]
+    fn test_attestation_deltas() {
+        for handler in ["basic", "leak", "random"] {
+            let base_path = format!("mainnet/tests/mainnet/electra/rewards/{handler}/pyspec_tests");
+            for entry in std::fs::read_dir(base_path).unwrap() {
+                let case_dir = entry.unwrap().path();
+                if !case_dir.is_dir() {
+                    continue;
+                }
+                let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+                    .expect("cannot find test asset(pre.ssz_snappy)");
+                let deltas = state.get_attestation_deltas().unwrap();
+
+                for (component, file_name) in [
+                    (TIMELY_SOURCE_FLAG_INDEX as usize, "source_deltas"),
+                    (TIMELY_TARGET_FLAG_INDEX as usize, "target_deltas"),
+                    (TIMELY_HEAD_FLAG_INDEX as usize, "head_deltas"),
+                    (
+                        PARTICIPATION_FLAG_WEIGHTS.len(),
+                        "inactivity_penalty_deltas",
+                    ),
+                ] {
+                    let expected: Deltas =
+                        utils::read_ssz_snappy(&case_dir.join(format!("{file_name}.ssz_snappy")))
+                            .expect("cannot find test asset(deltas)");
+                    assert_eq!(deltas[component].0, expected.rewards.to_vec());
+                    assert_eq!(deltas[component].1, expected.penalties.to_vec());
+                }
+
+                // Every unslashed participant earns exactly the ideal reward for its balance
+                let previous_epoch = state.get_previous_epoch();
+                for flag_index in [
+                    TIMELY_SOURCE_FLAG_INDEX,
+                    TIMELY_TARGET_FLAG_INDEX,
+                    TIMELY_HEAD_FLAG_INDEX,
+                ] {
+                    let participants = state
+                        .get_unslashed_participating_indices(flag_index, previous_epoch)
+                        .unwrap();
+                    for index in state.get_eligible_validator_indices().unwrap() {
+                        if !participants.contains(&index) {
+                            continue;
+                        }
+                        let effective_balance = state.validators[index as usize].effective_balance;
+                        let ideal_rewards = state
+                            .get_ideal_flag_index_rewards(&[effective_balance])
+                            .unwrap();
+                        assert_eq!(
+                            deltas[flag_index as usize].0[index as usize],
+                            ideal_rewards[0][flag_index as usize]
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Testing committee caching for attesting indices
+#[cfg(test)]
+mod tests_get_attesting_indices_with_cache {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_get_attesting_indices_with_cache() {
+        let base_path = "mainnet/tests/mainnet/electra/operations/attestation/pyspec_tests";
+        for entry in std::fs::read_dir(base_path).unwrap() {
+            let case_dir = entry.unwrap().path();
+            if !case_dir.is_dir() || !case_dir.join("post.ssz_snappy").exists() {
+                continue;
+            }
+            let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+                .expect("cannot find test asset(pre.ssz_snappy)");
+            let attestation: Attestation =
+                utils::read_ssz_snappy(&case_dir.join("attestation.ssz_snappy"))
+                    .expect("cannot find test asset(attestation.ssz_snappy)");
+
+            let expected = state.get_attesting_indices(&attestation).unwrap();
+            let mut committee_cache = HashMap::new();
+            for _ in 0..8 {
+                assert_eq!(
+                    state
+                        .get_attesting_indices_with_cache(&attestation, &mut committee_cache)
+                        .unwrap(),
+                    expected
+                );
+            }
+
+            // Each committee of the attestation is shuffled exactly once
+            let committee_count = attestation.committee_bits.num_set_bits();
+            assert_eq!(committee_cache.len(), committee_count);
+        }
+    }
+}
+
+// Testing SSZ export and import of stored blocks and states
+#[cfg(test)]
+mod tests_ream_db_ssz_export_import {
+    use ream_rpc::test_utils::TestDB;
+    use ream_storage::tables::Table;
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0",
+        );
+        let block: SignedBeaconBlock =
+            utils::read_ssz_snappy(&case_dir.join("serialized.ssz_snappy"))
+                .expect("cannot find test asset(serialized.ssz_snappy)");
+        let state_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/BeaconState/ssz_random/case_0",
+        );
+        let state: BeaconState = utils::read_ssz_snappy(&state_dir.join("serialized.ssz_snappy"))
+            .expect("cannot find test asset(serialized.ssz_snappy)");
+
+        let source = TestDB::new().unwrap();
+        let block_root = block.message.tree_hash_root();
+        source
+            .db
+            .beacon_block_provider()
+            .insert(block_root, block.clone())
+            .unwrap();
+        source
+            .db
+            .beacon_state_provider()
+            .insert(block_root, state.clone())
+            .unwrap();
+
+        let block_path = source.db.data_dir.join("block.ssz");
+        let state_path = source.db.data_dir.join("state.ssz");
+        source.db.export_block_ssz(block_root, &block_path).unwrap();
+        source.db.export_state_ssz(block_root, &state_path).unwrap();
+
+        let target = TestDB::new().unwrap();
+        assert_eq!(target.db.import_block_ssz(&block_path).unwrap(), block_root);
+        target.db.import_state_ssz(block_root, &state_path).unwrap();
+
+        assert_eq!(
+            target.db.beacon_block_provider().get(block_root).unwrap(),
+            Some(block)
+        );
+        assert_eq!(
+            target.db.beacon_state_provider().get(block_root).unwrap(),
+            Some(state)
+        );
+    }
+}
+
+// Testing pruning of finalized states and blocks
+#[cfg(test)]
+mod tests_ream_db_prune_finalized {
+    use alloy_primitives::B256;
+    use ream_consensus::constants::SLOTS_PER_EPOCH;
+    use ream_rpc::test_utils::TestDB;
+    use ream_storage::{
+        db::PruneReport,
+        tables::{Field, Table},
+    };
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    #[test]
+    fn test_prune_finalized() {
+        let block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset(serialized.ssz_snappy)");
+        let state = read_state();
+
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let store_block = |slot: u64, parent_root: B256| {
+            let mut block = block.clone();
+            block.message.slot = slot;
+            block.message.parent_root = parent_root;
+            block.message.state_root = B256::with_last_byte(slot as u8);
+            let block_root = block.message.tree_hash_root();
+            db.beacon_block_provider()
+                .insert(block_root, block)
+                .unwrap();
+
+            let mut state = state.clone();
+            state.slot = slot;
+            db.beacon_state_provider()
+                .insert(block_root, state)
+                .unwrap();
+            block_root
+        };
+
+        // Two blocks per epoch for four epochs, plus an orphan in epoch 1
+        let mut canonical_roots = vec![];
+        let mut parent_root = B256::ZERO;
+        for epoch in 0..4 {
+            for offset in [0, 5] {
+                parent_root = store_block(epoch * SLOTS_PER_EPOCH + offset, parent_root);
+                canonical_roots.push(parent_root);
+            }
+        }
+        let orphan_root = store_block(SLOTS_PER_EPOCH + 6, canonical_roots[2]);
+
+        let finalized_checkpoint = Checkpoint {
+            epoch: 3,
+            root: canonical_roots[6],
+        };
+        test_db.set_checkpoints(finalized_checkpoint).unwrap();
+
+        // Everything before epoch 2 is outside the retention window
+        assert_eq!(
+            db.prune_finalized(1).unwrap(),
+            PruneReport {
+                pruned_states: 3,
+                pruned_blocks: 1,
+            }
+        );
+
+        let has_state = |block_root| {
+            db.beacon_state_provider()
+                .get(block_root)
+                .unwrap()
+                .is_some()
+        };
+        let has_block = |block_root| {
+            db.beacon_block_provider()
+                .get(block_root)
+                .unwrap()
+                .is_some()
+        };
+        let expected_states = [true, false, true, false, true, true, true, true];
+        for (block_root, expected) in canonical_roots.iter().zip(expected_states) {
+            assert!(has_block(*block_root));
+            assert_eq!(has_state(*block_root), expected);
+        }
+        assert!(!has_block(orphan_root));
+        assert!(!has_state(orphan_root));
+        assert_eq!(
+            db.slot_index_provider().get(SLOTS_PER_EPOCH + 6).unwrap(),
+            None
+        );
+
+        // Pruning is idempotent
+        assert_eq!(db.prune_finalized(1).unwrap(), PruneReport::default());
+    }
+}
+
+// Testing historical state reconstruction from the nearest stored state
+#[cfg(test)]
+mod tests_reconstruct_state_at {
+    use std::fs;
+
+    use ream_consensus::execution_engine::mock_engine::MockExecutionEngine;
+    use ream_rpc::test_utils::TestDB;
+    use ream_storage::tables::Table;
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reconstruct_state_at() {
+        #[derive(Debug, serde::Deserialize)]
+        struct MetaData {
+            blocks_count: usize,
+        }
+
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/random/random/pyspec_tests/randomized_0",
+        );
+        let meta: MetaData = serde_yaml::from_str(
+            &fs::read_to_string(case_dir.join("meta.yaml")).expect("Failed to read meta.yaml"),
+        )
+        .expect("Failed to parse meta.yaml");
+        let mut state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+            .expect("cannot find test asset (pre.ssz_snappy)");
+
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let mut pre_header = state.latest_block_header.clone();
+        pre_header.state_root = state.tree_hash_root();
+        let pre_root = pre_header.tree_hash_root();
+        db.slot_index_provider()
+            .insert(state.slot, pre_root)
+            .unwrap();
+        db.beacon_state_provider()
+            .insert(pre_root, state.clone())
+            .unwrap();
+
+        let mock_engine = Some(MockExecutionEngine::new());
+        let mut stored = vec![];
+        for i in 0..meta.blocks_count {
+            let block: SignedBeaconBlock =
+                utils::read_ssz_snappy(&case_dir.join(format!("blocks_{i}.ssz_snappy")))
+                    .expect("cannot find test asset (blocks ssz_snappy)");
+            state
+                .state_transition(&block, true, &mock_engine)
+                .await
+                .unwrap();
+
+            let block_root = block.message.tree_hash_root();
+            db.slot_index_provider()
+                .insert(block.message.slot, block_root)
+                .unwrap();
+            db.beacon_block_provider()
+                .insert(block_root, block.clone())
+                .unwrap();
+            stored.push((block.message.slot, state.tree_hash_root()));
+        }
+
+        // Only the pre-state is stored, so every post-state has to be replayed
+        for (slot, state_root) in &stored {
+            let reconstructed = db.reconstruct_state_at(*slot).await.unwrap();
+            assert_eq!(reconstructed.slot, *slot);
+            assert_eq!(reconstructed.tree_hash_root(), *state_root);
+        }
+
+        // Empty slots after the last block are processed on top of it
+        let (last_slot, _) = stored.last().unwrap();
+        let mut expected = state.clone();
+        expected.process_slots(last_slot + 1).unwrap();
+        assert_eq!(
+            db.reconstruct_state_at(last_slot + 1)
+                .await
+                .unwrap()
+                .tree_hash_root(),
+            expected.tree_hash_root()
+        );
+    }
+}
+
+// Testing batched writes of blocks and states
+#[cfg(test)]
+mod tests_ream_db_write_batch {
+    use alloy_primitives::B256;
+    use ream_rpc::test_utils::TestDB;
+    use ream_storage::{errors::StoreError, tables::Table};
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    #[test]
+    fn test_write_batch_is_atomic() {
+        let block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset(serialized.ssz_snappy)");
+        let state = read_state();
+        let block_root = block.message.tree_hash_root();
+
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let result = db.write_batch(|batch| {
+            batch.insert_block(block_root, block.clone())?;
+            batch.insert_state(block_root, state.clone())?;
+            Err::<(), _>(StoreError::NotFound("simulated failure".to_string()))
+        });
+        assert!(matches!(result, Err(StoreError::NotFound(_))));
+
+        // Nothing from the failed batch landed
+        assert!(
+            db.beacon_block_provider()
+                .get(block_root)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            db.beacon_state_provider()
+                .get(block_root)
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            db.slot_index_provider().get(block.message.slot).unwrap(),
+            None
+        );
+
+        db.write_batch(|batch| {
+            batch.insert_block(block_root, block.clone())?;
+            batch.insert_state(block_root, state.clone())
+        })
+        .unwrap();
+        assert_eq!(
+            db.beacon_block_provider().get(block_root).unwrap(),
+            Some(block.clone())
+        );
+        assert_eq!(
+            db.beacon_state_provider().get(block_root).unwrap(),
+            Some(state)
+        );
+        // Only fork choice knows whether the block is canonical
+        assert_eq!(
+            db.slot_index_provider().get(block.message.slot).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_fork_block_does_not_replace_canonical_block() {
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset(serialized.ssz_snappy)");
+        block.message.parent_root = B256::ZERO;
+
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let parent_slot = block.message.slot;
+        let parent_root = test_db.insert_block(block.clone()).unwrap();
+        let insert_child = |slot: u64, parent_root: B256, proposer_index: u64| {
+            let mut child = block.clone();
+            child.message.slot = slot;
+            child.message.parent_root = parent_root;
+            child.message.proposer_index = proposer_index;
+            let child_root = child.message.tree_hash_root();
+            db.write_batch(|batch| batch.insert_block(child_root, child))
+                .unwrap();
+            child_root
+        };
+
+        // Two blocks at the same slot, and a child of the first one after a skipped slot
+        let canonical_root = insert_child(parent_slot + 1, parent_root, 1);
+        let fork_root = insert_child(parent_slot + 1, parent_root, 2);
+        let canonical_child_root = insert_child(parent_slot + 3, canonical_root, 1);
+
+        db.update_slot_index(canonical_child_root).unwrap();
+        let indexed_roots = || {
+            (parent_slot..=parent_slot + 3)
+                .map(|slot| db.slot_index_provider().get(slot).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            indexed_roots(),
+            [
+                Some(parent_root),
+                Some(canonical_root),
+                None,
+                Some(canonical_child_root)
+            ]
+        );
+
+        // The fork block is only indexed once it becomes the head
+        db.update_slot_index(fork_root).unwrap();
+        assert_eq!(
+            indexed_roots(),
+            [Some(parent_root), Some(fork_root), None, None]
+        );
+        db.update_slot_index(canonical_child_root).unwrap();
+        assert_eq!(
+            indexed_roots(),
+            [
+                Some(parent_root),
+                Some(canonical_root),
+                None,
+                Some(canonical_child_root)
+            ]
+        );
+    }
+}
+
+// Testing validation of the stored genesis against the configured network
+#[cfg(test)]
+mod tests_validate_genesis {
+    use alloy_primitives::B256;
+    use ream_network_spec::networks::{DEV, MAINNET};
+    use ream_rpc::test_utils::TestDB;
+    use ream_storage::{errors::StoreError, tables::Table};
+
+    use super::*;
+
+    #[test]
+    fn test_validate_genesis() {
+        let state_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/BeaconState/ssz_random/case_0",
+        );
+        let state: BeaconState = utils::read_ssz_snappy(&state_dir.join("serialized.ssz_snappy"))
+            .expect("cannot find test asset(serialized.ssz_snappy)");
+
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let block_root = B256::repeat_byte(1);
+        db.slot_index_provider()
+            .insert(state.slot, block_root)
+            .unwrap();
+        db.beacon_state_provider()
+            .insert(block_root, state.clone())
+            .unwrap();
+
+        assert_eq!(
+            db.genesis_validators_root().unwrap(),
+            state.genesis_validators_root
+        );
+        assert_eq!(
+            db.validate_genesis(Some(state.genesis_validators_root), state.genesis_time)
+                .unwrap(),
+            state.genesis_validators_root
+        );
+
+        // A configured root that differs from the stored state means the wrong network
+        let mut wrong_root = state.genesis_validators_root;
+        wrong_root.0[0] ^= 0xff;
+        assert!(matches!(
+            db.validate_genesis(Some(wrong_root), state.genesis_time),
+            Err(StoreError::GenesisMismatch(_))
+        ));
+        assert!(matches!(
+            db.validate_genesis(Some(state.genesis_validators_root), state.genesis_time + 1),
+            Err(StoreError::GenesisMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_stored_genesis_is_checked_against_the_network_spec() {
+        let state_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/BeaconState/ssz_random/case_0",
+        );
+        let state: BeaconState = utils::read_ssz_snappy(&state_dir.join("serialized.ssz_snappy"))
+            .expect("cannot find test asset(serialized.ssz_snappy)");
+
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let block_root = B256::repeat_byte(1);
+        db.slot_index_provider()
+            .insert(state.slot, block_root)
+            .unwrap();
+        db.beacon_state_provider()
+            .insert(block_root, state.clone())
+            .unwrap();
+
+        // A random state is not the mainnet genesis, however it was stored
+        assert!(matches!(
+            db.validate_genesis(MAINNET.genesis_validators_root(), state.genesis_time),
+            Err(StoreError::GenesisMismatch(_))
+        ));
+
+        // Without a known root only the genesis time is checked
+        assert_eq!(DEV.genesis_validators_root(), None);
+        assert_eq!(
+            db.validate_genesis(DEV.genesis_validators_root(), state.genesis_time)
+                .unwrap(),
+            state.genesis_validators_root
+        );
+    }
+}
+
+// Testing optimistic status propagation from execution payload statuses
+#[cfg(test)]
+mod tests_is_optimistic {
+    use alloy_primitives::B256;
+    use ream_consensus::checkpoint::Checkpoint;
+    use ream_rpc::test_utils::TestDB;
+    use ream_storage::{
+        db::ReamDB,
+        errors::StoreError,
+        tables::{Field, Table, execution_status::ExecutionStatus},
+    };
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    /// Store a chain of three consecutive blocks without an execution status, oldest first.
+    fn insert_chain(db: &ReamDB) -> Vec<B256> {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0",
+        );
+        let block: SignedBeaconBlock =
+            utils::read_ssz_snappy(&case_dir.join("serialized.ssz_snappy"))
+                .expect("cannot find test asset(serialized.ssz_snappy)");
+
+        let mut roots = vec![];
+        let mut parent_root = block.message.parent_root;
+        for offset in 0..3 {
+            let mut block = block.clone();
+            block.message.slot += offset;
+            block.message.parent_root = parent_root;
+            parent_root = block.message.tree_hash_root();
+            db.beacon_block_provider()
+                .insert(parent_root, block)
+                .unwrap();
+            roots.push(parent_root);
+        }
+        roots
+    }
+
+    #[test]
+    fn test_is_optimistic() {
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let roots = insert_chain(db);
+        let (ancestor, parent, head) = (roots[0], roots[1], roots[2]);
+
+        // An optimistic head
+        db.execution_status_provider()
+            .insert(head, ExecutionStatus::Optimistic)
+            .unwrap();
+        assert!(db.is_optimistic(head).unwrap());
+
+        // A validated head, even on top of an optimistic ancestor
+        db.execution_status_provider()
+            .insert(ancestor, ExecutionStatus::Optimistic)
+            .unwrap();
+        db.execution_status_provider()
+            .insert(head, ExecutionStatus::Valid)
+            .unwrap();
+        assert!(!db.is_optimistic(head).unwrap());
+
+        // A block without a status inherits the status of its optimistic ancestor
+        assert!(db.is_optimistic(parent).unwrap());
+        db.execution_status_provider()
+            .insert(ancestor, ExecutionStatus::Valid)
+            .unwrap();
+        assert!(!db.is_optimistic(parent).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_payload_is_an_error() {
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let roots = insert_chain(db);
+        let (ancestor, parent) = (roots[0], roots[1]);
+
+        db.execution_status_provider()
+            .insert(ancestor, ExecutionStatus::Invalid)
+            .unwrap();
+        assert!(matches!(
+            db.is_optimistic(ancestor),
+            Err(StoreError::InvalidExecutionPayload(_))
+        ));
+        assert!(matches!(
+            db.is_optimistic(parent),
+            Err(StoreError::InvalidExecutionPayload(_))
+        ));
+    }
+
+    #[test]
+    fn test_walk_stops_at_the_finalized_block() {
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let roots = insert_chain(db);
+        let (ancestor, parent, head) = (roots[0], roots[1], roots[2]);
+
+        db.execution_status_provider()
+            .insert(ancestor, ExecutionStatus::Optimistic)
+            .unwrap();
+        assert!(db.is_optimistic(head).unwrap());
+
+        // Nothing at or before the finalized block is optimistic
+        db.finalized_checkpoint_provider()
+            .insert(Checkpoint {
+                epoch: 0,
+                root: parent,
+            })
+            .unwrap();
+        assert!(!db.is_optimistic(head).unwrap());
+        assert!(!db.is_optimistic(parent).unwrap());
+    }
+
+    #[test]
+    fn test_set_execution_valid_confirms_optimistic_ancestors() {
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let roots = insert_chain(db);
+        let (ancestor, parent, head) = (roots[0], roots[1], roots[2]);
+
+        db.execution_status_provider()
+            .insert(ancestor, ExecutionStatus::Optimistic)
+            .unwrap();
+        db.execution_status_provider()
+            .insert(head, ExecutionStatus::Optimistic)
+            .unwrap();
+        assert!(db.is_optimistic(head).unwrap());
+
+        db.set_execution_valid(parent).unwrap();
+        assert!(!db.is_optimistic(parent).unwrap());
+        assert_eq!(
+            db.execution_status_provider().get(ancestor).unwrap(),
+            Some(ExecutionStatus::Valid)
+        );
+        // Descendants still wait for their own payload to be verified
+        assert!(db.is_optimistic(head).unwrap());
+
+        db.set_execution_valid(head).unwrap();
+        assert!(!db.is_optimistic(head).unwrap());
+
+        // A valid payload cannot sit on top of an invalid one
+        db.execution_status_provider()
+            .insert(ancestor, ExecutionStatus::Invalid)
+            .unwrap();
+        db.execution_status_provider()
+            .insert(parent, ExecutionStatus::Optimistic)
+            .unwrap();
+        assert!(matches!(
+            db.set_execution_valid(parent),
+            Err(StoreError::InvalidExecutionPayload(_))
+        ));
+        assert_eq!(
+            db.execution_status_provider().get(parent).unwrap(),
+            Some(ExecutionStatus::Optimistic)
+        );
+    }
+}
+
+// Testing the state root check on block import
+#[cfg(test)]
+mod tests_block_state_root_validation {
+    use alloy_primitives::B256;
+    use ream_consensus::execution_engine::mock_engine::MockExecutionEngine;
+    use ream_rpc::test_utils::TestDB;
+    use ream_storage::{errors::StoreError, tables::Table};
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_block_with_mismatched_state_root_is_rejected() {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/random/random/pyspec_tests/randomized_0",
+        );
+        let mut state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+            .expect("cannot find test asset (pre.ssz_snappy)");
+        let block: SignedBeaconBlock =
+            utils::read_ssz_snappy(&case_dir.join("blocks_0.ssz_snappy"))
+                .expect("cannot find test asset (blocks_0.ssz_snappy)");
+        state
+            .state_transition(&block, true, &Some(MockExecutionEngine::new()))
+            .await
+            .unwrap();
+
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let mut tampered = block.clone();
+        tampered.message.state_root = B256::repeat_byte(0xab);
+        let tampered_root = tampered.message.tree_hash_root();
+        let result = db.write_batch(|batch| {
+            batch.insert_block_with_state(
+                tampered_root,
+                tampered.clone(),
+                state.clone(),
+                state.tree_hash_root(),
+            )
+        });
+        assert!(matches!(result, Err(StoreError::StateRootMismatch(_))));
+        assert!(
+            db.beacon_block_provider()
+                .get(tampered_root)
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(
+            db.state_root_index_provider()
+                .get(tampered.message.state_root)
+                .unwrap(),
+            None
+        );
+
+        let block_root = block.message.tree_hash_root();
+        db.write_batch(|batch| {
+            batch.insert_block_with_state(
+                block_root,
+                block.clone(),
+                state.clone(),
+                state.tree_hash_root(),
+            )
+        })
+        .unwrap();
+        assert_eq!(
+            db.beacon_block_provider().get(block_root).unwrap(),
+            Some(block.clone())
+        );
+        assert_eq!(
+            db.beacon_state_provider().get(block_root).unwrap(),
+            Some(state)
+        );
+        assert_eq!(
+            db.state_root_index_provider()
+                .get(block.message.state_root)
+                .unwrap(),
+            Some(block_root)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validating_transition_rejects_mismatched_state_root() {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/random/random/pyspec_tests/randomized_0",
+        );
+        let mut state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+            .expect("cannot find test asset (pre.ssz_snappy)");
+        let mut block: SignedBeaconBlock =
+            utils::read_ssz_snappy(&case_dir.join("blocks_0.ssz_snappy"))
+                .expect("cannot find test asset (blocks_0.ssz_snappy)");
+
+        // Block import passes the root the transition checked instead of hashing the state again,
+        // so the transition must catch the mismatch on its own
+        block.message.state_root = B256::repeat_byte(0xab);
+        assert!(
+            state
+                .state_transition(&block, true, &Some(MockExecutionEngine::new()))
+                .await
+                .is_err()
+        );
+    }
+}
+
+// Testing state ID resolution in the beacon API handlers
+#[cfg(test)]
+mod tests_get_state_root_from_id {
+    use actix_web::{
+        http::{
+            StatusCode,
+            header::{ETAG, IF_NONE_MATCH},
+        },
+        test,
+    };
+    use alloy_primitives::B256;
+    use ream_beacon_api_types::{error::ApiError, id::ID};
+    use ream_consensus::{checkpoint::Checkpoint, constants::GENESIS_SLOT};
+    use ream_rpc::{
+        handlers::{
+            block::{BlockHandlerError, get_block_root_from_id},
+            state::{get_state_from_id, get_state_root_from_id},
+        },
+        test_utils::{TestDB, test_app},
+    };
+    use ream_storage::{
+        db::ReamDB,
+        tables::{Field, Table},
+    };
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_state_root_from_id() {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0",
+        );
+        let block: SignedBeaconBlock =
+            utils::read_ssz_snappy(&case_dir.join("serialized.ssz_snappy"))
+                .expect("cannot find test asset(serialized.ssz_snappy)");
+        let state_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/BeaconState/ssz_random/case_0",
+        );
+        let state: BeaconState = utils::read_ssz_snappy(&state_dir.join("serialized.ssz_snappy"))
+            .expect("cannot find test asset(serialized.ssz_snappy)");
+
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let block_root = block.message.tree_hash_root();
+        let state_root = block.message.state_root;
+        db.beacon_block_provider()
+            .insert(block_root, block.clone())
+            .unwrap();
+        db.beacon_state_provider()
+            .insert(block_root, state.clone())
+            .unwrap();
+
+        let mut genesis_block = block.clone();
+        genesis_block.message.slot = GENESIS_SLOT;
+        genesis_block.message.state_root = B256::repeat_byte(1);
+        db.beacon_block_provider()
+            .insert(genesis_block.message.tree_hash_root(), genesis_block)
+            .unwrap();
+
+        let checkpoint = Checkpoint {
+            epoch: 1,
+            root: block_root,
+        };
+        db.finalized_checkpoint_provider()
+            .insert(checkpoint)
+            .unwrap();
+        db.justified_checkpoint_provider()
+            .insert(checkpoint)
+            .unwrap();
+
+        for state_id in [
+            ID::Root(state_root),
+            ID::Slot(block.message.slot),
+            ID::Finalized,
+            ID::Justified,
+        ] {
+            assert_eq!(
+                get_state_root_from_id(state_id, db).await.unwrap(),
+                state_root
+            );
+        }
+        assert_eq!(
+            get_state_root_from_id(ID::Genesis, db).await.unwrap(),
+            B256::repeat_byte(1)
+        );
+
+        // A state ID root is a state root, so the block root does not resolve
+        assert!(matches!(
+            get_state_root_from_id(ID::Root(block_root), db).await,
+            Err(ApiError::NotFound(_))
+        ));
+
+        assert_eq!(
+            get_state_from_id(ID::Root(state_root), db).await.unwrap(),
+            state
+        );
+        assert!(matches!(
+            get_state_from_id(ID::Root(block_root), db).await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    /// Stores a state and a later block, so the slot right after the state is skipped rather than
+    /// in the future. Returns the stored state and the state expected at the skipped slot.
+    fn store_skipped_slot(db: &ReamDB) -> (BeaconState, BeaconState) {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/random/random/pyspec_tests/randomized_0",
+        );
+        let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+            .expect("cannot find test asset (pre.ssz_snappy)");
+
+        let mut header = state.latest_block_header.clone();
+        header.state_root = state.tree_hash_root();
+        let block_root = header.tree_hash_root();
+        db.slot_index_provider()
+            .insert(state.slot, block_root)
+            .unwrap();
+        db.beacon_state_provider()
+            .insert(block_root, state.clone())
+            .unwrap();
+        db.slot_index_provider()
+            .insert(state.slot + 2, B256::repeat_byte(1))
+            .unwrap();
+
+        let mut expected = state.clone();
+        expected.process_slots(state.slot + 1).unwrap();
+        (state, expected)
+    }
+
+    #[tokio::test]
+    async fn test_skipped_slot() {
+        let test_db = TestDB::new().unwrap();
+        let db = &test_db.db;
+        let (state, expected) = store_skipped_slot(db);
+
+        let skipped_slot = state.slot + 1;
+        assert!(matches!(
+            get_block_root_from_id(ID::Slot(skipped_slot), db).await,
+            Err(BlockHandlerError::NotFound(_))
+        ));
+        assert_eq!(
+            get_state_from_id(ID::Slot(skipped_slot), db).await.unwrap(),
+            expected
+        );
+        assert_eq!(
+            get_state_root_from_id(ID::Slot(skipped_slot), db)
+                .await
+                .unwrap(),
+            expected.tree_hash_root()
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_validator_stats_at_skipped_slot() {
+        let test_db = TestDB::new().unwrap();
+        let (state, expected) = store_skipped_slot(&test_db.db);
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+
+        let request = test::TestRequest::get()
+            .uri(&format!(
+                "/eth/v1/debug/beacon/validator_stats?state_id={}",
+                state.slot + 1
+            ))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = format!("\"{}\"", expected.tree_hash_root());
+        assert_eq!(response.headers().get(ETAG).unwrap(), etag.as_str());
+
+        // Clients holding the root of the rebuilt state keep their copy
+        let request = test::TestRequest::get()
+            .uri(&format!(
+                "/eth/v1/debug/beacon/validator_stats?state_id={}",
+                state.slot + 1
+            ))
+            .insert_header((IF_NONE_MATCH, etag))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    }
+}
+
+// Testing proposer and attester duties computed from the head state
+#[cfg(test)]
+mod tests_validator_duties {
+    use alloy_primitives::B256;
+    use ream_beacon_api_types::error::ApiError;
+    use ream_consensus::{constants::SLOTS_PER_EPOCH, misc::compute_start_slot_at_epoch};
+    use ream_rpc::{
+        handlers::duties::{compute_attester_duties, compute_proposer_duties},
+        proposer_cache::ProposerCache,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_proposer_duties() {
+        let state = read_state();
+        let head_root = B256::repeat_byte(1);
+        let current_epoch = state.get_current_epoch();
+        let proposer_cache = ProposerCache::default();
+
+        let duties =
+            compute_proposer_duties(&state, current_epoch, head_root, &proposer_cache).unwrap();
+        assert_eq!(duties.data.len() as u64, SLOTS_PER_EPOCH);
+        for (duty, slot) in duties
+            .data
+            .iter()
+            .zip(compute_start_slot_at_epoch(current_epoch)..)
+        {
+            assert_eq!(duty.slot, slot);
+            assert_eq!(
+                duty.validator_index,
+                state.get_beacon_proposer_index(Some(slot)).unwrap()
+            );
+        }
+
+        let dependent_slot = compute_start_slot_at_epoch(current_epoch).saturating_sub(1);
+        let expected_root = if dependent_slot < state.slot {
+            state.get_block_root_at_slot(dependent_slot).unwrap()
+        } else {
+            head_root
+        };
+        assert_eq!(duties.dependent_root, expected_root);
+
+        // Served from the cache the second time
+        let cached_duties =
+            compute_proposer_duties(&state, current_epoch, head_root, &proposer_cache).unwrap();
+        assert_eq!(cached_duties.dependent_root, duties.dependent_root);
+        for (cached_duty, duty) in cached_duties.data.iter().zip(&duties.data) {
+            assert_eq!(cached_duty.slot, duty.slot);
+            assert_eq!(cached_duty.validator_index, duty.validator_index);
+        }
+
+        // The last slot of the current epoch is still ahead of the state, so it has no root yet
+        let next_epoch_duties =
+            compute_proposer_duties(&state, current_epoch + 1, head_root, &proposer_cache).unwrap();
+        assert_eq!(next_epoch_duties.data.len() as u64, SLOTS_PER_EPOCH);
+        assert_eq!(next_epoch_duties.dependent_root, head_root);
+    }
+
+    #[test]
+    fn test_proposer_duties_out_of_range_epoch() {
+        let state = read_state();
+        let current_epoch = state.get_current_epoch();
+
+        assert!(matches!(
+            compute_proposer_duties(
+                &state,
+                current_epoch + 2,
+                B256::ZERO,
+                &ProposerCache::default()
+            ),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_attester_duties() {
+        let state = read_state();
+        let current_epoch = state.get_current_epoch();
+        let validator_index = 0;
+
+        let duties =
+            compute_attester_duties(&state, current_epoch, B256::ZERO, &[validator_index]).unwrap();
+        let (committee, committee_index, slot) = state
+            .get_committee_assignment(current_epoch, validator_index)
+            .unwrap()
+            .expect("every active validator is assigned to a committee");
+
+        assert_eq!(duties.data.len(), 1);
+        let duty = &duties.data[0];
+        assert_eq!(duty.slot, slot);
+        assert_eq!(duty.committee_index, committee_index);
+        assert_eq!(duty.committee_length, committee.len() as u64);
+        assert_eq!(
+            committee[duty.validator_committee_index as usize],
+            validator_index
+        );
+        assert_eq!(
+            duty.committees_at_slot,
+            state.get_committee_count_per_slot(current_epoch)
+        );
+    }
+
+    #[test]
+    fn test_attester_duties_out_of_range_epoch() {
+        let state = read_state();
+        let current_epoch = state.get_current_epoch();
+
+        assert!(matches!(
+            compute_attester_duties(&state, current_epoch + 2, B256::ZERO, &[0]),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
+
+// Testing sync committee lookup
+#[cfg(test)]
+mod tests_sync_committees {
+    use ream_beacon_api_types::error::ApiError;
+    use ream_consensus::{
+        constants::{EPOCHS_PER_SYNC_COMMITTEE_PERIOD, SYNC_COMMITTEE_SIZE},
+        misc::compute_sync_committee_period,
+    };
+    use ream_rpc::handlers::state::{SYNC_COMMITTEE_SUBNET_COUNT, compute_sync_committees};
+
+    use super::*;
+
+    #[test]
+    fn test_sync_committees_partitioning() {
+        let mut state = read_state();
+        let response = compute_sync_committees(&state, None).unwrap();
+
+        assert_eq!(response.validators.len() as u64, SYNC_COMMITTEE_SIZE);
+        for (validator_index, public_key) in response
+            .validators
+            .iter()
+            .zip(state.current_sync_committee.public_keys.iter())
+        {
+            assert_eq!(
+                state.validators[*validator_index as usize].public_key,
+                *public_key
+            );
+        }
+
+        assert_eq!(
+            response.validator_aggregates.len() as u64,
+            SYNC_COMMITTEE_SUBNET_COUNT
+        );
+        let subcommittee_size = (SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT) as usize;
+        for (subnet, subcommittee) in response.validator_aggregates.iter().enumerate() {
+            assert_eq!(
+                subcommittee.0,
+                response.validators[subnet * subcommittee_size..(subnet + 1) * subcommittee_size]
+            );
+        }
+
+        let expected_balance = response
+            .validators
+            .iter()
+            .map(|&index| state.validators[index as usize].effective_balance)
+            .sum::<u64>();
+        assert_eq!(response.total_effective_balance, expected_balance);
+
+        // Only seats of validators with the timely target flag count as participating
+        let participant = response.validators[0];
+        for flags in state.previous_epoch_participation.iter_mut() {
+            *flags = 0;
+        }
+        state.previous_epoch_participation[participant as usize] = 0b111;
+        let seats = response
+            .validators
+            .iter()
+            .filter(|&&index| index == participant)
+            .count() as u64;
+        let response = compute_sync_committees(&state, None).unwrap();
+        assert_eq!(response.participation, seats);
+    }
+
+    #[test]
+    fn test_sync_committees_epoch_range() {
+        let state = read_state();
+        let current_epoch = state.get_current_epoch();
+        let next_period_epoch =
+            (compute_sync_committee_period(current_epoch) + 1) * EPOCHS_PER_SYNC_COMMITTEE_PERIOD;
+
+        let response = compute_sync_committees(&state, Some(next_period_epoch)).unwrap();
+        assert_eq!(response.validators.len() as u64, SYNC_COMMITTEE_SIZE);
+
+        assert!(matches!(
+            compute_sync_committees(
+                &state,
+                Some(next_period_epoch + EPOCHS_PER_SYNC_COMMITTEE_PERIOD)
+            ),
+            Err(ApiError::BadRequest(_))
+        ));
+    }
+}
+
+// Testing bls to execution change pool validation
+#[cfg(test)]
+mod tests_bls_to_execution_change_pool {
+    use ream_beacon_api_types::error::ApiError;
+    use ream_operation_pool::OperationPool;
+    use ream_rpc::handlers::pool::insert_bls_to_execution_changes;
+
+    use super::*;
+
+    fn read_case(name: &str) -> (BeaconState, SignedBLSToExecutionChange) {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/operations/bls_to_execution_change/pyspec_tests",
+        )
+        .join(name);
+        let state = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+            .expect("cannot find test asset (pre.ssz_snappy)");
+        let address_change = utils::read_ssz_snappy(&case_dir.join("address_change.ssz_snappy"))
+            .expect("cannot find test asset (address_change.ssz_snappy)");
+        (state, address_change)
+    }
+
+    #[test]
+    fn test_valid_change_is_pooled() {
+        let (state, address_change) = read_case("success");
+        let operation_pool = OperationPool::default();
+
+        insert_bls_to_execution_changes(&state, &operation_pool, vec![address_change.clone()])
+            .unwrap();
+        assert_eq!(
+            operation_pool.get_bls_to_execution_changes(),
+            vec![address_change]
+        );
+    }
+
+    #[test]
+    fn test_invalid_changes_are_rejected() {
+        for name in ["invalid_incorrect_from_bls_pubkey", "invalid_already_0x01"] {
+            let (state, address_change) = read_case(name);
+            let operation_pool = OperationPool::default();
+
+            assert!(
+                matches!(
+                    insert_bls_to_execution_changes(&state, &operation_pool, vec![address_change]),
+                    Err(ApiError::BadRequest(_))
+                ),
+                "{name} should be rejected"
+            );
+            assert!(operation_pool.get_bls_to_execution_changes().is_empty());
+            assert_eq!(operation_pool.rejection_stats().invalid_operation, 1);
+        }
+    }
+}
+
+// Testing fork choice leaf ordering
+#[cfg(test)]
+mod tests_fork_choice_leaves {
+    use alloy_primitives::B256;
+    use hashbrown::HashMap;
+    use ream_rpc::handlers::block::get_fork_choice_leaves;
+
+    use super::*;
+
+    fn read_block() -> BeaconBlock {
+        let signed_block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        signed_block.message
+    }
+
+    #[test]
+    fn test_leaves_are_sorted_by_slot_and_root() {
+        let template = read_block();
+        let block = |slot: u64, parent_root: B256| {
+            let mut block = template.clone();
+            block.slot = slot;
+            block.parent_root = parent_root;
+            block
+        };
+
+        let root = B256::repeat_byte(0xaa);
+        let mut blocks = HashMap::new();
+        blocks.insert(root, block(1, B256::ZERO));
+        for (leaf_root, slot) in [
+            (B256::repeat_byte(0x01), 3),
+            (B256::repeat_byte(0x03), 2),
+            (B256::repeat_byte(0x02), 2),
+            (B256::repeat_byte(0x04), 4),
+        ] {
+            blocks.insert(leaf_root, block(slot, root));
+        }
+
+        let leaves = get_fork_choice_leaves(&blocks)
+            .into_iter()
+            .map(|(leaf_root, leaf)| (leaf.slot, leaf_root))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            leaves,
+            vec![
+                (2, B256::repeat_byte(0x02)),
+                (2, B256::repeat_byte(0x03)),
+                (3, B256::repeat_byte(0x01)),
+                (4, B256::repeat_byte(0x04)),
+            ]
+        );
+        for _ in 0..10 {
+            let repeated = get_fork_choice_leaves(&blocks)
+                .into_iter()
+                .map(|(leaf_root, leaf)| (leaf.slot, leaf_root))
+                .collect::<Vec<_>>();
+            assert_eq!(repeated, leaves);
+        }
+
+        // A tree of only the checkpoint block has that block as its single leaf
+        let blocks = HashMap::from([(root, block(1, B256::ZERO))]);
+        let leaves = get_fork_choice_leaves(&blocks);
+        assert_eq!(leaves.len(), 1);
+        assert_eq!(leaves[0].0, root);
+    }
+}
+
+// Testing the Beacon API through the full actix stack
+#[cfg(test)]
+mod tests_rpc_test_app {
+    use std::sync::Arc;
+
+    use actix_web::{http::StatusCode, test};
+    use ream_beacon_api_types::responses::{ApiResponse, ETH_CONSENSUS_VERSION_HEADER, VERSION};
+    use ream_consensus::{
+        checkpoint::Checkpoint, constants::SLOTS_PER_EPOCH, genesis::Genesis,
+        misc::compute_epoch_at_slot,
+    };
+    use ream_network_spec::networks::MAINNET;
+    use ream_rpc::test_utils::{InMemoryStore, TestDB, test_app, test_app_with_store};
+    use ream_storage::errors::StoreError;
+    use ssz_types::VariableList;
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_get_block_by_slot() {
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = MAINNET.electra_fork_epoch * SLOTS_PER_EPOCH;
+
+        let test_db = TestDB::new().unwrap();
+        let block_root = test_db.insert_block(block.clone()).unwrap();
+        test_db
+            .set_checkpoints(Checkpoint {
+                epoch: compute_epoch_at_slot(block.message.slot),
+                root: block_root,
+            })
+            .unwrap();
+
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+        let request = test::TestRequest::get()
+            .uri(&format!("/eth/v2/beacon/blocks/{}", block.message.slot))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get(ETH_CONSENSUS_VERSION_HEADER)
+                .unwrap(),
+            VERSION
+        );
+
+        let body: ApiResponse<SignedBeaconBlock> = test::read_body_json(response).await;
+        assert_eq!(body.version.as_deref(), Some(VERSION));
+        assert_eq!(body.execution_optimistic, Some(false));
+        assert_eq!(body.finalized, Some(true));
+        assert_eq!(body.data, block);
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/eth/v2/beacon/blocks/{}", block.message.slot + 1))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_get_block_from_in_memory_store() {
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = MAINNET.electra_fork_epoch * SLOTS_PER_EPOCH;
+
+        let store = Arc::new(InMemoryStore::default());
+        let block_root = store.insert_block(block.clone());
+        store.set_checkpoints(Checkpoint {
+            epoch: compute_epoch_at_slot(block.message.slot),
+            root: block_root,
+        });
+
+        let app = test::init_service(test_app_with_store(store)).await;
+        for block_id in [block.message.slot.to_string(), "head".to_string()] {
+            let request = test::TestRequest::get()
+                .uri(&format!("/eth/v2/beacon/blocks/{block_id}"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body: ApiResponse<SignedBeaconBlock> = test::read_body_json(response).await;
+            assert_eq!(body.finalized, Some(true));
+            assert_eq!(body.data, block);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_block_root_and_heads_from_in_memory_store() {
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = MAINNET.electra_fork_epoch * SLOTS_PER_EPOCH;
+
+        let store = Arc::new(InMemoryStore::default());
+        let block_root = store.insert_block(block.clone());
+        store.set_checkpoints(Checkpoint {
+            epoch: compute_epoch_at_slot(block.message.slot),
+            root: block_root,
+        });
+        let mut child = block.clone();
+        child.message.slot += 1;
+        child.message.parent_root = block_root;
+        let child_root = store.insert_block(child.clone());
+
+        let app = test::init_service(test_app_with_store(store)).await;
+        let request = test::TestRequest::get()
+            .uri("/eth/v1/beacon/blocks/head/root")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(body["data"]["root"], serde_json::json!(child_root));
+        assert_eq!(body["finalized"], false);
+
+        let request = test::TestRequest::get()
+            .uri("/eth/v2/debug/beacon/heads")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = test::read_body_json(response).await;
+        assert_eq!(
+            body["data"],
+            serde_json::json!([{
+                "root": child_root,
+                "slot": child.message.slot.to_string(),
+                "execution_optimistic": false,
+            }])
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_init_from_checkpoint_serves_finalized_block() {
+        let state = read_state();
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = state.slot;
+
+        // The block has to commit to the state
+        let test_db = TestDB::new().unwrap();
+        assert!(matches!(
+            test_db
+                .db
+                .init_from_checkpoint(state.clone(), block.clone()),
+            Err(StoreError::CheckpointMismatch(_))
+        ));
+
+        block.message.state_root = state.tree_hash_root();
+        test_db
+            .db
+            .init_from_checkpoint(state.clone(), block.clone())
+            .unwrap();
+
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+        for block_id in ["finalized", "justified", &state.slot.to_string()] {
+            let request = test::TestRequest::get()
+                .uri(&format!("/eth/v2/beacon/blocks/{block_id}"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK, "{block_id}");
+
+            let body: ApiResponse<SignedBeaconBlock> = test::read_body_json(response).await;
+            assert_eq!(body.finalized, Some(true));
+            assert_eq!(body.data, block);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_genesis_time_is_read_from_the_stored_genesis_state() {
+        let mut state = read_state();
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = 0;
+        state.genesis_time = MAINNET.min_genesis_time + 1_000;
+
+        let store = Arc::new(InMemoryStore::default());
+        let app = test::init_service(test_app_with_store(store.clone())).await;
+        let request = test::TestRequest::get()
+            .uri("/eth/v1/beacon/genesis")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        // Without a genesis state the network config is all there is
+        let body: ApiResponse<Genesis> = test::read_body_json(response).await;
+        assert_eq!(body.data.genesis_time, MAINNET.min_genesis_time);
+
+        let block_root = store.insert_block(block);
+        store.insert_state(block_root, state);
+        let request = test::TestRequest::get()
+            .uri("/eth/v1/beacon/genesis")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: ApiResponse<Genesis> = test::read_body_json(response).await;
+        assert_eq!(body.data.genesis_time, MAINNET.min_genesis_time + 1_000);
+    }
+
+    #[actix_web::test]
+    async fn test_finalized_flag_of_finalized_block_and_head() {
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = MAINNET.electra_fork_epoch * SLOTS_PER_EPOCH;
+
+        let store = Arc::new(InMemoryStore::default());
+        let finalized_root = store.insert_block(block.clone());
+        store.set_checkpoints(Checkpoint {
+            epoch: compute_epoch_at_slot(block.message.slot),
+            root: finalized_root,
+        });
+        let mut head = block.clone();
+        head.message.slot += 1;
+        head.message.parent_root = finalized_root;
+        store.insert_block(head.clone());
+
+        let app = test::init_service(test_app_with_store(store)).await;
+        for (block_id, expected_block, finalized) in [
+            (format!("{finalized_root:?}"), &block, true),
+            ("finalized".to_string(), &block, true),
+            ("head".to_string(), &head, false),
+        ] {
+            let request = test::TestRequest::get()
+                .uri(&format!("/eth/v2/beacon/blocks/{block_id}"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body: ApiResponse<SignedBeaconBlock> = test::read_body_json(response).await;
+            assert_eq!(&body.data, expected_block, "{block_id}");
+            assert_eq!(body.finalized, Some(finalized), "{block_id}");
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_block_attestations_dedup() {
+        let attestation: Attestation = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/operations/attestation/pyspec_tests/one_basic_attestation/attestation.ssz_snappy",
+        ))
+        .expect("cannot find test asset (attestation.ssz_snappy)");
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = MAINNET.electra_fork_epoch * SLOTS_PER_EPOCH;
+        block.message.body.attestations =
+            VariableList::new(vec![attestation.clone(), attestation.clone()]).unwrap();
+
+        let store = Arc::new(InMemoryStore::default());
+        let block_root = store.insert_block(block);
+        store.set_checkpoints(Checkpoint {
+            epoch: compute_epoch_at_slot(MAINNET.electra_fork_epoch * SLOTS_PER_EPOCH),
+            root: block_root,
+        });
+
+        let app = test::init_service(test_app_with_store(store)).await;
+        for (query, expected_len) in [("", 2), ("?dedup=false", 2), ("?dedup=true", 1)] {
+            let request = test::TestRequest::get()
+                .uri(&format!("/eth/v2/beacon/blocks/head/attestations{query}"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body: ApiResponse<Vec<Attestation>> = test::read_body_json(response).await;
+            assert_eq!(body.data.len(), expected_len, "{query}");
+            assert!(body.data.iter().all(|included| *included == attestation));
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_finalized_flag_follows_the_canonical_chain() {
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = MAINNET.electra_fork_epoch * SLOTS_PER_EPOCH;
+
+        let store = Arc::new(InMemoryStore::default());
+        let ancestor_root = store.insert_block(block.clone());
+        let mut fork = block.clone();
+        fork.message.slot += 1;
+        fork.message.parent_root = ancestor_root;
+        fork.message.proposer_index += 1;
+        let fork_root = store.insert_block(fork);
+        // Indexed after the fork, so the canonical block owns the slot
+        let mut canonical = block.clone();
+        canonical.message.slot += 1;
+        canonical.message.parent_root = ancestor_root;
+        let canonical_root = store.insert_block(canonical.clone());
+        let mut finalized = canonical.clone();
+        finalized.message.slot += 1;
+        finalized.message.parent_root = canonical_root;
+        let finalized_root = store.insert_block(finalized.clone());
+        store.set_checkpoints(Checkpoint {
+            epoch: compute_epoch_at_slot(finalized.message.slot),
+            root: finalized_root,
+        });
+
+        let app = test::init_service(test_app_with_store(store)).await;
+        for (block_root, expected) in [
+            (ancestor_root, true),
+            (canonical_root, true),
+            (finalized_root, true),
+            (fork_root, false),
+        ] {
+            let request = test::TestRequest::get()
+                .uri(&format!("/eth/v1/beacon/blocks/{block_root}/root"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let body: serde_json::Value = test::read_body_json(response).await;
+            assert_eq!(body["finalized"], expected, "{block_root}");
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_finalized_flag_without_checkpoint_is_service_unavailable() {
+        let block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+
+        let store = Arc::new(InMemoryStore::default());
+        let block_root = store.insert_block(block);
+
+        let app = test::init_service(test_app_with_store(store)).await;
+        let request = test::TestRequest::get()
+            .uri(&format!("/eth/v1/beacon/blocks/{block_root}/root"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
+// Testing retries of transient storage errors in block handlers
+#[cfg(test)]
+mod tests_block_read_retry {
+    use std::{
+        io,
+        sync::{
+            Arc,
+            atomic::{AtomicU32, Ordering},
+        },
+    };
+
+    use actix_web::{http::StatusCode, test};
+    use alloy_primitives::B256;
+    use hashbrown::HashMap;
+    use ream_consensus::{
+        checkpoint::Checkpoint, constants::SLOTS_PER_EPOCH, electra::beacon_block::BeaconBlock,
+        misc::compute_epoch_at_slot,
+    };
+    use ream_network_spec::networks::MAINNET;
+    use ream_rpc::{
+        retry::STORAGE_READ_ATTEMPTS,
+        store::BeaconStore,
+        test_utils::{InMemoryStore, test_app_with_store},
+    };
+    use ream_storage::errors::StoreError;
+
+    use super::*;
+
+    /// Fails the first `failures` slot index reads with a transient error, serving everything
+    /// else from `store`.
+    struct FlakyStore {
+        store: InMemoryStore,
+        failures: u32,
+        slot_index_reads: AtomicU32,
+    }
+
+    impl BeaconStore for FlakyStore {
+        fn beacon_block(&self, block_root: B256) -> Result<Option<SignedBeaconBlock>, StoreError> {
+            self.store.beacon_block(block_root)
+        }
+
+        fn beacon_state(&self, block_root: B256) -> Result<Option<BeaconState>, StoreError> {
+            self.store.beacon_state(block_root)
+        }
+
+        fn block_root_at_slot(&self, slot: u64) -> Result<Option<B256>, StoreError> {
+            if self.slot_index_reads.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(StoreError::Io(io::Error::from(io::ErrorKind::WouldBlock)));
+            }
+            self.store.block_root_at_slot(slot)
+        }
+
+        fn justified_checkpoint(&self) -> Result<Checkpoint, StoreError> {
+            self.store.justified_checkpoint()
+        }
+
+        fn finalized_checkpoint(&self) -> Result<Checkpoint, StoreError> {
+            self.store.finalized_checkpoint()
+        }
+
+        fn is_optimistic(&self, block_root: B256) -> Result<bool, StoreError> {
+            self.store.is_optimistic(block_root)
+        }
+
+        fn head_root(&self) -> anyhow::Result<B256> {
+            self.store.head_root()
+        }
+
+        fn justified_block_tree(&self) -> anyhow::Result<HashMap<B256, BeaconBlock>> {
+            self.store.justified_block_tree()
+        }
+    }
+
+    /// Requests the stored block by its slot from a store failing its first `failures` slot index
+    /// reads, returning the response status and how many times the slot index was read.
+    async fn get_block_by_slot(failures: u32) -> (StatusCode, u32) {
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = MAINNET.electra_fork_epoch * SLOTS_PER_EPOCH;
+
+        let store = InMemoryStore::default();
+        let block_root = store.insert_block(block.clone());
+        store.set_checkpoints(Checkpoint {
+            epoch: compute_epoch_at_slot(block.message.slot),
+            root: block_root,
+        });
+        let flaky_store = Arc::new(FlakyStore {
+            store,
+            failures,
+            slot_index_reads: AtomicU32::new(0),
+        });
+
+        let app = test::init_service(test_app_with_store(flaky_store.clone())).await;
+        let request = test::TestRequest::get()
+            .uri(&format!("/eth/v2/beacon/blocks/{}", block.message.slot))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        (
+            response.status(),
+            flaky_store.slot_index_reads.load(Ordering::SeqCst),
+        )
+    }
+
+    #[actix_web::test]
+    async fn test_block_is_served_after_transient_failures() {
+        let (status, attempts) = get_block_by_slot(STORAGE_READ_ATTEMPTS - 1).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(attempts, STORAGE_READ_ATTEMPTS);
+    }
+
+    #[actix_web::test]
+    async fn test_block_read_gives_up_after_attempts() {
+        let (status, attempts) = get_block_by_slot(STORAGE_READ_ATTEMPTS).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(attempts, STORAGE_READ_ATTEMPTS);
+    }
+}
+
+// Testing block attestation rewards
+#[cfg(test)]
+mod tests_block_attestation_rewards {
+    use std::sync::Arc;
+
+    use actix_web::{http::StatusCode, test};
+    use ream_beacon_api_types::responses::ApiResponse;
+    use ream_consensus::misc::compute_epoch_at_slot;
+    use ream_rpc::{
+        handlers::block::{BlockRewards, get_attestations_rewards},
+        test_utils::{InMemoryStore, test_app_with_store},
+    };
+    use ssz_types::VariableList;
+
+    use super::*;
+
+    fn block_with_attestations(
+        state: &BeaconState,
+        attestations: Vec<Attestation>,
+    ) -> SignedBeaconBlock {
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = state.slot;
+        block.message.body.attestations = VariableList::new(attestations).unwrap();
+        block
+    }
+
+    #[test]
+    fn test_only_new_participation_is_rewarded() {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/operations/attestation/pyspec_tests/one_basic_attestation",
+        );
+        let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+            .expect("cannot find test asset (pre.ssz_snappy)");
+        let post_state: BeaconState = utils::read_ssz_snappy(&case_dir.join("post.ssz_snappy"))
+            .expect("cannot find test asset (post.ssz_snappy)");
+        let attestation: Attestation =
+            utils::read_ssz_snappy(&case_dir.join("attestation.ssz_snappy"))
+                .expect("cannot find test asset (attestation.ssz_snappy)");
+
+        // The reward matches what `process_attestation` paid the proposer
+        let proposer_index = state.get_beacon_proposer_index(None).unwrap() as usize;
+        let expected_reward = post_state.balances[proposer_index] - state.balances[proposer_index];
+        assert!(expected_reward > 0);
+        let block = block_with_attestations(&state, vec![attestation.clone()]);
+        assert_eq!(get_attestations_rewards(&state, &block), expected_reward);
+
+        // A duplicate in the same block adds nothing
+        let block = block_with_attestations(&state, vec![attestation.clone(), attestation.clone()]);
+        assert_eq!(get_attestations_rewards(&state, &block), expected_reward);
+
+        // Neither does an attestation whose participation is already recorded in the state
+        let block = block_with_attestations(&post_state, vec![attestation]);
+        assert_eq!(get_attestations_rewards(&post_state, &block), 0);
+    }
+
+    #[actix_web::test]
+    async fn test_head_block_rewards_use_the_advanced_pre_state() {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/sanity/blocks/pyspec_tests/attestation",
+        );
+        let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+            .expect("cannot find test asset (pre.ssz_snappy)");
+        let block: SignedBeaconBlock =
+            utils::read_ssz_snappy(&case_dir.join("blocks_0.ssz_snappy"))
+                .expect("cannot find test asset (blocks_0.ssz_snappy)");
+        assert!(!block.message.body.attestations.is_empty());
+        // The parent post-state is behind the block, rewards need it advanced to the block slot
+        assert!(state.slot < block.message.slot);
+        let mut pre_state = state.clone();
+        pre_state.process_slots(block.message.slot).unwrap();
+        let expected_attestations = get_attestations_rewards(&pre_state, &block);
+        assert!(expected_attestations > 0);
+
+        let store = Arc::new(InMemoryStore::default());
+        store.insert_state(block.message.parent_root, state);
+        let block_root = store.insert_block(block.clone());
+        store.set_checkpoints(Checkpoint {
+            epoch: compute_epoch_at_slot(block.message.slot),
+            root: block_root,
+        });
+
+        let app = test::init_service(test_app_with_store(store)).await;
+        let request = test::TestRequest::get()
+            .uri("/eth/v1/beacon/blocks/head/rewards")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: ApiResponse<BlockRewards> = test::read_body_json(response).await;
+        assert_eq!(body.data.proposer_index, block.message.proposer_index);
+        assert_eq!(body.data.attestations, expected_attestations);
+        assert!(body.data.total > 0);
+    }
+
+    #[test]
+    fn test_cached_base_rewards_match_process_attestation() {
+        let base_path = "mainnet/tests/mainnet/electra/operations/attestation/pyspec_tests";
+        for entry in std::fs::read_dir(base_path).unwrap() {
+            let case_dir = entry.unwrap().path();
+            if !case_dir.join("post.ssz_snappy").exists() {
+                continue;
+            }
+            let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+                .expect("cannot find test asset (pre.ssz_snappy)");
+            let post_state: BeaconState = utils::read_ssz_snappy(&case_dir.join("post.ssz_snappy"))
+                .expect("cannot find test asset (post.ssz_snappy)");
+            let attestation: Attestation =
+                utils::read_ssz_snappy(&case_dir.join("attestation.ssz_snappy"))
+                    .expect("cannot find test asset (attestation.ssz_snappy)");
+
+            // `process_attestation` computes every base reward from scratch
+            let proposer_index = state.get_beacon_proposer_index(None).unwrap() as usize;
+            let expected_reward =
+                post_state.balances[proposer_index] - state.balances[proposer_index];
+
+            // Repeated attesters hit the base reward cache
+            let block = block_with_attestations(&state, vec![attestation.clone(); 3]);
+            assert_eq!(
+                get_attestations_rewards(&state, &block),
+                expected_reward,
+                "{case_dir:?}"
+            );
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_block_rewards_total_is_the_sum_of_its_parts() {
+        let base_path =
+            std::path::Path::new("mainnet/tests/mainnet/electra/sanity/blocks/pyspec_tests");
+        for case in ["attestation", "proposer_slashing", "attester_slashing"] {
+            let case_dir = base_path.join(case);
+            let state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+                .expect("cannot find test asset (pre.ssz_snappy)");
+            let block: SignedBeaconBlock =
+                utils::read_ssz_snappy(&case_dir.join("blocks_0.ssz_snappy"))
+                    .expect("cannot find test asset (blocks_0.ssz_snappy)");
+
+            let store = Arc::new(InMemoryStore::default());
+            store.insert_state(block.message.parent_root, state);
+            let block_root = store.insert_block(block.clone());
+            store.set_checkpoints(Checkpoint {
+                epoch: compute_epoch_at_slot(block.message.slot),
+                root: block_root,
+            });
+
+            let app = test::init_service(test_app_with_store(store)).await;
+            let request = test::TestRequest::get()
+                .uri(&format!("/eth/v1/beacon/blocks/{block_root:?}/rewards"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK, "{case}");
+
+            let rewards = test::read_body_json::<ApiResponse<BlockRewards>, _>(response)
+                .await
+                .data;
+            assert_eq!(
+                rewards.total,
+                rewards.attestations
+                    + rewards.sync_aggregate
+                    + rewards.proposer_slashings
+                    + rewards.attester_slashings,
+                "{case}"
+            );
+            assert!(rewards.total > 0, "{case}");
+            match case {
+                "proposer_slashing" => assert!(rewards.proposer_slashings > 0),
+                "attester_slashing" => assert!(rewards.attester_slashings > 0),
+                _ => assert!(rewards.attestations > 0),
+            }
+        }
+    }
+}
+
+// Testing the Electra pending queue endpoints
+#[cfg(test)]
+mod tests_pending_queues {
+    use actix_web::{http::StatusCode, test};
+    use ream_beacon_api_types::responses::{BeaconVersionedResponse, VERSION};
+    use ream_consensus::{
+        pending_consolidation::PendingConsolidation, pending_deposit::PendingDeposit,
+    };
+    use ream_network_spec::networks::MAINNET;
+    use ream_rpc::test_utils::{TestDB, test_app};
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    /// The first ``pre`` state of the epoch processing ``handler`` cases with a non empty queue.
+    fn populated_state(handler: &str, is_populated: impl Fn(&BeaconState) -> bool) -> BeaconState {
+        let base_path =
+            format!("mainnet/tests/mainnet/electra/epoch_processing/{handler}/pyspec_tests");
+        let mut case_dirs = std::fs::read_dir(&base_path)
+            .expect("cannot find test asset directory")
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        case_dirs.sort();
+
+        case_dirs
+            .into_iter()
+            .map(|case_dir| {
+                utils::read_ssz_snappy::<BeaconState>(&case_dir.join("pre.ssz_snappy"))
+                    .expect("cannot find test asset (pre.ssz_snappy)")
+            })
+            .find(is_populated)
+            .expect("no case with a populated queue")
+    }
+
+    /// Serves ``state`` as the post-state of a block at its slot.
+    fn store_state(state: &BeaconState) -> TestDB {
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = state.slot;
+        block.message.state_root = state.tree_hash_root();
+
+        let test_db = TestDB::new().unwrap();
+        let block_root = test_db.insert_block(block).unwrap();
+        test_db.insert_state(block_root, state.clone()).unwrap();
+        test_db
+    }
+
+    async fn get_queue(state: &BeaconState, queue: &str) -> (Vec<u8>, StatusCode) {
+        let test_db = store_state(state);
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+        let request = test::TestRequest::get()
+            .uri(&format!("/eth/v1/beacon/states/{}/{queue}", state.slot))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        let status = response.status();
+        (test::read_body(response).await.to_vec(), status)
+    }
+
+    #[actix_web::test]
+    async fn test_pending_consolidations() {
+        let state = populated_state("pending_consolidations", |state| {
+            !state.pending_consolidations.is_empty()
+        });
+
+        let (body, status) = get_queue(&state, "pending_consolidations").await;
+        assert_eq!(status, StatusCode::OK);
+        let response: BeaconVersionedResponse<Vec<PendingConsolidation>> =
+            serde_json::from_slice(&body).unwrap();
+        assert_eq!(response.version, VERSION);
+        assert_eq!(
+            response.data,
+            Vec::from(state.pending_consolidations.clone())
+        );
+
+        let consolidation = &state.pending_consolidations[0];
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.contains(&format!(
+            r#"{{"source_index":"{}","target_index":"{}"}}"#,
+            consolidation.source_index, consolidation.target_index
+        )));
+    }
+
+    #[actix_web::test]
+    async fn test_pending_deposits() {
+        let state = populated_state("pending_deposits", |state| {
+            !state.pending_deposits.is_empty()
+        });
+
+        let (body, status) = get_queue(&state, "pending_deposits").await;
+        assert_eq!(status, StatusCode::OK);
+        let response: BeaconVersionedResponse<Vec<PendingDeposit>> =
+            serde_json::from_slice(&body).unwrap();
+        assert_eq!(response.data, Vec::from(state.pending_deposits.clone()));
+
+        let deposit = &state.pending_deposits[0];
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.contains(&format!(r#""amount":"{}""#, deposit.amount)));
+        assert!(body.contains(&format!(r#""slot":"{}""#, deposit.slot)));
+    }
+
+    #[actix_web::test]
+    async fn test_pre_electra_state_is_bad_request() {
+        let mut state = populated_state("pending_consolidations", |_| true);
+        state.fork.current_version = MAINNET.deneb_fork_version;
+
+        let (_, status) = get_queue(&state, "pending_consolidations").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
+
+// Testing block slashing rewards
+#[cfg(test)]
+mod tests_block_slashing_rewards {
+    use ream_rpc::handlers::block::{
+        get_attester_slashing_rewards, get_proposer_slashing_rewards,
+        get_slashable_attester_indices,
+    };
+    use ssz_types::VariableList;
+
+    use super::*;
+
+    fn read_case<T: ssz::Decode>(operation: &str, case: &str, file: &str) -> T {
+        utils::read_ssz_snappy(
+            &std::path::Path::new(&format!(
+                "mainnet/tests/mainnet/electra/operations/{operation}/pyspec_tests/{case}"
+            ))
+            .join(file),
+        )
+        .expect("cannot find test asset")
+    }
+
+    /// The balance the proposer gained processing the operation of `case`.
+    fn proposer_balance_delta(operation: &str, case: &str) -> u64 {
+        let pre: BeaconState = read_case(operation, case, "pre.ssz_snappy");
+        let post: BeaconState = read_case(operation, case, "post.ssz_snappy");
+        let proposer_index = pre.get_beacon_proposer_index(None).unwrap() as usize;
+        post.balances[proposer_index] - pre.balances[proposer_index]
+    }
+
+    fn empty_block(state: &BeaconState) -> SignedBeaconBlock {
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = state.slot;
+        block.message.body.proposer_slashings = VariableList::empty();
+        block.message.body.attester_slashings = VariableList::empty();
+        block
+    }
+
+    #[test]
+    fn test_proposer_slashing_reward() {
+        let state: BeaconState = read_case("proposer_slashing", "basic", "pre.ssz_snappy");
+        let proposer_slashing: ProposerSlashing =
+            read_case("proposer_slashing", "basic", "proposer_slashing.ssz_snappy");
+
+        let mut block = empty_block(&state);
+        assert_eq!(get_proposer_slashing_rewards(&state, &block), 0);
+        block.message.body.proposer_slashings = VariableList::new(vec![proposer_slashing]).unwrap();
+        assert_eq!(
+            get_proposer_slashing_rewards(&state, &block),
+            proposer_balance_delta("proposer_slashing", "basic")
+        );
+    }
+
+    #[test]
+    fn test_attester_slashing_reward() {
+        let state: BeaconState = read_case("attester_slashing", "basic_double", "pre.ssz_snappy");
+        let attester_slashing: AttesterSlashing = read_case(
+            "attester_slashing",
+            "basic_double",
+            "attester_slashing.ssz_snappy",
+        );
+        assert!(!get_slashable_attester_indices(&state, &attester_slashing).is_empty());
+
+        let mut block = empty_block(&state);
+        assert_eq!(get_attester_slashing_rewards(&state, &block), 0);
+        block.message.body.attester_slashings = VariableList::new(vec![attester_slashing]).unwrap();
+        assert_eq!(
+            get_attester_slashing_rewards(&state, &block),
+            proposer_balance_delta("attester_slashing", "basic_double")
+        );
+    }
+}
+
+// Testing database snapshot export and import
+#[cfg(test)]
+mod tests_db_snapshot {
+    use ream_consensus::misc::compute_epoch_at_slot;
+    use ream_rpc::test_utils::TestDB;
+    use ream_storage::{
+        errors::StoreError,
+        tables::{Field, Table},
+    };
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    /// A finalized block committing to ``state`` followed by two descendants.
+    fn populated_db() -> TestDB {
+        let state = read_state();
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = state.slot;
+        block.message.state_root = state.tree_hash_root();
+
+        let test_db = TestDB::new().unwrap();
+        let finalized_root = test_db.insert_block(block.clone()).unwrap();
+        test_db.insert_state(finalized_root, state).unwrap();
+        test_db
+            .set_checkpoints(Checkpoint {
+                epoch: compute_epoch_at_slot(block.message.slot),
+                root: finalized_root,
+            })
+            .unwrap();
+
+        let mut parent_root = finalized_root;
+        for _ in 0..2 {
+            block.message.slot += 1;
+            block.message.parent_root = parent_root;
+            parent_root = test_db.insert_block(block.clone()).unwrap();
+        }
+        test_db
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let source = populated_db();
+        let mut snapshot = vec![];
+        source.db.export_snapshot(&mut snapshot).unwrap();
+
+        let target = TestDB::new().unwrap();
+        target.db.import_snapshot(snapshot.as_slice()).unwrap();
+
+        let head_root = source.db.slot_index_provider().get_highest_root().unwrap();
+        assert!(head_root.is_some());
+        assert_eq!(
+            target.db.slot_index_provider().get_highest_root().unwrap(),
+            head_root
+        );
+        assert_eq!(
+            target
+                .db
+                .beacon_block_provider()
+                .get(head_root.unwrap())
+                .unwrap(),
+            source
+                .db
+                .beacon_block_provider()
+                .get(head_root.unwrap())
+                .unwrap()
+        );
+
+        let finalized_checkpoint = source.db.finalized_checkpoint_provider().get().unwrap();
+        assert_eq!(
+            target.db.finalized_checkpoint_provider().get().unwrap(),
+            finalized_checkpoint
+        );
+        assert_eq!(
+            target.db.justified_checkpoint_provider().get().unwrap(),
+            source.db.justified_checkpoint_provider().get().unwrap()
+        );
+        assert_eq!(
+            target
+                .db
+                .beacon_state_provider()
+                .get(finalized_checkpoint.root)
+                .unwrap(),
+            source
+                .db
+                .beacon_state_provider()
+                .get(finalized_checkpoint.root)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_snapshot_rejects_wrong_header() {
+        let source = populated_db();
+        let mut snapshot = vec![];
+        source.db.export_snapshot(&mut snapshot).unwrap();
+        snapshot[0] ^= 0xff;
+
+        let target = TestDB::new().unwrap();
+        assert!(matches!(
+            target.db.import_snapshot(snapshot.as_slice()),
+            Err(StoreError::Snapshot(_))
+        ));
+        assert!(target.db.finalized_checkpoint_provider().get().is_err());
+    }
+}
+
+// Testing single-flight loads of the state cache
+#[cfg(test)]
+mod tests_state_cache {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+
+    use alloy_primitives::B256;
+    use ream_beacon_api_types::error::ApiError;
+    use ream_rpc::state_cache::StateCache;
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    const CONCURRENT_REQUESTS: usize = 16;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_loads_are_coalesced() {
+        let state = read_state();
+        let state_root = state.tree_hash_root();
+        let cache = Arc::new(StateCache::default());
+        let loads = Arc::new(AtomicUsize::new(0));
+
+        let requests = (0..CONCURRENT_REQUESTS)
+            .map(|_| {
+                let cache = cache.clone();
+                let loads = loads.clone();
+                let state = state.clone();
+                tokio::spawn(async move {
+                    cache
+                        .get_or_load(state_root, || async move {
+                            loads.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            Ok::<_, ApiError>(state)
+                        })
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut states = vec![];
+        for request in requests {
+            states.push(request.await.unwrap());
+        }
+
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert!(states.iter().all(|loaded| Arc::ptr_eq(loaded, &states[0])));
+        assert_eq!(*states[0], state);
+    }
+
+    #[tokio::test]
+    async fn test_failed_load_is_retried() {
+        let cache = StateCache::default();
+        let state_root = B256::repeat_byte(1);
+
+        let result = cache
+            .get_or_load(state_root, || async move {
+                Err::<BeaconState, _>(ApiError::NotFound("state".to_string()))
+            })
+            .await;
+        assert!(matches!(result, Err(ApiError::NotFound(_))));
+
+        let state = read_state();
+        let loaded = cache
+            .get_or_load(
+                state_root,
+                || async move { Ok::<_, ApiError>(read_state()) },
+            )
+            .await
+            .unwrap();
+        assert_eq!(*loaded, state);
+    }
+
+    #[tokio::test]
+    async fn test_oldest_state_is_evicted() {
+        let state = read_state();
+        let cache = StateCache::new(2);
+        let loads = AtomicUsize::new(0);
+        let (loads_ref, state_ref) = (&loads, &state);
+        let load = move || async move {
+            loads_ref.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ApiError>(state_ref.clone())
+        };
+
+        for byte in [1, 2, 3, 1] {
+            cache
+                .get_or_load(B256::repeat_byte(byte), load)
+                .await
+                .unwrap();
+        }
+        // The first root was evicted by the third and had to be loaded again
+        assert_eq!(loads.load(Ordering::SeqCst), 4);
+
+        cache.get_or_load(B256::repeat_byte(3), load).await.unwrap();
+        assert_eq!(loads.load(Ordering::SeqCst), 4);
+    }
+}
+
+// Testing batched verification of block signatures
+#[cfg(test)]
+mod tests_block_signature_sets {
+    use ream_bls::traits::Verifiable;
+    use ream_consensus::signature_set::{SignatureKind, get_block_signature_sets};
+
+    use super::*;
+
+    fn read_case() -> (BeaconState, SignedBeaconBlock) {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/sanity/blocks/pyspec_tests/proposer_slashing",
+        );
+        let mut state: BeaconState = utils::read_ssz_snappy(&case_dir.join("pre.ssz_snappy"))
+            .expect("cannot find test asset (pre.ssz_snappy)");
+        let signed_block: SignedBeaconBlock =
+            utils::read_ssz_snappy(&case_dir.join("blocks_0.ssz_snappy"))
+                .expect("cannot find test asset (blocks_0.ssz_snappy)");
+        state.process_slots(signed_block.message.slot).unwrap();
+
+        (state, signed_block)
+    }
+
+    #[test]
+    fn test_valid_block_signatures() {
+        let (state, signed_block) = read_case();
+
+        let signature_sets = get_block_signature_sets(&state, &signed_block).unwrap();
+        let kinds = signature_sets
+            .sets()
+            .iter()
+            .map(|set| set.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                SignatureKind::BlockProposal,
+                SignatureKind::RandaoReveal,
+                SignatureKind::ProposerSlashing,
+                SignatureKind::ProposerSlashing
+            ]
+        );
+        assert_eq!(signature_sets.find_invalid(), None);
+        signature_sets.verify().unwrap();
+    }
+
+    #[test]
+    fn test_bad_randao_reveal_is_rejected() {
+        let (state, mut signed_block) = read_case();
+        // A valid signature, but over the block rather than the epoch
+        signed_block.message.body.randao_reveal = signed_block.signature.clone();
+
+        let signature_sets = get_block_signature_sets(&state, &signed_block).unwrap();
+        assert!(signature_sets.verify().is_err());
+        // Changing the body also invalidates the block signature, which is checked first
+        assert_eq!(
+            signature_sets.find_invalid().unwrap().kind,
+            SignatureKind::BlockProposal
+        );
+        let randao_set = &signature_sets.sets()[1];
+        assert_eq!(randao_set.kind, SignatureKind::RandaoReveal);
+        assert!(
+            !randao_set
+                .signature
+                .verify(&randao_set.public_key, randao_set.signing_root.as_ref())
+                .unwrap()
+        );
+    }
+}
+
+// Testing ancestor lookups and proposer equivocations of the fork choice store
+#[cfg(test)]
+mod tests_store_ancestors {
+    use std::sync::Arc;
+
+    use alloy_primitives::B256;
+    use ream_beacon_api_types::events::{BeaconEvent, ChainReorgEvent};
+    use ream_fork_choice::store::Store;
+    use ream_operation_pool::OperationPool;
+    use ream_rpc::test_utils::TestDB;
+    use ream_storage::tables::{Table, execution_status::ExecutionStatus};
+    use tokio::sync::broadcast;
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    struct Chain {
+        test_db: TestDB,
+        store: Store,
+        template: SignedBeaconBlock,
+    }
+
+    impl Chain {
+        fn new() -> Self {
+            let template: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+                "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+            ))
+            .expect("cannot find test asset (serialized.ssz_snappy)");
+            let test_db = TestDB::new().unwrap();
+            let store = Store::new(test_db.db.clone(), Arc::new(OperationPool::default()));
+            Self {
+                test_db,
+                store,
+                template,
+            }
+        }
+
+        /// Inserts a child of `parent_root` at `slot`, `proposer_index` tells siblings apart.
+        fn insert(&self, parent_root: B256, slot: u64, proposer_index: u64) -> B256 {
+            let mut block = self.template.clone();
+            block.message.parent_root = parent_root;
+            block.message.slot = slot;
+            block.message.proposer_index = proposer_index;
+            self.test_db.insert_block(block).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_get_ancestor_on_linear_chain() {
+        let chain = Chain::new();
+        let root_32 = chain.insert(B256::ZERO, 32, 0);
+        let root_40 = chain.insert(root_32, 40, 0);
+        let root_64 = chain.insert(root_40, 64, 0);
+        let root_70 = chain.insert(root_64, 70, 0);
+
+        assert_eq!(chain.store.get_ancestor(root_70, 70).unwrap(), root_70);
+        assert_eq!(chain.store.get_ancestor(root_70, 64).unwrap(), root_64);
+        // Skipped slots resolve to the latest block before them
+        assert_eq!(chain.store.get_ancestor(root_70, 63).unwrap(), root_40);
+        assert_eq!(chain.store.get_ancestor(root_70, 32).unwrap(), root_32);
+        // A block at or before the slot is its own ancestor
+        assert_eq!(chain.store.get_ancestor(root_40, 64).unwrap(), root_40);
+        // Walking past the oldest stored block fails
+        assert!(chain.store.get_ancestor(root_70, 31).is_err());
+    }
+
+    #[test]
+    fn test_is_finalized_descendant_across_fork() {
+        let chain = Chain::new();
+        let root_32 = chain.insert(B256::ZERO, 32, 0);
+        let root_40 = chain.insert(root_32, 40, 0);
+        let root_64 = chain.insert(root_40, 64, 0);
+        let root_70 = chain.insert(root_64, 70, 0);
+        let fork_root_64 = chain.insert(root_40, 64, 1);
+        let fork_root_70 = chain.insert(fork_root_64, 70, 1);
+        chain
+            .test_db
+            .set_checkpoints(Checkpoint {
+                epoch: 2,
+                root: root_64,
+            })
+            .unwrap();
+
+        assert_eq!(
+            chain.store.get_ancestor(fork_root_70, 64).unwrap(),
+            fork_root_64
+        );
+        assert_eq!(chain.store.get_ancestor(fork_root_70, 40).unwrap(), root_40);
+
+        assert!(chain.store.is_finalized_descendant(root_64).unwrap());
+        assert!(chain.store.is_finalized_descendant(root_70).unwrap());
+        assert!(!chain.store.is_finalized_descendant(fork_root_64).unwrap());
+        assert!(!chain.store.is_finalized_descendant(fork_root_70).unwrap());
+        // Ancestors of the finalized block are not its descendants
+        assert!(!chain.store.is_finalized_descendant(root_40).unwrap());
+    }
+
+    #[test]
+    fn test_switching_branches_emits_reorg() {
+        let chain = Chain::new();
+        let root_32 = chain.insert(B256::ZERO, 32, 0);
+        let root_40 = chain.insert(root_32, 40, 0);
+        let root_64 = chain.insert(root_40, 64, 0);
+        let root_70 = chain.insert(root_64, 70, 0);
+        let fork_root_66 = chain.insert(root_40, 66, 1);
+        let fork_root_71 = chain.insert(fork_root_66, 71, 1);
+        let (event_sender, mut events) = broadcast::channel(16);
+
+        assert_eq!(
+            chain
+                .store
+                .get_common_ancestor(root_70, fork_root_71)
+                .unwrap(),
+            root_40
+        );
+        chain
+            .store
+            .on_head_update(root_70, fork_root_71, &event_sender)
+            .unwrap();
+
+        let block = |root| {
+            chain
+                .test_db
+                .db
+                .beacon_block_provider()
+                .get(root)
+                .unwrap()
+                .unwrap()
+                .message
+        };
+        assert_eq!(
+            events.try_recv().unwrap(),
+            BeaconEvent::ChainReorg(ChainReorgEvent {
+                slot: 71,
+                depth: 2,
+                old_head_block: root_70,
+                new_head_block: fork_root_71,
+                old_head_state: block(root_70).state_root,
+                new_head_state: block(fork_root_71).state_root,
+                epoch: 2,
+                execution_optimistic: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_reorg_to_optimistic_head_is_flagged() {
+        let chain = Chain::new();
+        let root_32 = chain.insert(B256::ZERO, 32, 0);
+        let root_40 = chain.insert(root_32, 40, 0);
+        let root_64 = chain.insert(root_40, 64, 0);
+        let fork_root_66 = chain.insert(root_40, 66, 1);
+        let fork_root_71 = chain.insert(fork_root_66, 71, 1);
+        let (event_sender, mut events) = broadcast::channel(16);
+
+        // The new head inherits the status of its optimistic parent
+        chain
+            .test_db
+            .db
+            .execution_status_provider()
+            .insert(fork_root_66, ExecutionStatus::Optimistic)
+            .unwrap();
+        chain
+            .store
+            .on_head_update(root_64, fork_root_71, &event_sender)
+            .unwrap();
+
+        let BeaconEvent::ChainReorg(event) = events.try_recv().unwrap() else {
+            panic!("expected a chain_reorg event");
+        };
+        assert!(event.execution_optimistic);
+    }
+
+    #[test]
+    fn test_extending_head_emits_no_reorg() {
+        let chain = Chain::new();
+        let root_32 = chain.insert(B256::ZERO, 32, 0);
+        let root_40 = chain.insert(root_32, 40, 0);
+        let root_64 = chain.insert(root_40, 64, 0);
+        let (event_sender, mut events) = broadcast::channel(16);
+
+        chain
+            .store
+            .on_head_update(root_40, root_64, &event_sender)
+            .unwrap();
+        chain
+            .store
+            .on_head_update(root_64, root_64, &event_sender)
+            .unwrap();
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_detect_proposer_equivocations() {
+        let chain = Chain::new();
+        let root_32 = chain.insert(B256::ZERO, 32, 0);
+        chain
+            .test_db
+            .set_checkpoints(Checkpoint {
+                epoch: 1,
+                root: root_32,
+            })
+            .unwrap();
+        let root_33 = chain.insert(root_32, 33, 5);
+        // The same proposer signs a second, different block for the same slot
+        let mut equivocation = chain.template.clone();
+        equivocation.message.parent_root = root_32;
+        equivocation.message.slot = 33;
+        equivocation.message.proposer_index = 5;
+        equivocation.message.body.graffiti = B256::repeat_byte(0xee);
+        let equivocation_root = chain.test_db.insert_block(equivocation).unwrap();
+        assert_ne!(root_33, equivocation_root);
+        // Successive blocks of one proposer are not an equivocation
+        chain.insert(root_33, 34, 5);
+
+        let signed_header = |root| {
+            chain
+                .test_db
+                .db
+                .beacon_block_provider()
+                .get(root)
+                .unwrap()
+                .unwrap()
+                .signed_header()
+        };
+        let proposer_slashings = chain.store.detect_proposer_equivocations().unwrap();
+        assert_eq!(proposer_slashings.len(), 1);
+        let proposer_slashing = &proposer_slashings[0];
+        let mut headers = [
+            proposer_slashing.signed_header_1.clone(),
+            proposer_slashing.signed_header_2.clone(),
+        ];
+        headers.sort_by_key(|header| header.message.tree_hash_root());
+        let mut expected_headers = [signed_header(root_33), signed_header(equivocation_root)];
+        expected_headers.sort_by_key(|header| header.message.tree_hash_root());
+        assert_eq!(headers, expected_headers);
+    }
+
+    #[test]
+    fn test_blocks_in_different_slots_are_not_equivocations() {
+        let chain = Chain::new();
+        let root_32 = chain.insert(B256::ZERO, 32, 0);
+        chain
+            .test_db
+            .set_checkpoints(Checkpoint {
+                epoch: 1,
+                root: root_32,
+            })
+            .unwrap();
+        // Sibling blocks of the same proposer, one slot apart
+        chain.insert(root_32, 33, 5);
+        chain.insert(root_32, 34, 5);
+
+        assert!(
+            chain
+                .store
+                .detect_proposer_equivocations()
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
+
+// Testing fork choice weights and head selection
+#[cfg(test)]
+mod tests_fork_choice_head {
+    use actix_web::{
+        App,
+        http::StatusCode,
+        test,
+        web::{Data, scope},
+    };
+    use alloy_primitives::B256;
+    use awc::ws::Frame;
+    use futures::StreamExt;
+    use ream_bls::BLSSignature;
+    use ream_consensus::{constants::SECONDS_PER_SLOT, fork_choice::latest_message::LatestMessage};
+    use ream_fork_choice::{
+        handlers::on_tick,
+        store::{Store, get_forkchoice_store},
+    };
+    use ream_rpc::{
+        handlers::events::{EVENT_CHANNEL_CAPACITY, get_events_ws},
+        test_utils::{TestDB, test_app},
+    };
+    use ream_storage::tables::{Field, Table};
+    use serde_json::{Value, json};
+    use tokio::sync::broadcast;
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    struct ForkChoice {
+        store: Store,
+        anchor_state: BeaconState,
+        anchor_block: BeaconBlock,
+        anchor_root: B256,
+        _test_db: TestDB,
+    }
+
+    impl ForkChoice {
+        /// Builds a store anchored at the genesis fork choice fixture, in a temporary database.
+        fn new() -> Self {
+            let case_dir = std::path::Path::new(
+                "mainnet/tests/mainnet/electra/fork_choice/get_head/pyspec_tests/genesis",
+            );
+            let anchor_state: BeaconState =
+                utils::read_ssz_snappy(&case_dir.join("anchor_state.ssz_snappy"))
+                    .expect("cannot find test asset(anchor_state.ssz_snappy)");
+            let anchor_block: BeaconBlock =
+                utils::read_ssz_snappy(&case_dir.join("anchor_block.ssz_snappy"))
+                    .expect("cannot find test asset(anchor_block.ssz_snappy)");
+            let test_db = TestDB::new().unwrap();
+            let store = get_forkchoice_store(
+                anchor_state.clone(),
+                anchor_block.clone(),
+                test_db.db.clone(),
+            )
+            .unwrap();
+            Self {
+                store,
+                _test_db: test_db,
+                anchor_root: anchor_block.tree_hash_root(),
+                anchor_state,
+                anchor_block,
+            }
+        }
+
+        /// Inserts a child of `parent_root` at `slot`, `proposer_index` tells siblings apart.
+        fn insert(&self, parent_root: B256, slot: u64, proposer_index: u64) -> B256 {
+            let mut block = self.anchor_block.clone();
+            block.slot = slot;
+            block.parent_root = parent_root;
+            block.proposer_index = proposer_index;
+            let block_root = block.tree_hash_root();
+            self.store
+                .db
+                .beacon_block_provider()
+                .insert(
+                    block_root,
+                    SignedBeaconBlock {
+                        message: block,
+                        signature: BLSSignature::default(),
+                    },
+                )
+                .unwrap();
+            self.store
+                .db
+                .beacon_state_provider()
+                .insert(block_root, self.anchor_state.clone())
+                .unwrap();
+            block_root
+        }
+
+        /// Records a latest message from each of `validator_indices` for `root`.
+        fn vote(&self, validator_indices: &[u64], root: B256) {
+            for &validator_index in validator_indices {
+                self.store
+                    .db
+                    .latest_messages_provider()
+                    .insert(validator_index, LatestMessage { epoch: 0, root })
+                    .unwrap();
+            }
+        }
+
+        fn effective_balance(&self, validator_index: u64) -> u64 {
+            self.anchor_state.validators[validator_index as usize].effective_balance
+        }
+    }
+
+    #[test]
+    fn test_equivocating_votes_are_excluded_from_weight() {
+        let fork_choice = ForkChoice::new();
+        let root_a = fork_choice.insert(fork_choice.anchor_root, 1, 1);
+        let root_b = fork_choice.insert(fork_choice.anchor_root, 1, 2);
+        fork_choice.vote(&[0, 1], root_a);
+        fork_choice.vote(&[2], root_b);
+
+        assert_eq!(
+            fork_choice.store.get_weight(root_a).unwrap(),
+            fork_choice.effective_balance(0) + fork_choice.effective_balance(1)
+        );
+        assert_eq!(fork_choice.store.get_head().unwrap(), root_a);
+
+        fork_choice
+            .store
+            .db
+            .equivocating_indices_provider()
+            .insert([0, 1].into_iter().collect())
+            .unwrap();
+
+        // The latest messages are kept but no longer count towards the weight of their root
+        assert!(
+            fork_choice
+                .store
+                .db
+                .latest_messages_provider()
+                .get(0)
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(fork_choice.store.get_weight(root_a).unwrap(), 0);
+        assert_eq!(
+            fork_choice.store.get_weight(root_b).unwrap(),
+            fork_choice.effective_balance(2)
+        );
+        assert_eq!(fork_choice.store.get_head().unwrap(), root_b);
+    }
+
+    #[test]
+    fn test_proposer_boost_wins_the_slot_and_expires() {
+        let mut fork_choice = ForkChoice::new();
+        let genesis_time = fork_choice.anchor_state.genesis_time;
+        on_tick(&mut fork_choice.store, genesis_time + SECONDS_PER_SLOT).unwrap();
+        let root_a = fork_choice.insert(fork_choice.anchor_root, 1, 1);
+        let root_b = fork_choice.insert(fork_choice.anchor_root, 1, 2);
+        // Without a boost the tie is broken in favour of the larger root, so boost the smaller one
+        let (boosted_root, competitor_root) = if root_a < root_b {
+            (root_a, root_b)
+        } else {
+            (root_b, root_a)
+        };
+        fork_choice.vote(&[0], boosted_root);
+        fork_choice.vote(&[1], competitor_root);
+        assert_eq!(
+            fork_choice.store.get_weight(boosted_root).unwrap(),
+            fork_choice.store.get_weight(competitor_root).unwrap()
+        );
+        assert_eq!(fork_choice.store.get_head().unwrap(), competitor_root);
+
+        fork_choice
+            .store
+            .db
+            .proposer_boost_root_provider()
+            .insert(boosted_root)
+            .unwrap();
+        assert_eq!(
+            fork_choice.store.get_weight(boosted_root).unwrap(),
+            fork_choice.effective_balance(0) + fork_choice.store.get_proposer_score().unwrap()
+        );
+        assert_eq!(fork_choice.store.get_head().unwrap(), boosted_root);
+
+        // The boost only applies within the slot of the boosted block
+        on_tick(&mut fork_choice.store, genesis_time + 2 * SECONDS_PER_SLOT).unwrap();
+        assert_eq!(fork_choice.store.get_proposer_boost_root().unwrap(), None);
+        assert_eq!(fork_choice.store.get_head().unwrap(), competitor_root);
+    }
+
+    #[test]
+    fn test_get_head_follows_the_heavier_branch_and_breaks_ties_by_root() {
+        let fork_choice = ForkChoice::new();
+        let root_a1 = fork_choice.insert(fork_choice.anchor_root, 1, 1);
+        let root_a2 = fork_choice.insert(root_a1, 2, 1);
+        let root_b1 = fork_choice.insert(fork_choice.anchor_root, 1, 2);
+
+        // Votes for a descendant count towards every block of its branch
+        fork_choice.vote(&[0, 1], root_a2);
+        fork_choice.vote(&[2], root_b1);
+        assert_eq!(
+            fork_choice.store.get_weight(root_a1).unwrap(),
+            fork_choice.store.get_weight(root_a2).unwrap()
+        );
+        assert_eq!(fork_choice.store.get_head().unwrap(), root_a2);
+
+        fork_choice.vote(&[3, 4], root_b1);
+        assert_eq!(fork_choice.store.get_head().unwrap(), root_b1);
+
+        // With equal weights the branch with the lexicographically larger root wins
+        fork_choice.vote(&[5], root_a2);
+        assert_eq!(
+            fork_choice.store.get_weight(root_a1).unwrap(),
+            fork_choice.store.get_weight(root_b1).unwrap()
+        );
+        let expected_head = if root_a1 > root_b1 { root_a2 } else { root_b1 };
+        assert_eq!(fork_choice.store.get_head().unwrap(), expected_head);
+    }
+
+    #[actix_web::test]
+    async fn test_headers_report_canonical_flag() {
+        let fork_choice = ForkChoice::new();
+        let orphan_root = fork_choice.insert(fork_choice.anchor_root, 1, 2);
+        let head_root = fork_choice.insert(fork_choice.anchor_root, 1, 1);
+        fork_choice.vote(&[0, 1], head_root);
+        fork_choice.vote(&[2], orphan_root);
+        assert_eq!(fork_choice.store.get_head().unwrap(), head_root);
+
+        let header_data = |root: B256, canonical: bool| {
+            let block = fork_choice
+                .store
+                .db
+                .beacon_block_provider()
+                .get(root)
+                .unwrap()
+                .unwrap();
+            json!({
+                "root": root,
+                "canonical": canonical,
+                "header": block.signed_header(),
+            })
+        };
+        let anchor_root = fork_choice.anchor_root;
+        let app = test::init_service(test_app(fork_choice.store.db.clone())).await;
+        for (uri, expected_data) in [
+            // By id, including a block that is not on the canonical chain
+            (
+                "/eth/v1/beacon/headers/head".to_string(),
+                header_data(head_root, true),
+            ),
+            (
+                format!("/eth/v1/beacon/headers/{orphan_root:?}"),
+                header_data(orphan_root, false),
+            ),
+            (
+                format!("/eth/v1/beacon/headers/{anchor_root:?}"),
+                header_data(anchor_root, true),
+            ),
+            // By slot, the block of the slot index
+            (
+                "/eth/v1/beacon/headers?slot=1".to_string(),
+                json!([header_data(head_root, true)]),
+            ),
+        ] {
+            let request = test::TestRequest::get().uri(&uri).to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK, "{uri}");
+            let body: Value = test::read_body_json(response).await;
+            assert_eq!(body["data"], expected_data, "{uri}");
+        }
+
+        // By parent root, every child is listed with its own flag
+        let request = test::TestRequest::get()
+            .uri(&format!(
+                "/eth/v1/beacon/headers?parent_root={anchor_root:?}"
+            ))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(response).await;
+        let mut headers = body["data"].as_array().unwrap().clone();
+        headers.sort_by_key(|header| header["canonical"].as_bool());
+        assert_eq!(
+            headers,
+            vec![
+                header_data(orphan_root, false),
+                header_data(head_root, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_proposer_boost_is_reset_at_the_next_slot() {
+        let mut fork_choice = ForkChoice::new();
+        let genesis_time = fork_choice.anchor_state.genesis_time;
+        fork_choice
+            .store
+            .on_tick_per_slot(genesis_time + SECONDS_PER_SLOT)
+            .unwrap();
+        let root_a = fork_choice.insert(fork_choice.anchor_root, 1, 1);
+        let root_b = fork_choice.insert(fork_choice.anchor_root, 1, 2);
+        let (boosted_root, competitor_root) = if root_a < root_b {
+            (root_a, root_b)
+        } else {
+            (root_b, root_a)
+        };
+        fork_choice.vote(&[0], boosted_root);
+        fork_choice.vote(&[1], competitor_root);
+        let attestation_weight = fork_choice.store.get_weight(boosted_root).unwrap();
+        let proposer_score = fork_choice.store.get_proposer_score().unwrap();
+        assert!(proposer_score > 0);
+
+        fork_choice
+            .store
+            .db
+            .proposer_boost_root_provider()
+            .insert(boosted_root)
+            .unwrap();
+        // Later ticks within the slot keep the boost
+        fork_choice
+            .store
+            .on_tick_per_slot(genesis_time + SECONDS_PER_SLOT + 1)
+            .unwrap();
+        assert_eq!(
+            fork_choice.store.get_proposer_boost_root().unwrap(),
+            Some(boosted_root)
+        );
+        assert_eq!(
+            fork_choice.store.get_weight(boosted_root).unwrap(),
+            attestation_weight + proposer_score
+        );
+        assert_eq!(fork_choice.store.get_head().unwrap(), boosted_root);
+
+        fork_choice
+            .store
+            .on_tick_per_slot(genesis_time + 2 * SECONDS_PER_SLOT)
+            .unwrap();
+        assert_eq!(
+            fork_choice
+                .store
+                .db
+                .proposer_boost_root_provider()
+                .get()
+                .unwrap(),
+            B256::ZERO
+        );
+        assert_eq!(fork_choice.store.get_proposer_boost_root().unwrap(), None);
+        // Without the boost the tie between the branches goes to the larger root
+        assert_eq!(
+            fork_choice.store.get_weight(boosted_root).unwrap(),
+            attestation_weight
+        );
+        assert_eq!(fork_choice.store.get_head().unwrap(), competitor_root);
+    }
+
+    #[actix_web::test]
+    async fn test_head_update_streams_reorg_to_event_subscribers() {
+        let fork_choice = ForkChoice::new();
+        let root_a1 = fork_choice.insert(fork_choice.anchor_root, 1, 1);
+        let root_a2 = fork_choice.insert(root_a1, 2, 1);
+        let root_b1 = fork_choice.insert(fork_choice.anchor_root, 1, 2);
+        let (event_sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+
+        fork_choice.vote(&[0], root_a2);
+        let head = fork_choice.store.update_head(None, &event_sender).unwrap();
+        assert_eq!(head, root_a2);
+
+        let server = actix_test::start({
+            let event_sender = event_sender.clone();
+            move || {
+                App::new()
+                    .app_data(Data::new(event_sender.clone()))
+                    .service(scope("/ws").service(get_events_ws))
+            }
+        });
+        let mut framed = server.ws_at("/ws/events?topics=chain_reorg").await.unwrap();
+
+        // Extending the head is no reorg, moving the votes to the other branch is
+        let root_a3 = fork_choice.insert(root_a2, 3, 1);
+        fork_choice.vote(&[0], root_a3);
+        let head = fork_choice
+            .store
+            .update_head(Some(head), &event_sender)
+            .unwrap();
+        assert_eq!(head, root_a3);
+        fork_choice.vote(&[0, 1, 2], root_b1);
+        let head = fork_choice
+            .store
+            .update_head(Some(head), &event_sender)
+            .unwrap();
+        assert_eq!(head, root_b1);
+        // The slot index follows the head onto the other branch
+        let slot_index = fork_choice.store.db.slot_index_provider();
+        assert_eq!(slot_index.get(1).unwrap(), Some(root_b1));
+        assert_eq!(slot_index.get(2).unwrap(), None);
+        assert_eq!(slot_index.get(3).unwrap(), None);
+
+        // Skip the heartbeat pings sent before the event
+        let text = loop {
+            match framed.next().await.unwrap().unwrap() {
+                Frame::Text(text) => break text,
+                Frame::Ping(_) => {}
+                frame => panic!("Unexpected frame {frame:?}"),
+            }
+        };
+        let state_root = fork_choice.anchor_block.state_root;
+        assert_eq!(
+            serde_json::from_slice::<Value>(&text).unwrap(),
+            json!({
+                "event": "chain_reorg",
+                "data": {
+                    "slot": "1",
+                    "depth": "3",
+                    "old_head_block": root_a3,
+                    "new_head_block": root_b1,
+                    "old_head_state": state_root,
+                    "new_head_state": state_root,
+                    "epoch": "0",
+                    "execution_optimistic": false,
+                },
+            })
+        );
+    }
+}
+
+// Testing the validator identities endpoint
+#[cfg(test)]
+mod tests_validator_identities {
+    use actix_web::{http::StatusCode, test};
+    use ream_beacon_api_types::id::ValidatorID;
+    use ream_rpc::test_utils::{TestDB, test_app};
+    use serde_json::{Value, json};
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_out_of_range_indices_are_omitted() {
+        let state: BeaconState = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/random/random/pyspec_tests/randomized_0/pre.ssz_snappy",
+        ))
+        .expect("cannot find test asset (pre.ssz_snappy)");
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = state.slot;
+        block.message.state_root = state.tree_hash_root();
+        let test_db = TestDB::new().unwrap();
+        let block_root = test_db.insert_block(block).unwrap();
+        test_db.insert_state(block_root, state.clone()).unwrap();
+
+        let validator_count = state.validators.len() as u64;
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+        let request = test::TestRequest::post()
+            .uri(&format!(
+                "/eth/v1/beacon/states/{}/validator_identities",
+                state.slot
+            ))
+            .set_json([
+                ValidatorID::Index(2),
+                ValidatorID::Index(validator_count),
+                ValidatorID::Address(state.validators[0].public_key.clone()),
+                ValidatorID::Index(validator_count + 5),
+                ValidatorID::Index(2),
+            ])
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: Value = test::read_body_json(response).await;
+        let identity = |index: usize| {
+            let validator = &state.validators[index];
+            json!({
+                "index": index.to_string(),
+                "pubkey": validator.public_key,
+                "activation_epoch": validator.activation_epoch.to_string(),
+            })
+        };
+        assert_eq!(body["data"], json!([identity(0), identity(2)]));
+    }
+}
+// Testing validator lookups by state and validator id
+#[cfg(test)]
+mod tests_state_validator {
+    use actix_web::{http::StatusCode, test};
+    use ream_rpc::test_utils::{TestDB, test_app};
+    use serde_json::Value;
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    fn stored_state() -> (TestDB, BeaconState) {
+        let state = read_state();
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = state.slot;
+        block.message.state_root = state.tree_hash_root();
+
+        let test_db = TestDB::new().unwrap();
+        let block_root = test_db.insert_block(block).unwrap();
+        test_db.insert_state(block_root, state.clone()).unwrap();
+        (test_db, state)
+    }
+
+    #[actix_web::test]
+    async fn test_validator_by_index_and_public_key() {
+        let (test_db, state) = stored_state();
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+
+        let public_key = serde_json::to_value(&state.validators[3].public_key).unwrap();
+        let public_key = public_key.as_str().unwrap();
+        for validator_id in ["3", public_key] {
+            let request = test::TestRequest::get()
+                .uri(&format!(
+                    "/eth/v1/beacon/states/{}/validators/{validator_id}",
+                    state.slot
+                ))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK, "{validator_id}");
+
+            let body: Value = test::read_body_json(response).await;
+            assert_eq!(body["data"]["index"], "3", "{validator_id}");
+            assert_eq!(body["data"]["validator"]["pubkey"], public_key);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_unknown_public_key_is_not_found() {
+        let (test_db, state) = stored_state();
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+
+        let request = test::TestRequest::get()
+            .uri(&format!(
+                "/eth/v1/beacon/states/{}/validators/0x{}",
+                state.slot,
+                "ab".repeat(48)
+            ))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_malformed_public_key_is_bad_request() {
+        let (test_db, state) = stored_state();
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+
+        let request = test::TestRequest::get()
+            .uri(&format!(
+                "/eth/v1/beacon/states/{}/validators/0xnothex",
+                state.slot
+            ))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_validator_matches_bulk_entry_and_out_of_range_index_is_not_found() {
+        let (test_db, state) = stored_state();
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+
+        let uri = format!("/eth/v1/beacon/states/{}/validators", state.slot);
+        let request = test::TestRequest::get().uri(&uri).to_request();
+        let validators: Value = test::call_and_read_body_json(&app, request).await;
+        let request = test::TestRequest::get()
+            .uri(&format!("{uri}/3"))
+            .to_request();
+        let validator: Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(validator["data"], validators["data"][3]);
+
+        let request = test::TestRequest::get()
+            .uri(&format!("{uri}/{}", state.validators.len()))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
+
+// Testing block header extraction
+#[cfg(test)]
+mod tests_signed_header {
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    #[test]
+    fn test_signed_header_root_matches_block_root() {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0",
+        );
+        let block: SignedBeaconBlock =
+            utils::read_ssz_snappy(&case_dir.join("serialized.ssz_snappy"))
+                .expect("cannot find test asset(serialized.ssz_snappy)");
+
+        let signed_header = block.signed_header();
+        assert_eq!(
+            signed_header.message.tree_hash_root(),
+            block.message.tree_hash_root()
+        );
+        assert_eq!(signed_header.message, block.message.header());
+        assert_eq!(signed_header.signature, block.signature);
+    }
+}
+
+#[cfg(test)]
+mod tests_debug_state {
+    use actix_web::{body::to_bytes, test::TestRequest};
+    use ream_beacon_api_types::responses::{
+        ETH_CONSENSUS_VERSION_HEADER, JSON_CONTENT_TYPE, SSZ_CONTENT_TYPE,
+    };
+    use ream_consensus::constants::SLOTS_PER_EPOCH;
+    use ream_network_spec::networks::MAINNET;
+    use ream_rpc::{handlers::debug::debug_state_response, test_utils::init_network_spec};
+    use ssz::Decode;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_debug_state_ssz_round_trip() {
+        init_network_spec();
+        let state_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/BeaconState/ssz_random/case_0",
+        );
+        let state: BeaconState = utils::read_ssz_snappy(&state_dir.join("serialized.ssz_snappy"))
+            .expect("cannot find test asset(serialized.ssz_snappy)");
+
+        let http_request = TestRequest::default()
+            .insert_header(("accept", SSZ_CONTENT_TYPE))
+            .to_http_request();
+        let response = debug_state_response(&http_request, &state);
+        assert_eq!(
+            response
+                .headers()
+                .get(ETH_CONSENSUS_VERSION_HEADER)
+                .unwrap(),
+            MAINNET.fork_name_at_slot(state.slot).as_str()
+        );
+
+        let body = to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(BeaconState::from_ssz_bytes(&body).unwrap(), state);
+    }
+
+    #[tokio::test]
+    async fn test_debug_state_version_follows_the_state_slot() {
+        init_network_spec();
+        let state_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/BeaconState/ssz_random/case_0",
+        );
+        let mut state: BeaconState =
+            utils::read_ssz_snappy(&state_dir.join("serialized.ssz_snappy"))
+                .expect("cannot find test asset(serialized.ssz_snappy)");
+        state.slot = MAINNET.deneb_fork_epoch * SLOTS_PER_EPOCH;
+
+        for accept in [SSZ_CONTENT_TYPE, JSON_CONTENT_TYPE] {
+            let http_request = TestRequest::default()
+                .insert_header(("accept", accept))
+                .to_http_request();
+            let response = debug_state_response(&http_request, &state);
+            assert_eq!(
+                response
+                    .headers()
+                    .get(ETH_CONSENSUS_VERSION_HEADER)
+                    .unwrap(),
+                "deneb"
+            );
+        }
+
+        let http_request = TestRequest::default().to_http_request();
+        let body = to_bytes(debug_state_response(&http_request, &state).into_body())
+            .await
+            .unwrap();
+        let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response["version"], "deneb");
+    }
+}
+
+#[cfg(test)]
+mod tests_debug_heads {
+    use ream_bls::BLSSignature;
+    use ream_consensus::fork_choice::latest_message::LatestMessage;
+    use ream_fork_choice::store::get_forkchoice_store;
+    use ream_rpc::{handlers::debug::get_debug_heads_from_store, test_utils::TestDB};
+    use ream_storage::tables::Table;
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    #[test]
+    fn test_debug_heads_report_branch_weight() {
+        let case_dir = std::path::Path::new(
+            "mainnet/tests/mainnet/electra/fork_choice/get_head/pyspec_tests/genesis",
+        );
+        let anchor_state: BeaconState =
+            utils::read_ssz_snappy(&case_dir.join("anchor_state.ssz_snappy"))
+                .expect("cannot find test asset(anchor_state.ssz_snappy)");
+        let anchor_block: BeaconBlock =
+            utils::read_ssz_snappy(&case_dir.join("anchor_block.ssz_snappy"))
+                .expect("cannot find test asset(anchor_block.ssz_snappy)");
+        let anchor_root = anchor_block.tree_hash_root();
+        let test_db = TestDB::new().unwrap();
+        let store = get_forkchoice_store(
+            anchor_state.clone(),
+            anchor_block.clone(),
+            test_db.db.clone(),
+        )
+        .unwrap();
+
+        // Two children of the anchor block, differing only in their proposer
+        let [heavy_root, light_root] = [1, 2].map(|proposer_index| {
+            let mut block = anchor_block.clone();
+            block.slot += 1;
+            block.parent_root = anchor_root;
+            block.proposer_index = proposer_index;
+            let block_root = block.tree_hash_root();
+            store
+                .db
+                .beacon_block_provider()
+                .insert(
+                    block_root,
+                    SignedBeaconBlock {
+                        message: block,
+                        signature: BLSSignature::default(),
+                    },
+                )
+                .unwrap();
+            store
+                .db
+                .beacon_state_provider()
+                .insert(block_root, anchor_state.clone())
+                .unwrap();
+            block_root
+        });
+
+        for (validator_index, root) in [
+            (0, heavy_root),
+            (1, heavy_root),
+            (2, heavy_root),
+            (3, light_root),
+        ] {
+            store
+                .db
+                .latest_messages_provider()
+                .insert(validator_index, LatestMessage { epoch: 0, root })
+                .unwrap();
+        }
+
+        let debug_heads = get_debug_heads_from_store(&store).unwrap();
+        assert_eq!(debug_heads.justified_checkpoint.root, anchor_root);
+        assert_eq!(debug_heads.finalized_checkpoint.root, anchor_root);
+        assert_eq!(debug_heads.heads.len(), 2);
+        assert_eq!(debug_heads.heads[0].root, heavy_root);
+        assert_eq!(debug_heads.heads[1].root, light_root);
+        assert!(debug_heads.heads[0].weight > debug_heads.heads[1].weight);
+        assert_eq!(debug_heads.heads[0].weight, 3 * debug_heads.heads[1].weight);
+    }
+}
+
+// Testing the validator count mode of the validators endpoint
+#[cfg(test)]
+mod tests_validators_count {
+    use actix_web::{http::StatusCode, test};
+    use ream_beacon_api_types::{
+        responses::{BeaconResponse, ValidatorCountResponse},
+        validator::ValidatorData,
+    };
+    use ream_rpc::test_utils::{TestDB, test_app};
+    use tree_hash::TreeHash;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_count_matches_full_response() {
+        let state = read_state();
+        let mut block: SignedBeaconBlock = utils::read_ssz_snappy(std::path::Path::new(
+            "mainnet/tests/mainnet/electra/ssz_static/SignedBeaconBlock/ssz_random/case_0/serialized.ssz_snappy",
+        ))
+        .expect("cannot find test asset (serialized.ssz_snappy)");
+        block.message.slot = state.slot;
+        block.message.state_root = state.tree_hash_root();
+
+        let test_db = TestDB::new().unwrap();
+        let block_root = test_db.insert_block(block).unwrap();
+        test_db.insert_state(block_root, state.clone()).unwrap();
+        let app = test::init_service(test_app(test_db.db.clone())).await;
+
+        let uri = format!("/eth/v1/beacon/states/{}/validators", state.slot);
+        let request = test::TestRequest::get().uri(&uri).to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let validators: BeaconResponse<Vec<ValidatorData>> = test::read_body_json(response).await;
+        assert_eq!(validators.data.len(), state.validators.len());
+
+        // Pagination does not apply to the count
+        for query in ["count_only=true", "count_only=true&page=1&page_size=1"] {
+            let request = test::TestRequest::get()
+                .uri(&format!("{uri}?{query}"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK, "{query}");
+
+            let body = test::read_body(response).await;
+            let count: BeaconResponse<ValidatorCountResponse> =
+                serde_json::from_slice(&body).unwrap();
+            assert_eq!(count.data.count, validators.data.len() as u64, "{query}");
+            assert_eq!(
+                String::from_utf8(body.to_vec()).unwrap(),
+                format!("{{\"data\":{{\"count\":\"{}\"}}}}", validators.data.len())
+            );
+        }
+    }
+}